@@ -9,6 +9,8 @@ mod link;
 use fnv::FnvHashMap;
 use std::ops::BitOr;
 
+use rendy_resource::SharingMode;
+
 pub use self::link::{Link, LinkNode};
 use resource::{Buffer, Image, Resource};
 use Id;
@@ -18,6 +20,7 @@ use Id;
 #[derive(Clone, Debug)]
 pub struct Chain<R: Resource> {
     links: Vec<Link<R>>,
+    sharing: SharingMode,
 }
 
 impl<R> Chain<R>
@@ -31,7 +34,22 @@ where
 
     /// Create new empty `Chain`
     pub fn new() -> Self {
-        Chain { links: Vec::new() }
+        Chain {
+            links: Vec::new(),
+            sharing: SharingMode::Exclusive,
+        }
+    }
+
+    /// Get sharing mode of resources of the chain.
+    pub fn sharing(&self) -> SharingMode {
+        self.sharing
+    }
+
+    /// Set sharing mode of resources of the chain.
+    /// No ownership transfers are generated between families
+    /// that share resources concurrently.
+    pub fn set_sharing(&mut self, sharing: SharingMode) {
+        self.sharing = sharing;
     }
 
     /// Get links slice
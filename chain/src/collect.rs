@@ -1,8 +1,10 @@
 use std::cmp::max;
+use std::collections::HashMap;
 use std::hash::Hash;
 use std::ops::Range;
 
 use fnv::FnvHashMap;
+use rendy_resource::SharingMode;
 
 use chain::{BufferChains, Chain, ImageChains, Link, LinkNode};
 use node::{Node, State};
@@ -62,6 +64,8 @@ struct ResolvedNodeSet {
     queues: Vec<QueueId>,
     buffers: Vec<Id>,
     images: Vec<Id>,
+    buffer_sharing: Vec<SharingMode>,
+    image_sharing: Vec<SharingMode>,
 }
 
 struct ChainData<R: Resource> {
@@ -88,6 +92,12 @@ struct QueueData {
 
 /// Calculate automatic `Chains` for nodes.
 /// This function tries to find most appropriate schedule for nodes execution.
+///
+/// # Panics
+///
+/// Panics if nodes have dependency loop,
+/// if `max_queues` returns 0 for family of any node
+/// or if nodes accessing the same resource disagree on its sharing mode.
 pub fn collect<Q>(nodes: Vec<Node>, max_queues: Q) -> Chains
 where
     Q: Fn(FamilyId) -> usize,
@@ -99,6 +109,12 @@ where
     // Chains.
     let mut images: Vec<ChainData<Image>> = fill(nodes.images.len());
     let mut buffers: Vec<ChainData<Buffer>> = fill(nodes.buffers.len());
+    for (data, &sharing) in images.iter_mut().zip(&nodes.image_sharing) {
+        data.chain.set_sharing(sharing);
+    }
+    for (data, &sharing) in buffers.iter_mut().zip(&nodes.buffer_sharing) {
+        data.chain.set_sharing(sharing);
+    }
 
     // Schedule
     let mut schedule = Vec::with_capacity(nodes.queues.len());
@@ -215,6 +231,8 @@ where
     let mut queues = LookupBuilder::new();
     let mut buffers = LookupBuilder::new();
     let mut images = LookupBuilder::new();
+    let mut buffer_sharing = Vec::new();
+    let mut image_sharing = Vec::new();
 
     let mut family_full = FnvHashMap::default();
     for node in nodes {
@@ -246,16 +264,23 @@ where
         reified_nodes[id].id = id;
         reified_nodes[id].family = node.family;
         reified_nodes[id].queues = family_full[&family].clone();
+        let ref node_buffer_sharing = node.buffer_sharing;
         reified_nodes[id].buffers = node
             .buffers
             .into_iter()
-            .map(|(k, v)| (buffers.forward(k), v))
-            .collect();
+            .map(|(k, v)| {
+                let index =
+                    resolve_sharing(&mut buffers, &mut buffer_sharing, k, node_buffer_sharing);
+                (index, v)
+            }).collect();
+        let ref node_image_sharing = node.image_sharing;
         reified_nodes[id].images = node
             .images
             .into_iter()
-            .map(|(k, v)| (images.forward(k), v))
-            .collect();
+            .map(|(k, v)| {
+                let index = resolve_sharing(&mut images, &mut image_sharing, k, node_image_sharing);
+                (index, v)
+            }).collect();
     }
 
     (
@@ -264,11 +289,38 @@ where
             queues: queues.backward,
             buffers: buffers.backward,
             images: images.backward,
+            buffer_sharing,
+            image_sharing,
         },
         unscheduled_nodes,
     )
 }
 
+/// Resolve resource id and record its sharing mode.
+/// All nodes accessing the resource must agree on the sharing mode.
+fn resolve_sharing(
+    ids: &mut LookupBuilder<Id>,
+    sharing: &mut Vec<SharingMode>,
+    id: Id,
+    node_sharing: &HashMap<Id, SharingMode>,
+) -> usize {
+    let index = ids.forward(id);
+    let mode = node_sharing
+        .get(&id)
+        .cloned()
+        .unwrap_or(SharingMode::Exclusive);
+    if index == sharing.len() {
+        sharing.push(mode);
+    } else {
+        assert_eq!(
+            sharing[index], mode,
+            "Nodes disagree on sharing mode of resource {:?}",
+            id
+        );
+    }
+    index
+}
+
 fn reify_chain<R: Resource>(ids: &[Id], vec: Vec<ChainData<R>>) -> FnvHashMap<Id, Chain<R>> {
     let mut map = FnvHashMap::with_capacity_and_hasher(vec.len(), Default::default());
     for (chain, &i) in vec.into_iter().zip(ids) {
@@ -297,20 +349,24 @@ fn fitness(
     // Collect minimal waits required and resource transfers count.
     for &(id, _) in &node.buffers {
         let chain = &buffers[id];
-        if chain
-            .current_family
-            .map_or(false, |family| family != node.family)
-        {
+        if chain.current_family.map_or(false, |family| {
+            chain
+                .chain
+                .sharing()
+                .transfer_required(family.0, node.family.0)
+        }) {
             transfers += 1;
         }
         wait_factor_from_chains = max(wait_factor_from_chains, chain.last_link_wait_factor);
     }
     for &(id, _) in &node.images {
         let chain = &images[id];
-        if chain
-            .current_family
-            .map_or(false, |family| family != node.family)
-        {
+        if chain.current_family.map_or(false, |family| {
+            chain
+                .chain
+                .sharing()
+                .transfer_required(family.0, node.family.0)
+        }) {
             transfers += 1;
         }
         wait_factor_from_chains = max(wait_factor_from_chains, chain.last_link_wait_factor);
@@ -46,6 +46,9 @@ mod stage;
 /// ???
 mod sync;
 
+#[cfg(test)]
+mod test;
+
 pub use chain::Chain;
 pub use node::{Node, State};
 pub use resource::{Buffer, Image, Resource};
@@ -1,5 +1,7 @@
 use std::collections::hash_map::{HashMap, Iter as HashMapIter};
 
+use rendy_resource::SharingMode;
+
 use access::AccessFlags;
 use resource::{Buffer, Image, Resource};
 use schedule::FamilyId;
@@ -40,9 +42,48 @@ pub struct Node {
 
     /// Image category ids and required state.
     pub images: HashMap<Id, State<Image>>,
+
+    /// Sharing mode of buffers the node accesses.
+    /// Buffers not listed here are exclusive.
+    /// All nodes accessing the buffer must agree on its sharing mode.
+    pub buffer_sharing: HashMap<Id, SharingMode>,
+
+    /// Sharing mode of images the node accesses.
+    /// Images not listed here are exclusive.
+    /// All nodes accessing the image must agree on its sharing mode.
+    pub image_sharing: HashMap<Id, SharingMode>,
 }
 
 impl Node {
+    /// Create node that accesses all resources exclusively.
+    pub fn new(
+        id: usize,
+        family: FamilyId,
+        dependencies: Vec<usize>,
+        buffers: HashMap<Id, State<Buffer>>,
+        images: HashMap<Id, State<Image>>,
+    ) -> Self {
+        Node {
+            id,
+            family,
+            dependencies,
+            buffers,
+            images,
+            buffer_sharing: HashMap::new(),
+            image_sharing: HashMap::new(),
+        }
+    }
+
+    /// Set sharing mode of the buffer.
+    pub fn set_buffer_sharing(&mut self, id: Id, sharing: SharingMode) {
+        self.buffer_sharing.insert(id, sharing);
+    }
+
+    /// Set sharing mode of the image.
+    pub fn set_image_sharing(&mut self, id: Id, sharing: SharingMode) {
+        self.image_sharing.insert(id, sharing);
+    }
+
     /// Get family on which this node will be executed.
     pub fn family(&self) -> FamilyId {
         self.family
@@ -62,4 +103,20 @@ impl Node {
     pub fn images(&self) -> HashMapIter<'_, Id, State<Image>> {
         self.images.iter()
     }
+
+    /// Get sharing mode of the buffer.
+    pub fn buffer_sharing(&self, id: Id) -> SharingMode {
+        self.buffer_sharing
+            .get(&id)
+            .cloned()
+            .unwrap_or(SharingMode::Exclusive)
+    }
+
+    /// Get sharing mode of the image.
+    pub fn image_sharing(&self, id: Id) -> SharingMode {
+        self.image_sharing
+            .get(&id)
+            .cloned()
+            .unwrap_or(SharingMode::Exclusive)
+    }
 }
@@ -367,6 +367,20 @@ fn generate_semaphore_pair<R: Resource>(
     }
 }
 
+/// Generate semaphores from `sid` to the first submissions of the `link`
+/// on queues other than the one of `sid`.
+fn generate_semaphores_from<R: Resource>(
+    sync: &mut SyncTemp,
+    id: Id,
+    link: &Link<R>,
+    sid: SubmissionId,
+) {
+    for (queue_id, queue) in link.queues() {
+        let head = SubmissionId::new(queue_id, queue.first);
+        generate_semaphore_pair(sync, id, link, sid..head);
+    }
+}
+
 fn sync_chain<R, S>(id: Id, chain: &Chain<R>, schedule: &Schedule<S>, sync: &mut SyncTemp)
 where
     R: Resource,
@@ -385,10 +399,7 @@ where
                     .insert(id, Barrier::new(prev_link.state()..link.state()));
 
                 // Generate semaphores between queues in the previous link and the current one.
-                for (queue_id, queue) in link.queues() {
-                    let head = SubmissionId::new(queue_id, queue.first);
-                    generate_semaphore_pair(sync, uid, link, signal_sid..head);
-                }
+                generate_semaphores_from(sync, uid, link, signal_sid);
             } else {
                 let wait_sid = earliest(link, schedule);
 
@@ -404,16 +415,38 @@ where
                     .pick()
                     .insert(id, Barrier::new(prev_link.state()..link.state()));
 
-                if !link.single_queue() {
-                    unimplemented!("This case is unimplemented");
-                }
+                // Other queues of the link wait for the barrier.
+                generate_semaphores_from(sync, uid, link, wait_sid);
+            }
+        } else if !chain
+            .sharing()
+            .transfer_required(prev_link.family().0, link.family().0)
+        {
+            // Resource is shared concurrently so no ownership transfer is required.
+            let wait_sid = earliest(link, schedule);
+
+            // Generate semaphores between queues in the previous link and the current one.
+            for (queue_id, queue) in prev_link.queues() {
+                let tail = SubmissionId::new(queue_id, queue.last);
+                generate_semaphore_pair(sync, uid, link, tail..wait_sid);
             }
+
+            // Generate barrier in next link's first submission.
+            sync.get_sync(wait_sid)
+                .acquire
+                .pick()
+                .insert(id, Barrier::new(prev_link.state()..link.state()));
+
+            // Other queues of the link wait for the barrier.
+            generate_semaphores_from(sync, uid, link, wait_sid);
         } else {
             let signal_sid = latest(prev_link, schedule);
             let wait_sid = earliest(link, schedule);
 
-            if !prev_link.single_queue() {
-                unimplemented!("This case is unimplemented");
+            // Release waits for other queues of the previous link.
+            for (queue_id, queue) in prev_link.queues() {
+                let tail = SubmissionId::new(queue_id, queue.last);
+                generate_semaphore_pair(sync, uid, prev_link, tail..signal_sid);
             }
 
             // Generate a semaphore between the signal and wait sides of the transfer.
@@ -437,9 +470,8 @@ where
                 ),
             );
 
-            if !link.single_queue() {
-                unimplemented!("This case is unimplemented");
-            }
+            // Other queues of the link wait for the acquire.
+            generate_semaphores_from(sync, uid, link, wait_sid);
         }
     }
 }
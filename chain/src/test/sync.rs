@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+
+use rendy_resource::{buffer, Families, SharingMode};
+
+use access::AccessFlags;
+use collect::collect;
+use node::{Node, State};
+use resource::Buffer;
+use schedule::FamilyId;
+use stage::PipelineStageFlags;
+use sync::{sync, SyncData};
+use Id;
+
+/// Node that accesses single buffer on specified family.
+fn node(
+    id: usize,
+    family: u32,
+    dependencies: Vec<usize>,
+    state: State<Buffer>,
+    sharing: SharingMode,
+) -> Node {
+    let mut buffers = HashMap::new();
+    buffers.insert(Id(0), state);
+    let mut node = Node::new(id, FamilyId(family), dependencies, buffers, HashMap::new());
+    node.set_buffer_sharing(Id(0), sharing);
+    node
+}
+
+fn write() -> State<Buffer> {
+    State {
+        access: AccessFlags::TRANSFER_WRITE,
+        layout: (),
+        stages: PipelineStageFlags::TRANSFER,
+        usage: buffer::UsageFlags::TRANSFER_DST,
+    }
+}
+
+fn read() -> State<Buffer> {
+    State {
+        access: AccessFlags::SHADER_READ,
+        layout: (),
+        stages: PipelineStageFlags::COMPUTE_SHADER,
+        usage: buffer::UsageFlags::STORAGE_BUFFER,
+    }
+}
+
+/// Buffer written on family 0 and then read on family 1.
+/// Returns number of ownership transfer barriers.
+fn transfers(sharing: SharingMode) -> usize {
+    let nodes = vec![
+        node(0, 0, Vec::new(), write(), sharing),
+        node(1, 1, vec![0], read(), sharing),
+    ];
+
+    let chains = collect(nodes, |_| 1);
+    assert_eq!(chains.buffers[&Id(0)].sharing(), sharing);
+
+    let schedule = sync(&chains, || ((), ()));
+    schedule
+        .iter()
+        .flat_map(|family| family.iter())
+        .flat_map(|queue| queue.iter())
+        .map(|submission| {
+            let sync: &SyncData<(), ()> = submission.sync();
+            sync.acquire
+                .buffers
+                .values()
+                .chain(sync.release.buffers.values())
+                .filter(|barrier| barrier.queues.is_some())
+                .count()
+        }).sum()
+}
+
+#[test]
+fn exclusive_transfer_test() {
+    // Release on family 0 and acquire on family 1.
+    assert_eq!(transfers(SharingMode::Exclusive), 2);
+}
+
+#[test]
+fn concurrent_transfer_test() {
+    let families = vec![0, 1].into_iter().collect::<Families>();
+    assert_eq!(transfers(SharingMode::Concurrent(families)), 0);
+
+    // Families outside of the set still require ownership transfer.
+    let families = vec![0, 2].into_iter().collect::<Families>();
+    assert_eq!(transfers(SharingMode::Concurrent(families)), 2);
+}
+
+#[test]
+fn multi_queue_link_test() {
+    let families = vec![0, 1].into_iter().collect::<Families>();
+    for sharing in vec![SharingMode::Exclusive, SharingMode::Concurrent(families)] {
+        // Buffer written on family 0 and then read on both queues of family 1.
+        let nodes = vec![
+            node(0, 0, Vec::new(), write(), sharing),
+            node(1, 1, vec![0], read(), sharing),
+            node(2, 1, vec![0], read(), sharing),
+        ];
+        let chains = collect(nodes, |family| if family == FamilyId(1) { 2 } else { 1 });
+        let links = chains.buffers[&Id(0)].links();
+        assert_eq!(links.len(), 2);
+        assert_eq!(links[1].queues().count(), 2);
+
+        // Both readers wait for the writer.
+        let schedule = sync(&chains, || ((), ()));
+        let waits = schedule
+            .iter()
+            .flat_map(|family| family.iter())
+            .flat_map(|queue| queue.iter())
+            .map(|submission| {
+                let sync: &SyncData<(), ()> = submission.sync();
+                (submission.node(), sync.wait.len())
+            }).collect::<Vec<_>>();
+        assert!(waits.contains(&(0, 0)));
+        assert!(waits.contains(&(1, 1)));
+        assert!(waits.contains(&(2, 1)));
+    }
+}
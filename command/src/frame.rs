@@ -2,10 +2,7 @@
 
 use error::DeviceLost;
 
-/// Unique index of the frame.
-/// It must be unique per render instance.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub struct FrameIndex(u64);
+pub use resource::FrameIndex;
 
 /// Generate `Frame`s.
 #[derive(Debug)]
@@ -22,7 +19,7 @@ impl FrameGen {
     pub fn next<F>(&mut self) -> Frame<F> {
         self.0 += 1;
         unsafe {
-            Frame::new(FrameIndex(self.0))
+            Frame::new(FrameIndex::new(self.0))
         }
     }
 
@@ -30,7 +27,7 @@ impl FrameGen {
     pub fn next_with_fences<F>(&mut self, fences: Vec<F>) -> Frame<F> {
         self.0 += 1;
         unsafe {
-            Frame::with_fences(FrameIndex(self.0), fences)
+            Frame::with_fences(FrameIndex::new(self.0), fences)
         }
     }
 }
@@ -1,11 +1,12 @@
 use ash::{
     self,
-    version::{DeviceV1_0, FunctionPointers},
+    version::{DeviceV1_0, FunctionPointers, InstanceV1_0},
     vk,
 };
 
 use device::{CommandBuffer, CommandQueue, Device};
 use fence;
+use resource::AshDevice;
 
 impl From<fence::FenceCreateFlags> for vk::FenceCreateFlags {
     fn from(flags: fence::FenceCreateFlags) -> Self {
@@ -36,6 +37,24 @@ where
     }
 }
 
+impl<V> Device for AshDevice<V>
+where
+    V: FunctionPointers,
+    ash::Device<V>: DeviceV1_0,
+    ash::Instance<V>: InstanceV1_0,
+{
+    type Semaphore = vk::Semaphore;
+    type Fence = vk::Fence;
+    type Submit = vk::CommandBuffer;
+    type CommandPool = (vk::DeviceFnV1_0, vk::CommandBuffer);
+    type CommandBuffer = (vk::DeviceFnV1_0, vk::CommandBuffer);
+    type CommandQueue = vk::Queue;
+
+    unsafe fn create_fence(&self, info: fence::FenceCreateInfo) -> Self::Fence {
+        Device::create_fence(self.raw(), info)
+    }
+}
+
 impl CommandBuffer for (vk::DeviceFnV1_0, vk::CommandBuffer) {
     type Submit = vk::CommandBuffer;
 
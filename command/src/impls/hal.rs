@@ -5,6 +5,7 @@ use hal;
 
 use device::{CommandBuffer, CommandQueue, Device};
 use fence;
+use resource::HalDevice;
 
 impl<D, B> Device for (D, PhantomData<B>)
 where
@@ -23,6 +24,24 @@ where
     }
 }
 
+impl<B, D, P> Device for HalDevice<B, D, P>
+where
+    B: hal::Backend,
+    D: Borrow<B::Device>,
+    P: Borrow<B::PhysicalDevice>,
+{
+    type Semaphore = B::Semaphore;
+    type Fence = B::Fence;
+    type Submit = B::CommandBuffer;
+    type CommandPool = B::CommandPool;
+    type CommandBuffer = (B::CommandBuffer, PhantomData<B>);
+    type CommandQueue = (B::CommandQueue, PhantomData<B>);
+
+    unsafe fn create_fence(&self, info: fence::FenceCreateInfo) -> Self::Fence {
+        hal::Device::create_fence(self.raw(), info.flags.contains(fence::FenceCreateFlags::CREATE_SIGNALED))
+    }
+}
+
 impl<C, B> CommandBuffer for (C, PhantomData<B>)
 where
     B: hal::Backend,
@@ -0,0 +1,193 @@
+use std::{
+    cmp::{max, Reverse},
+    ops::Range,
+};
+
+use util::aligned;
+
+/// Memory requirements and lifetime of transient resource.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AliasingRequest {
+    /// Interval during which resource is alive.
+    /// Typically range of framegraph pass indices.
+    pub lifetime: Range<u64>,
+
+    /// Size of memory range required by the resource.
+    pub size: u64,
+
+    /// Alignment required by the resource.
+    /// Must be power of two.
+    pub align: u64,
+
+    /// Memory type mask with bits set for memory types that support the resource.
+    pub mask: u32,
+
+    /// Whether resource is linear.
+    /// Buffers and images with linear tiling are linear.
+    /// Linear and non-linear resources alive at the same time
+    /// never share a page of buffer-image granularity.
+    pub linear: bool,
+}
+
+/// Memory block shared by resources with compatible memory types.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AliasingGroup {
+    /// Memory types suitable for all resources of the group.
+    pub mask: u32,
+
+    /// Size of the block required.
+    pub size: u64,
+
+    /// Alignment of the block required.
+    pub align: u64,
+
+    /// Indices of the requests placed into the group.
+    pub requests: Vec<usize>,
+}
+
+/// Placement of the request in the shared block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Placement {
+    /// Index of the group.
+    pub group: usize,
+
+    /// Offset from the start of the group block.
+    pub offset: u64,
+}
+
+/// Plan of packing transient resources into shared memory blocks.
+///
+/// Resources alive at the same time never overlap,
+/// while resources with disjoint lifetimes may be placed to overlapping ranges.
+/// Placement is first-fit coloring of the interval graph of lifetimes
+/// with larger resources placed first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AliasingPlan {
+    /// Shared blocks to allocate.
+    pub groups: Vec<AliasingGroup>,
+
+    /// Placement of each request, in order of requests.
+    pub placements: Vec<Placement>,
+}
+
+impl AliasingPlan {
+    /// Pack `requests` into shared blocks.
+    /// `granularity` is the device's `bufferImageGranularity` limit.
+    /// Must be power of two.
+    pub fn new(requests: &[AliasingRequest], granularity: u64) -> Self {
+        assert_eq!(
+            granularity.count_ones(),
+            1,
+            "Granularity must be power of two"
+        );
+
+        let mut order = (0..requests.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&index| {
+            let ref request = requests[index];
+            (Reverse(request.size), request.lifetime.start, index)
+        });
+
+        let mut groups: Vec<AliasingGroup> = Vec::new();
+        let mut placements = vec![
+            Placement {
+                group: 0,
+                offset: 0,
+            };
+            requests.len()
+        ];
+
+        for index in order {
+            let ref request = requests[index];
+            assert!(
+                request.lifetime.start <= request.lifetime.end,
+                "Lifetime {:?} is invalid",
+                request.lifetime
+            );
+            assert_eq!(
+                request.align.count_ones(),
+                1,
+                "Alignment must be power of two"
+            );
+            assert_ne!(request.mask, 0, "Memory type mask can't be empty");
+
+            let group = match groups
+                .iter()
+                .position(|group| group.mask & request.mask != 0)
+            {
+                Some(group) => group,
+                None => {
+                    groups.push(AliasingGroup {
+                        mask: request.mask,
+                        size: 0,
+                        align: 1,
+                        requests: Vec::new(),
+                    });
+                    groups.len() - 1
+                }
+            };
+
+            let offset = first_fit(requests, &placements, &groups[group], request, granularity);
+            let ref mut group_ref = groups[group];
+            // Pages are counted from the start of the memory object,
+            // so block with mixed resources must start at page boundary.
+            let mixed = group_ref
+                .requests
+                .iter()
+                .any(|&other| requests[other].linear != request.linear);
+            if mixed {
+                group_ref.align = max(group_ref.align, granularity);
+            }
+            group_ref.mask &= request.mask;
+            group_ref.size = max(group_ref.size, offset + request.size);
+            group_ref.align = max(group_ref.align, request.align);
+            group_ref.requests.push(index);
+            placements[index] = Placement { group, offset };
+        }
+
+        AliasingPlan { groups, placements }
+    }
+
+    /// Total size of all shared blocks.
+    pub fn size(&self) -> u64 {
+        self.groups.iter().map(|group| group.size).sum()
+    }
+}
+
+/// Find lowest offset in the `group` where `request` doesn't overlap
+/// with resources alive at the same time.
+/// Ranges of alive resources with different linearity are extended to whole pages of `granularity`.
+fn first_fit(
+    requests: &[AliasingRequest],
+    placements: &[Placement],
+    group: &AliasingGroup,
+    request: &AliasingRequest,
+    granularity: u64,
+) -> u64 {
+    let mut occupied = group
+        .requests
+        .iter()
+        .filter(|&&other| overlaps(&requests[other].lifetime, &request.lifetime))
+        .map(|&other| {
+            let offset = placements[other].offset;
+            let end = offset + requests[other].size;
+            if requests[other].linear == request.linear {
+                offset..end
+            } else {
+                offset & !(granularity - 1)..aligned(end, granularity)
+            }
+        }).collect::<Vec<_>>();
+    occupied.sort_by_key(|range| range.start);
+
+    let mut offset = 0;
+    for range in occupied {
+        if aligned(offset, request.align) + request.size <= range.start {
+            break;
+        }
+        offset = max(offset, range.end);
+    }
+    aligned(offset, request.align)
+}
+
+fn overlaps(left: &Range<u64>, right: &Range<u64>) -> bool {
+    left.start < right.end && right.start < left.end
+}
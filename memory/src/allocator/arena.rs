@@ -107,6 +107,7 @@ pub struct ArenaConfig {
 pub struct ArenaAllocator<T> {
     memory_type: u32,
     memory_properties: Properties,
+    non_coherent_atom_size: u64,
     arena_size: u64,
     offset: u64,
     arenas: VecDeque<Arena<T>>,
@@ -139,7 +140,12 @@ impl<T: 'static> ArenaAllocator<T> {
     /// Create new `ArenaAllocator`
     /// for `memory_type` with `memory_properties` specified,
     /// with `ArenaConfig` provided.
-    pub fn new(memory_type: u32, memory_properties: Properties, config: ArenaConfig) -> Self {
+    pub fn new(
+        memory_type: u32,
+        memory_properties: Properties,
+        config: ArenaConfig,
+        non_coherent_atom_size: u64,
+    ) -> Self {
         assert!(memory_properties.contains(Self::properties_required()));
         assert!(
             fits_usize(config.arena_size),
@@ -148,6 +154,7 @@ impl<T: 'static> ArenaAllocator<T> {
         ArenaAllocator {
             memory_type,
             memory_properties,
+            non_coherent_atom_size,
             arena_size: config.arena_size,
             offset: 0,
             arenas: VecDeque::new(),
@@ -244,7 +251,12 @@ impl<T: 'static> Allocator for ArenaAllocator<T> {
                 }
             };
 
-            let memory = Memory::from_raw(raw, self.arena_size, self.memory_properties);
+            let memory = Memory::from_raw(
+                raw,
+                self.arena_size,
+                self.memory_properties,
+                self.non_coherent_atom_size,
+            );
 
             (memory, ptr)
         };
@@ -0,0 +1,380 @@
+use std::{collections::BTreeSet, fmt::Debug, ops::Range, ptr::NonNull};
+
+use relevant::Relevant;
+use veclist::VecList;
+
+use allocator::Allocator;
+use block::Block;
+use device::Device;
+use error::*;
+use mapping::*;
+use memory::*;
+use util::*;
+
+/// Memory block allocated from `BuddyAllocator`
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct BuddyBlock<T> {
+    #[derivative(Debug(bound = "T: Debug", format_with = "super::memory_ptr_fmt"))]
+    memory: *const Memory<T>,
+    chunk_index: u32,
+    ptr: Option<NonNull<u8>>,
+    range: Range<u64>,
+    #[derivative(Debug = "ignore")]
+    relevant: Relevant,
+}
+
+unsafe impl<T: Send> Send for BuddyBlock<T> {}
+unsafe impl<T: Sync> Sync for BuddyBlock<T> {}
+
+impl<T> BuddyBlock<T> {
+    fn shared_memory(&self) -> &Memory<T> {
+        // Memory won't be freed until last block created from it deallocated.
+        unsafe { &*self.memory }
+    }
+
+    fn size(&self) -> u64 {
+        self.range.end - self.range.start
+    }
+
+    fn dispose(self) {
+        self.relevant.dispose();
+    }
+}
+
+impl<T: 'static> Block for BuddyBlock<T> {
+    type Memory = T;
+
+    #[inline]
+    fn properties(&self) -> Properties {
+        self.shared_memory().properties()
+    }
+
+    #[inline]
+    fn memory(&self) -> &T {
+        self.shared_memory().raw()
+    }
+
+    #[inline]
+    fn range(&self) -> Range<u64> {
+        self.range.clone()
+    }
+
+    #[inline]
+    fn map<'a, D>(
+        &'a mut self,
+        _device: &D,
+        range: Range<u64>,
+    ) -> Result<MappedRange<'a, T>, MappingError> {
+        assert!(
+            range.start <= range.end,
+            "Memory mapping region must have valid size"
+        );
+        if !self.shared_memory().host_visible() {
+            return Err(MappingError::HostInvisible);
+        }
+
+        if let Some(ptr) = self.ptr {
+            if let Some((ptr, range)) = mapped_sub_range(ptr, self.range.clone(), range) {
+                let mapping = unsafe { MappedRange::from_raw(self.shared_memory(), ptr, range) };
+                Ok(mapping)
+            } else {
+                Err(MappingError::OutOfBounds)
+            }
+        } else {
+            Err(MappingError::MappingUnsafe)
+        }
+    }
+
+    #[inline]
+    fn unmap<D>(&mut self, _device: &D) {}
+}
+
+/// Config for `BuddyAllocator`.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BuddyConfig {
+    /// Size of the memory object allocated from device.
+    /// Must be power of two.
+    pub chunk_size: u64,
+
+    /// Minimal block size.
+    /// All requests are rounded up to power of two not less than this value.
+    pub min_block_size: u64,
+}
+
+/// Binary buddy allocator.
+/// Suitable for medium to large long-lived allocations.
+/// Each chunk is recursively split in halves until block of required size is found.
+/// Freed blocks are merged with their buddies so fragmentation is bounded by
+/// rounding requests up to power of two.
+/// Memory object is returned to the device once every block allocated from it is freed.
+#[derive(Debug)]
+pub struct BuddyAllocator<T> {
+    /// Memory type that this allocator allocates.
+    memory_type: u32,
+
+    /// Memory properties of the memory type.
+    memory_properties: Properties,
+
+    /// `nonCoherentAtomSize` of the device.
+    non_coherent_atom_size: u64,
+
+    /// Size of the chunk.
+    chunk_size: u64,
+
+    /// Size of the smallest block.
+    min_block_size: u64,
+
+    /// Chunks allocated from device.
+    chunks: VecList<Chunk<T>>,
+
+    /// Free blocks per order.
+    /// Each order `n` corresponds to `min_block_size << n` size.
+    /// Blocks are identified by chunk index and offset in chunk.
+    free: Vec<BTreeSet<(u32, u64)>>,
+
+    /// Total size of blocks allocated.
+    used: u64,
+}
+
+/// Memory object allocated from device.
+#[derive(Debug)]
+struct Chunk<T> {
+    memory: Box<Memory<T>>,
+    ptr: Option<NonNull<u8>>,
+}
+
+unsafe impl<T: Send> Send for Chunk<T> {}
+unsafe impl<T: Sync> Sync for Chunk<T> {}
+
+impl<T: 'static> BuddyAllocator<T> {
+    /// Get properties required by the allocator.
+    pub fn properties_required() -> Properties {
+        Properties::empty()
+    }
+
+    /// Maximum allocation size.
+    pub fn max_allocation(&self) -> u64 {
+        self.chunk_size / 2
+    }
+
+    /// Create new `BuddyAllocator`
+    /// for `memory_type` with `memory_properties` specified,
+    /// with `BuddyConfig` provided.
+    pub fn new(
+        memory_type: u32,
+        memory_properties: Properties,
+        config: BuddyConfig,
+        non_coherent_atom_size: u64,
+    ) -> Self {
+        assert_eq!(
+            config.chunk_size.count_ones(),
+            1,
+            "Chunk size must be power of two"
+        );
+        assert_eq!(
+            config.min_block_size.count_ones(),
+            1,
+            "Minimal block size must be power of two"
+        );
+        assert!(
+            config.min_block_size < config.chunk_size,
+            "Minimal block size must be less than chunk size"
+        );
+        if memory_properties.host_visible() {
+            assert!(
+                fits_usize(config.chunk_size),
+                "Chunk size must fit usize for mapping"
+            );
+        }
+
+        let orders = (config.chunk_size / config.min_block_size).trailing_zeros() + 1;
+
+        BuddyAllocator {
+            memory_type,
+            memory_properties,
+            non_coherent_atom_size,
+            chunk_size: config.chunk_size,
+            min_block_size: config.min_block_size,
+            chunks: VecList::new(),
+            free: (0..orders).map(|_| BTreeSet::new()).collect(),
+            used: 0,
+        }
+    }
+
+    /// Perform full cleanup of the memory allocated.
+    pub fn dispose(self) {
+        assert_eq!(
+            self.used, 0,
+            "Blocks are not freed during allocator disposal"
+        );
+    }
+
+    /// Order of the whole chunk.
+    fn max_order(&self) -> usize {
+        self.free.len() - 1
+    }
+
+    /// Get block size for the order.
+    fn order_size(&self, order: usize) -> u64 {
+        self.min_block_size << order
+    }
+
+    /// Get order of the smallest block that can hold `size` bytes.
+    fn order(&self, size: u64) -> usize {
+        debug_assert!(size <= self.chunk_size);
+        let size = ::std::cmp::max(size, self.min_block_size).next_power_of_two();
+        (size / self.min_block_size).trailing_zeros() as usize
+    }
+
+    /// Allocate new chunk from device.
+    fn alloc_chunk<D>(&mut self, device: &D) -> Result<u32, MemoryError>
+    where
+        D: Device<Memory = T>,
+    {
+        let (memory, ptr) = unsafe {
+            // Valid memory type specified.
+            let raw = device.allocate(self.memory_type, self.chunk_size)?;
+
+            let ptr = if self.memory_properties.host_visible() {
+                match device.map(&raw, 0..self.chunk_size) {
+                    Ok(ptr) => Some(ptr),
+                    Err(error) => {
+                        device.free(raw);
+                        return Err(error.into());
+                    }
+                }
+            } else {
+                None
+            };
+            let memory = Memory::from_raw(
+                raw,
+                self.chunk_size,
+                self.memory_properties,
+                self.non_coherent_atom_size,
+            );
+            (memory, ptr)
+        };
+
+        let chunk_index = self.chunks.push(Chunk {
+            memory: Box::new(memory),
+            ptr,
+        });
+        assert!(
+            fits_u32(chunk_index),
+            "Number of chunks must fit in u32 limit"
+        );
+        Ok(chunk_index as u32)
+    }
+
+    /// Return chunk memory to the device.
+    fn free_chunk<D>(&mut self, device: &D, chunk_index: u32) -> u64
+    where
+        D: Device<Memory = T>,
+    {
+        let chunk = self
+            .chunks
+            .pop(chunk_index as usize)
+            .expect("Chunk must exist");
+        let size = chunk.memory.size();
+        unsafe {
+            if chunk.ptr.is_some() {
+                device.unmap(chunk.memory.raw());
+            }
+            device.free(chunk.memory.into_raw());
+        }
+        size
+    }
+}
+
+impl<T: 'static> Allocator for BuddyAllocator<T> {
+    type Memory = T;
+    type Block = BuddyBlock<T>;
+
+    fn alloc<D>(
+        &mut self,
+        device: &D,
+        size: u64,
+        align: u64,
+    ) -> Result<(BuddyBlock<T>, u64), MemoryError>
+    where
+        D: Device<Memory = T>,
+    {
+        // Blocks are always aligned to their size.
+        let size = ::std::cmp::max(size, align);
+        debug_assert!(size <= self.chunk_size);
+
+        let order = self.order(size);
+        let found = (order..self.free.len())
+            .filter_map(|order| self.free[order].iter().next().map(|&key| (order, key)))
+            .next();
+
+        let (mut found_order, (chunk_index, offset), allocated) = match found {
+            Some((found_order, key)) => {
+                self.free[found_order].remove(&key);
+                (found_order, key, 0)
+            }
+            None => {
+                let chunk_index = self.alloc_chunk(device)?;
+                (self.max_order(), (chunk_index, 0), self.chunk_size)
+            }
+        };
+
+        // Split block until it fits.
+        while found_order > order {
+            found_order -= 1;
+            let buddy = offset + self.order_size(found_order);
+            let old = self.free[found_order].insert((chunk_index, buddy));
+            debug_assert!(old);
+        }
+
+        let ref chunk = self.chunks[chunk_index as usize];
+        let block_range = offset..offset + self.order_size(order);
+        self.used += self.order_size(order);
+
+        Ok((
+            BuddyBlock {
+                memory: &*chunk.memory,
+                chunk_index,
+                ptr: chunk.ptr.map(|ptr| {
+                    mapped_fitting_range(ptr, 0..self.chunk_size, block_range.clone())
+                        .expect("Block must be in sub-range of chunk")
+                }),
+                range: block_range,
+                relevant: Relevant,
+            },
+            allocated,
+        ))
+    }
+
+    fn free<D>(&mut self, device: &D, block: BuddyBlock<T>) -> u64
+    where
+        D: Device<Memory = T>,
+    {
+        let mut order = self.order(block.size());
+        let mut offset = block.range.start;
+        let chunk_index = block.chunk_index;
+        self.used -= block.size();
+        block.dispose();
+
+        // Merge with free buddies.
+        while order < self.max_order() {
+            let buddy = offset ^ self.order_size(order);
+            if !self.free[order].remove(&(chunk_index, buddy)) {
+                break;
+            }
+            offset = ::std::cmp::min(offset, buddy);
+            order += 1;
+        }
+
+        if order == self.max_order() {
+            debug_assert_eq!(offset, 0);
+            self.free_chunk(device, chunk_index)
+        } else {
+            let old = self.free[order].insert((chunk_index, offset));
+            debug_assert!(old);
+            0
+        }
+    }
+}
@@ -4,13 +4,15 @@ use allocator::Allocator;
 use block::Block;
 use device::Device;
 use error::*;
+use heaps::Dedicated;
 use mapping::{mapped_fitting_range, MappedRange};
 use memory::*;
 
 /// Memory block allocated from `DedicatedAllocator`
 #[derive(Debug)]
 pub struct DedicatedBlock<T> {
-    memory: Memory<T>,
+    /// Boxed so that memory object keeps its address when block is moved.
+    memory: Box<Memory<T>>,
     mapping: Option<(NonNull<u8>, Range<u64>)>,
 }
 
@@ -22,13 +24,13 @@ impl<T> DedicatedBlock<T> {
     /// Panics if mapped.
     pub fn unwrap_memory(self) -> Memory<T> {
         assert!(self.mapping.is_none());
-        self.memory
+        *self.memory
     }
 
     /// Make unmapped block.
     pub fn from_memory(memory: Memory<T>) -> Self {
         DedicatedBlock {
-            memory,
+            memory: Box::new(memory),
             mapping: None,
         }
     }
@@ -76,9 +78,17 @@ impl<T: 'static> Block for DedicatedBlock<T> {
                 if self.mapping.take().is_some() {
                     device.unmap(&self.memory.raw());
                 }
-                let mapping = MappedRange::new(&self.memory, device, range.clone())?;
+                // Map aligned range so that flushed and invalidated ranges fit mapping.
+                let aligned = if self.memory.host_coherent() {
+                    range.clone()
+                } else {
+                    self.memory.non_coherent_range(range.clone())
+                };
+                let mapping = MappedRange::new(&self.memory, device, aligned)?;
+                let ptr = mapped_fitting_range(mapping.ptr(), mapping.range(), range.clone())
+                    .expect("Aligned range contains requested range");
                 self.mapping = Some((mapping.ptr(), mapping.range()));
-                Ok(mapping)
+                Ok(MappedRange::from_raw(&self.memory, ptr, range))
             }
         }
     }
@@ -106,6 +116,7 @@ impl<T: 'static> Block for DedicatedBlock<T> {
 pub struct DedicatedAllocator<T> {
     memory_type: u32,
     memory_properties: Properties,
+    non_coherent_atom_size: u64,
     used: u64,
     pd: PhantomData<T>,
 }
@@ -118,41 +129,68 @@ impl<T> DedicatedAllocator<T> {
 
     /// Create new `ArenaAllocator`
     /// for `memory_type` with `memory_properties` specified
-    pub fn new(memory_type: u32, memory_properties: Properties) -> Self {
+    pub fn new(
+        memory_type: u32,
+        memory_properties: Properties,
+        non_coherent_atom_size: u64,
+    ) -> Self {
         DedicatedAllocator {
             memory_type,
             memory_properties,
+            non_coherent_atom_size,
             used: 0,
             pd: PhantomData,
         }
     }
-}
 
-impl<T: 'static> Allocator for DedicatedAllocator<T> {
-    type Memory = T;
-    type Block = DedicatedBlock<T>;
-
-    #[inline]
-    fn alloc<D>(
+    /// Allocate memory object dedicated to the resource.
+    /// Resource handle is passed to `Device::allocate_dedicated`.
+    pub fn alloc_dedicated<D>(
         &mut self,
         device: &D,
         size: u64,
         _align: u64,
+        dedicated: Dedicated,
     ) -> Result<(DedicatedBlock<T>, u64), MemoryError>
     where
         D: Device<Memory = T>,
     {
+        let raw = unsafe { device.allocate_dedicated(self.memory_type, size, dedicated)? };
+        Ok(self.block(raw, size))
+    }
+
+    fn block(&mut self, raw: T, size: u64) -> (DedicatedBlock<T>, u64) {
         let memory = unsafe {
             Memory::from_raw(
-                device.allocate(self.memory_type, size)?,
+                raw,
                 size,
                 self.memory_properties,
+                self.non_coherent_atom_size,
             )
         };
 
         self.used += size;
 
-        Ok((DedicatedBlock::from_memory(memory), size))
+        (DedicatedBlock::from_memory(memory), size)
+    }
+}
+
+impl<T: 'static> Allocator for DedicatedAllocator<T> {
+    type Memory = T;
+    type Block = DedicatedBlock<T>;
+
+    #[inline]
+    fn alloc<D>(
+        &mut self,
+        device: &D,
+        size: u64,
+        _align: u64,
+    ) -> Result<(DedicatedBlock<T>, u64), MemoryError>
+    where
+        D: Device<Memory = T>,
+    {
+        let raw = unsafe { device.allocate(self.memory_type, size)? };
+        Ok(self.block(raw, size))
     }
 
     #[inline]
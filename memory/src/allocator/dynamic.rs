@@ -110,7 +110,8 @@ pub struct DynamicConfig {
 /// Suitable for any type of small allocations.
 /// Have up to `block_size_granularity - 1` memory overhead.
 /// Every freed block can be recycled independently.
-/// Memory objects can be returned to the system if whole memory object become unused (not implemented yet).
+/// Memory objects are returned to the system when whole memory object become unused.
+/// One unused chunk per block size is kept to avoid allocating it again on alloc/free churn.
 #[derive(Debug)]
 pub struct DynamicAllocator<T> {
     /// Memory type that this allocator allocates.
@@ -119,6 +120,9 @@ pub struct DynamicAllocator<T> {
     /// Memory properties of the memory type.
     memory_properties: Properties,
 
+    /// `nonCoherentAtomSize` of the device.
+    non_coherent_atom_size: u64,
+
     /// Number of blocks per chunk.
     blocks_per_chunk: u32,
 
@@ -141,6 +145,10 @@ struct Size<T> {
 
     /// Bits per free blocks.
     blocks: BitSet,
+
+    /// Index of the chunk that has all blocks free.
+    /// It is kept until another chunk becomes unused.
+    unused: Option<u32>,
 }
 
 impl<T: 'static> DynamicAllocator<T> {
@@ -157,7 +165,12 @@ impl<T: 'static> DynamicAllocator<T> {
     /// Create new `ArenaAllocator`
     /// for `memory_type` with `memory_properties` specified,
     /// with `ArenaConfig` provided.
-    pub fn new(memory_type: u32, memory_properties: Properties, mut config: DynamicConfig) -> Self {
+    pub fn new(
+        memory_type: u32,
+        memory_properties: Properties,
+        mut config: DynamicConfig,
+        non_coherent_atom_size: u64,
+    ) -> Self {
         // This is hack to simplify implementation of chunk cleaning.
         config.blocks_per_chunk = ::std::mem::size_of::<usize>() as u32 * 8;
 
@@ -189,6 +202,7 @@ impl<T: 'static> DynamicAllocator<T> {
         DynamicAllocator {
             memory_type,
             memory_properties,
+            non_coherent_atom_size,
             block_size_granularity: config.block_size_granularity,
             blocks_per_chunk: config.blocks_per_chunk,
             sizes: (0..sizes)
@@ -196,10 +210,33 @@ impl<T: 'static> DynamicAllocator<T> {
                     chunks: VecList::new(),
                     blocks: BitSet::new(),
                     total_chunks: 0,
+                    unused: None,
                 }).collect(),
         }
     }
 
+    /// Perform full cleanup of the memory allocated.
+    pub fn dispose<D>(mut self, device: &D)
+    where
+        D: Device<Memory = T>,
+    {
+        // Releasing chunk frees block of bigger size. Hence sizes are visited in ascending order.
+        for size_index in 0..self.sizes.len() {
+            if let Some(chunk_index) = self.sizes[size_index].unused.take() {
+                self.release_chunk(device, size_index, chunk_index);
+            }
+        }
+        assert!(
+            self.sizes.iter().all(|size| size.total_chunks == 0),
+            "Blocks are not freed during allocator disposal"
+        );
+    }
+
+    /// All requests are rounded up to multiple of this value.
+    pub fn block_size_granularity(&self) -> u64 {
+        self.block_size_granularity
+    }
+
     /// Maximum block size.
     /// Any request bigger will result in panic.
     pub fn max_block_size(&self) -> u64 {
@@ -244,7 +281,12 @@ impl<T: 'static> DynamicAllocator<T> {
                 } else {
                     None
                 };
-                let memory = Memory::from_raw(raw, size, self.memory_properties);
+                let memory = Memory::from_raw(
+                    raw,
+                    size,
+                    self.memory_properties,
+                    self.non_coherent_atom_size,
+                );
                 (memory, mapping)
             };
             Ok((Chunk::Dedicated(Box::new(memory), mapping), size))
@@ -262,10 +304,12 @@ impl<T: 'static> DynamicAllocator<T> {
         D: Device<Memory = T>,
     {
         match chunk {
-            Chunk::Dedicated(boxed, _) => {
+            Chunk::Dedicated(boxed, mapping) => {
                 let size = boxed.size();
                 unsafe {
-                    device.unmap(boxed.raw());
+                    if mapping.is_some() {
+                        device.unmap(boxed.raw());
+                    }
                     device.free(boxed.into_raw());
                 }
                 size
@@ -274,6 +318,25 @@ impl<T: 'static> DynamicAllocator<T> {
         }
     }
 
+    /// Remove unused chunk from the size list and free its memory.
+    fn release_chunk<D>(&mut self, device: &D, size_index: usize, chunk_index: u32) -> u64
+    where
+        D: Device<Memory = T>,
+    {
+        let chunk_start = chunk_index * self.blocks_per_chunk;
+        let chunk_end = chunk_start + self.blocks_per_chunk;
+        for index in chunk_start..chunk_end {
+            let old = self.sizes[size_index].blocks.remove(index);
+            debug_assert!(old);
+        }
+        let chunk = self.sizes[size_index]
+            .chunks
+            .pop(chunk_index as usize)
+            .expect("Chunk must exist");
+        self.sizes[size_index].total_chunks -= 1;
+        self.free_chunk(device, chunk)
+    }
+
     /// Allocate from chunk.
     fn alloc_from_chunk<D>(
         &mut self,
@@ -287,13 +350,16 @@ impl<T: 'static> DynamicAllocator<T> {
         let (block_index, allocated) = match (&self.sizes[size_index].blocks).iter().next() {
             Some(block_index) => {
                 self.sizes[size_index].blocks.remove(block_index);
+                if self.sizes[size_index].unused == Some(block_index / self.blocks_per_chunk) {
+                    self.sizes[size_index].unused = None;
+                }
                 (block_index, 0)
             }
             None => {
                 if self.sizes[self.size_index(size)].total_chunks == self.max_chunks_per_size() {
                     return Err(OutOfMemoryError::OutOfHostMemory.into());
                 }
-                let chunk_size = size * self.blocks_per_chunk as u64;
+                let chunk_size = self.block_size(size_index) * self.blocks_per_chunk as u64;
                 let (chunk, allocated) = self.alloc_chunk(device, chunk_size)?;
                 let chunk_index = self.sizes[size_index].chunks.push(chunk) as u32;
                 self.sizes[size_index].total_chunks += 1;
@@ -307,6 +373,11 @@ impl<T: 'static> DynamicAllocator<T> {
             }
         };
 
+        Ok((self.block(size_index, block_index), allocated))
+    }
+
+    /// Create block for the block index taken from free blocks.
+    fn block(&self, size_index: usize, block_index: u32) -> DynamicBlock<T> {
         let chunk_index = block_index / self.blocks_per_chunk;
 
         let ref chunk = self.sizes[size_index].chunks[chunk_index as usize];
@@ -316,19 +387,60 @@ impl<T: 'static> DynamicAllocator<T> {
             chunk_range.start + (block_index % self.blocks_per_chunk) as u64 * block_size;
         let block_range = block_offset..block_offset + block_size;
 
-        Ok((
-            DynamicBlock {
-                range: block_range.clone(),
-                memory: chunk.shared_memory(),
-                index: block_index,
-                ptr: chunk.ptr().map(|ptr| {
-                    mapped_fitting_range(ptr, chunk.range(), block_range)
-                        .expect("Block must be in sub-range of chunk")
-                }),
-                relevant: Relevant,
-            },
-            allocated,
-        ))
+        DynamicBlock {
+            range: block_range.clone(),
+            memory: chunk.shared_memory(),
+            index: block_index,
+            ptr: chunk.ptr().map(|ptr| {
+                mapped_fitting_range(ptr, chunk.range(), block_range)
+                    .expect("Block must be in sub-range of chunk")
+            }),
+            relevant: Relevant,
+        }
+    }
+
+    /// Number of blocks in use in the chunk.
+    fn chunk_used_blocks(&self, size_index: usize, chunk_index: u32) -> u32 {
+        let free = self.sizes[size_index]
+            .blocks
+            .layer0(chunk_index as usize)
+            .count_ones();
+        self.blocks_per_chunk - free
+    }
+
+    /// Find new place for the block in another chunk
+    /// that has more blocks in use (or same number of blocks but lower index).
+    /// Moving blocks this way lets sparsely used chunks become unused and return to the device.
+    /// Returns `None` if there is no better place.
+    ///
+    /// Content of the `block` must be copied into returned block before `block` is freed.
+    pub fn relocate(&mut self, block: &DynamicBlock<T>) -> Option<DynamicBlock<T>> {
+        let size_index = self.size_index(block.size());
+        let chunk_index = block.index / self.blocks_per_chunk;
+        let used = self.chunk_used_blocks(size_index, chunk_index);
+
+        let target = {
+            let ref size = self.sizes[size_index];
+            (0..size.chunks.upper_bound() as u32)
+                .filter(|&index| index != chunk_index && size.chunks.get(index as usize).is_some())
+                .filter(|&index| size.blocks.layer0(index as usize) != 0)
+                .map(|index| (index, self.chunk_used_blocks(size_index, index)))
+                .filter(|&(index, target_used)| {
+                    target_used > used || (target_used == used && index < chunk_index)
+                }).max_by_key(|&(index, target_used)| (target_used, !index))?
+                .0
+        };
+
+        let block_index = target * self.blocks_per_chunk
+            + self.sizes[size_index]
+                .blocks
+                .layer0(target as usize)
+                .trailing_zeros();
+        let old = self.sizes[size_index].blocks.remove(block_index);
+        debug_assert!(old);
+        debug_assert_ne!(self.sizes[size_index].unused, Some(target));
+
+        Some(self.block(size_index, block_index))
     }
 }
 
@@ -368,15 +480,13 @@ impl<T: 'static> Allocator for DynamicAllocator<T> {
         let chunk_end = chunk_start + self.blocks_per_chunk;
 
         if check_bit_range_set(&self.sizes[size_index].blocks, chunk_start..chunk_end) {
-            for index in chunk_start..chunk_end {
-                let old = self.sizes[size_index].blocks.remove(index);
-                debug_assert!(old);
+            match self.sizes[size_index].unused {
+                Some(_) => self.release_chunk(device, size_index, chunk_index),
+                None => {
+                    self.sizes[size_index].unused = Some(chunk_index);
+                    0
+                }
             }
-            let chunk = self.sizes[size_index]
-                .chunks
-                .pop(chunk_index as usize)
-                .expect("Chunk must exist");
-            self.free_chunk(device, chunk)
         } else {
             0
         }
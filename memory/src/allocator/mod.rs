@@ -3,8 +3,10 @@
 use std::{any::Any, fmt};
 
 mod arena;
+mod buddy;
 mod dedicated;
 mod dynamic;
+mod ring;
 // mod chunk;
 
 use block::Block;
@@ -14,8 +16,10 @@ use memory::Memory;
 
 pub use self::{
     arena::{ArenaAllocator, ArenaBlock, ArenaConfig},
+    buddy::{BuddyAllocator, BuddyBlock, BuddyConfig},
     dedicated::{DedicatedAllocator, DedicatedBlock},
     dynamic::{DynamicAllocator, DynamicBlock, DynamicConfig},
+    ring::{RingAllocator, RingBlock, RingConfig},
 };
 
 /// Allocator trait implemented for various allocators.
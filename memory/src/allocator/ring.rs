@@ -0,0 +1,316 @@
+use std::{collections::VecDeque, fmt::Debug, ops::Range, ptr::NonNull};
+
+use relevant::Relevant;
+
+use allocator::Allocator;
+use block::Block;
+use device::Device;
+use error::*;
+use mapping::*;
+use memory::*;
+use util::*;
+
+/// Memory block allocated from `RingAllocator`
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct RingBlock<T> {
+    #[derivative(Debug(bound = "T: Debug", format_with = "super::memory_ptr_fmt"))]
+    memory: *const Memory<T>,
+    frame: u64,
+    ptr: NonNull<u8>,
+    range: Range<u64>,
+    #[derivative(Debug = "ignore")]
+    relevant: Relevant,
+}
+
+unsafe impl<T: Send> Send for RingBlock<T> {}
+unsafe impl<T: Sync> Sync for RingBlock<T> {}
+
+impl<T> RingBlock<T> {
+    /// Frame block was allocated in.
+    /// Memory of the block is reused after this frame is completed.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    fn shared_memory(&self) -> &Memory<T> {
+        // Memory won't be freed until allocator is disposed.
+        unsafe { &*self.memory }
+    }
+
+    fn dispose(self) {
+        self.relevant.dispose();
+    }
+}
+
+impl<T: 'static> Block for RingBlock<T> {
+    type Memory = T;
+
+    #[inline]
+    fn properties(&self) -> Properties {
+        self.shared_memory().properties()
+    }
+
+    #[inline]
+    fn memory(&self) -> &T {
+        self.shared_memory().raw()
+    }
+
+    #[inline]
+    fn range(&self) -> Range<u64> {
+        self.range.clone()
+    }
+
+    #[inline]
+    fn map<'a, D>(
+        &'a mut self,
+        _device: &D,
+        range: Range<u64>,
+    ) -> Result<MappedRange<'a, T>, MappingError> {
+        assert!(
+            range.start <= range.end,
+            "Memory mapping region must have valid size"
+        );
+        debug_assert!(self.shared_memory().host_visible());
+
+        if let Some((ptr, range)) = mapped_sub_range(self.ptr, self.range.clone(), range) {
+            let mapping = unsafe { MappedRange::from_raw(self.shared_memory(), ptr, range) };
+            Ok(mapping)
+        } else {
+            Err(MappingError::OutOfBounds)
+        }
+    }
+
+    #[inline]
+    fn unmap<D>(&mut self, _device: &D) {
+        debug_assert!(self.shared_memory().host_visible());
+    }
+}
+
+/// Config for `RingAllocator`.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RingConfig {
+    /// Size of the ring buffer.
+    /// Should fit all allocations made during frames in flight.
+    pub ring_size: u64,
+}
+
+/// Allocator for per-frame transient data.
+/// Allocates blocks sequentially from single persistently mapped memory object
+/// wrapping around when the end is reached.
+/// Each block is tagged with current frame index.
+/// Memory is reclaimed in bulk when frame is reported complete,
+/// freeing blocks only disposes them.
+///
+/// This allocator suites best data uploaded every frame and consumed by GPU within that frame.
+/// Allocation fails if ring is full until some frames are completed.
+#[derive(Derivative)]
+#[derivative(Debug)]
+pub struct RingAllocator<T> {
+    memory_type: u32,
+    memory_properties: Properties,
+    non_coherent_atom_size: u64,
+    ring_size: u64,
+    #[derivative(Debug = "ignore")]
+    memory: Option<(Box<Memory<T>>, NonNull<u8>)>,
+
+    /// Offset of the next allocation. Never wraps.
+    head: u64,
+
+    /// Offset of the oldest allocation still in use. Never wraps.
+    tail: u64,
+
+    /// Current frame index.
+    frame: u64,
+
+    /// End offsets of the frames not yet completed.
+    frames: VecDeque<(u64, u64)>,
+}
+
+unsafe impl<T: Send> Send for RingAllocator<T> {}
+unsafe impl<T: Sync> Sync for RingAllocator<T> {}
+
+impl<T: 'static> RingAllocator<T> {
+    /// Get properties required by the allocator.
+    pub fn properties_required() -> Properties {
+        Properties::HOST_VISIBLE
+    }
+
+    /// Maximum allocation size.
+    pub fn max_allocation(&self) -> u64 {
+        self.ring_size / 2
+    }
+
+    /// Create new `RingAllocator`
+    /// for `memory_type` with `memory_properties` specified,
+    /// with `RingConfig` provided.
+    pub fn new(
+        memory_type: u32,
+        memory_properties: Properties,
+        config: RingConfig,
+        non_coherent_atom_size: u64,
+    ) -> Self {
+        assert!(memory_properties.contains(Self::properties_required()));
+        assert_ne!(config.ring_size, 0, "Ring size can't be 0");
+        assert!(
+            fits_usize(config.ring_size),
+            "Ring size must fit in both usize and u64"
+        );
+        RingAllocator {
+            memory_type,
+            memory_properties,
+            non_coherent_atom_size,
+            ring_size: config.ring_size,
+            memory: None,
+            head: 0,
+            tail: 0,
+            frame: 0,
+            frames: VecDeque::new(),
+        }
+    }
+
+    /// Number of bytes that can't be reused until frames in flight are completed.
+    pub fn in_flight(&self) -> u64 {
+        self.head - self.tail
+    }
+
+    /// Start new frame.
+    /// Following allocations will be tagged with `frame`.
+    pub fn begin_frame(&mut self, frame: u64) {
+        assert!(
+            frame >= self.frame,
+            "Frame indices must not decrease. Current frame is {}, new frame is {}",
+            self.frame,
+            frame
+        );
+        self.frame = frame;
+    }
+
+    /// Reclaim memory of all blocks allocated in frames up to `frame` inclusive.
+    pub fn complete_frame(&mut self, frame: u64) {
+        while let Some(&(index, end)) = self.frames.front() {
+            if index > frame {
+                break;
+            }
+            self.tail = end;
+            self.frames.pop_front();
+        }
+    }
+
+    /// Perform full cleanup of the memory allocated.
+    pub fn dispose<D>(self, device: &D)
+    where
+        D: Device<Memory = T>,
+    {
+        if let Some((memory, _)) = self.memory {
+            unsafe {
+                device.unmap(memory.raw());
+                device.free(memory.into_raw());
+            }
+        }
+    }
+
+    /// Allocate and map ring memory.
+    fn init<D>(&mut self, device: &D) -> Result<(), MemoryError>
+    where
+        D: Device<Memory = T>,
+    {
+        let (memory, ptr) = unsafe {
+            let raw = device.allocate(self.memory_type, self.ring_size)?;
+
+            let ptr = match device.map(&raw, 0..self.ring_size) {
+                Ok(ptr) => ptr,
+                Err(error) => {
+                    device.free(raw);
+                    return Err(error.into());
+                }
+            };
+
+            let memory = Memory::from_raw(
+                raw,
+                self.ring_size,
+                self.memory_properties,
+                self.non_coherent_atom_size,
+            );
+
+            (memory, ptr)
+        };
+
+        self.memory = Some((Box::new(memory), ptr));
+        Ok(())
+    }
+}
+
+impl<T: 'static> Allocator for RingAllocator<T> {
+    type Memory = T;
+
+    type Block = RingBlock<T>;
+
+    fn alloc<D>(
+        &mut self,
+        device: &D,
+        size: u64,
+        align: u64,
+    ) -> Result<(RingBlock<T>, u64), MemoryError>
+    where
+        D: Device<Memory = T>,
+    {
+        debug_assert!(self.memory_properties.host_visible());
+
+        assert!(size <= self.ring_size);
+        assert!(align <= self.ring_size);
+
+        let allocated = if self.memory.is_none() {
+            self.init(device)?;
+            self.ring_size
+        } else {
+            0
+        };
+
+        let offset = self.head % self.ring_size;
+        let mut start = aligned(offset, align);
+        if start + size > self.ring_size {
+            // Skip the rest of the ring and start from the beginning.
+            start = self.ring_size;
+        }
+        let end = self.head - offset + start + size;
+        if end - self.tail > self.ring_size {
+            return Err(OutOfMemoryError::HeapsExhausted.into());
+        }
+
+        let start = start % self.ring_size;
+        self.head = end;
+        match self.frames.back_mut() {
+            Some(&mut (frame, ref mut frame_end)) if frame == self.frame => *frame_end = end,
+            _ => self.frames.push_back((self.frame, end)),
+        }
+
+        let (ref memory, ptr) = *self.memory.as_ref().unwrap();
+        let (ptr, range) = mapped_sub_range(ptr, 0..self.ring_size, start..start + size)
+            .expect("This sub-range must fit in ring mapping");
+
+        Ok((
+            RingBlock {
+                memory: &**memory,
+                frame: self.frame,
+                ptr,
+                range,
+                relevant: Relevant,
+            },
+            allocated,
+        ))
+    }
+
+    fn free<D>(&mut self, _device: &D, block: Self::Block) -> u64
+    where
+        D: Device<Memory = T>,
+    {
+        debug_assert!(
+            block.frame <= self.frame,
+            "Block can't be allocated in future frame"
+        );
+        block.dispose();
+        0
+    }
+}
@@ -0,0 +1,216 @@
+use allocator::{ArenaConfig, BuddyConfig, DynamicConfig, RingConfig};
+use heaps::Config;
+use memory::Properties;
+
+/// Config that replaces derived one for particular memory type.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ConfigOverride {
+    /// Index of the memory type.
+    pub memory_type: u32,
+
+    /// Config for the memory type.
+    pub config: Config,
+}
+
+/// Builder of `Config`s for memory types.
+/// Derives config of each memory type from its `Properties` and size of its heap.
+///
+/// With `serde` feature builder can be deserialized from RON, TOML or any other format.
+/// Missing fields take default values
+/// so only tuned values are required to be written.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ConfigBuilder {
+    /// Size of memory objects allocated by sub-allocators is heap size divided by this value
+    /// rounded down to power of two.
+    pub chunk_fraction: u64,
+
+    /// Minimal size of memory objects allocated by sub-allocators.
+    pub min_chunk_size: u64,
+
+    /// Maximal size of memory objects allocated by sub-allocators.
+    pub max_chunk_size: u64,
+
+    /// Granularity of dynamic sub-allocator.
+    /// Must be power of two.
+    pub dynamic_granularity: u64,
+
+    /// Maximum block size of dynamic sub-allocator.
+    /// Lowered for small heaps to keep 64 blocks in chunk.
+    pub max_dynamic_block_size: u64,
+
+    /// Minimal block size of buddy sub-allocator.
+    /// Must be power of two.
+    pub min_buddy_block_size: u64,
+
+    /// Enable ring sub-allocator for host-visible memory types.
+    pub ring: bool,
+
+    /// Configs that replace derived ones.
+    pub overrides: Vec<ConfigOverride>,
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        ConfigBuilder {
+            chunk_fraction: 64,
+            min_chunk_size: 1024 * 1024,
+            max_chunk_size: 64 * 1024 * 1024,
+            dynamic_granularity: 256,
+            max_dynamic_block_size: 64 * 1024,
+            min_buddy_block_size: 64 * 1024,
+            ring: true,
+            overrides: Vec::new(),
+        }
+    }
+}
+
+impl ConfigBuilder {
+    /// Create builder with default heuristics.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace derived config of the `memory_type`.
+    pub fn with_override(mut self, memory_type: u32, config: Config) -> Self {
+        self.overrides
+            .retain(|other| other.memory_type != memory_type);
+        self.overrides.push(ConfigOverride {
+            memory_type,
+            config,
+        });
+        self
+    }
+
+    /// Get config for `memory_type`
+    /// with `properties`
+    /// from heap of `heap_size`.
+    pub fn config(&self, memory_type: u32, properties: Properties, heap_size: u64) -> Config {
+        if let Some(config) = self
+            .overrides
+            .iter()
+            .rev()
+            .find(|config| config.memory_type == memory_type)
+        {
+            return config.config;
+        }
+
+        let chunk_size = self.chunk_size(heap_size);
+
+        // Lazily allocated memory is meant for transient attachments
+        // that should get dedicated memory objects.
+        if properties.lazily_allocated() || chunk_size == 0 {
+            return Config {
+                arena: None,
+                dynamic: None,
+                buddy: None,
+                ring: None,
+            };
+        }
+
+        Config {
+            arena: if properties.host_visible() {
+                Some(ArenaConfig {
+                    arena_size: chunk_size,
+                })
+            } else {
+                None
+            },
+            dynamic: self.dynamic(chunk_size),
+            buddy: if properties.device_local() {
+                self.buddy(chunk_size)
+            } else {
+                None
+            },
+            ring: if self.ring && properties.host_visible() {
+                Some(RingConfig {
+                    ring_size: chunk_size,
+                })
+            } else {
+                None
+            },
+        }
+    }
+
+    /// Get configs for all memory types.
+    /// `types` are properties and heap indices of memory types.
+    /// `heaps` are sizes of memory heaps.
+    /// Result can be passed directly to `Heaps::new`.
+    pub fn build<P>(&self, types: P, heaps: &[u64]) -> Vec<(Properties, u32, Config)>
+    where
+        P: IntoIterator<Item = (Properties, u32)>,
+    {
+        types
+            .into_iter()
+            .enumerate()
+            .map(|(index, (properties, heap_index))| {
+                assert!(
+                    (heap_index as usize) < heaps.len(),
+                    "Heap index is out of bounds"
+                );
+                let heap_size = heaps[heap_index as usize];
+                (
+                    properties,
+                    heap_index,
+                    self.config(index as u32, properties, heap_size),
+                )
+            }).collect()
+    }
+
+    fn chunk_size(&self, heap_size: u64) -> u64 {
+        assert_ne!(self.chunk_fraction, 0, "Chunk fraction can't be 0");
+        let chunk_size = power_of_two_floor(heap_size / self.chunk_fraction);
+        let chunk_size = ::std::cmp::max(chunk_size, power_of_two_floor(self.min_chunk_size));
+        let chunk_size = ::std::cmp::min(chunk_size, power_of_two_floor(self.max_chunk_size));
+        // Chunk can't be larger than heap itself.
+        ::std::cmp::min(chunk_size, power_of_two_floor(heap_size))
+    }
+
+    fn dynamic(&self, chunk_size: u64) -> Option<DynamicConfig> {
+        let granularity = self.dynamic_granularity;
+        assert_eq!(
+            granularity.count_ones(),
+            1,
+            "Dynamic granularity must be power of two"
+        );
+        let max_block_size = ::std::cmp::min(self.max_dynamic_block_size, chunk_size / 64);
+        let max_block_size = max_block_size - max_block_size % granularity;
+        if max_block_size == 0 {
+            None
+        } else {
+            Some(DynamicConfig {
+                blocks_per_chunk: 64,
+                block_size_granularity: granularity,
+                max_block_size,
+            })
+        }
+    }
+
+    fn buddy(&self, chunk_size: u64) -> Option<BuddyConfig> {
+        assert_eq!(
+            self.min_buddy_block_size.count_ones(),
+            1,
+            "Minimal buddy block size must be power of two"
+        );
+        if self.min_buddy_block_size < chunk_size {
+            Some(BuddyConfig {
+                chunk_size,
+                min_block_size: self.min_buddy_block_size,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Largest power of two not greater than `value`.
+/// `0` for `0`.
+fn power_of_two_floor(value: u64) -> u64 {
+    if value == 0 {
+        0
+    } else {
+        1 << (63 - value.leading_zeros())
+    }
+}
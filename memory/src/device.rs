@@ -1,4 +1,5 @@
 use error::*;
+use heaps::Dedicated;
 use std::{ops::Range, ptr::NonNull};
 
 /// Trait for memory allocation and mapping.
@@ -13,6 +14,21 @@ pub trait Device: Sized {
     /// `index` - memory type index.
     unsafe fn allocate(&self, index: u32, size: u64) -> Result<Self::Memory, AllocationError>;
 
+    /// Allocate memory object dedicated to the resource.
+    /// Backend may pass resource handle to the driver
+    /// which can place resource more efficiently.
+    ///
+    /// Default implementation ignores `dedicated` and calls `allocate`.
+    unsafe fn allocate_dedicated(
+        &self,
+        index: u32,
+        size: u64,
+        dedicated: Dedicated,
+    ) -> Result<Self::Memory, AllocationError> {
+        let _ = dedicated;
+        self.allocate(index, size)
+    }
+
     /// Free memory object.
     unsafe fn free(&self, memory: Self::Memory);
 
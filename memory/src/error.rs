@@ -37,6 +37,10 @@ pub enum MappingError {
     #[fail(display = "Virtual memory allocation failed")]
     MappingFailed,
 
+    /// Device is lost and can't be used anymore.
+    #[fail(display = "Device lost")]
+    DeviceLost,
+
     /// Out of either host or device memory.
     #[fail(display = "{}", _0)]
     OutOfMemoryError(OutOfMemoryError),
@@ -73,6 +77,10 @@ pub enum AllocationError {
     #[fail(display = "Can't allocate more memory objects")]
     TooManyObjects,
 
+    /// Device is lost and can't be used anymore.
+    #[fail(display = "Device lost")]
+    DeviceLost,
+
     /// No memory types for specified mask and usage were found.
     #[fail(
         display = "No suitable memory types for mask: ({}) and usage: ({:?})",
@@ -1,4 +1,4 @@
-use std::ops::Range;
+use std::{cmp::Ordering, collections::BTreeMap, mem::replace, ops::Range};
 
 use allocator::*;
 use smallvec::SmallVec;
@@ -6,10 +6,13 @@ use smallvec::SmallVec;
 use block::Block;
 use device::Device;
 use error::*;
+use leak::{Leak, LeakReport, Tag};
 use mapping::*;
 use memory::*;
+use priority::Priority;
 use usage::{Usage, UsageValue};
 use util::*;
+use utilization::*;
 
 /// Config for `Heaps` allocator.
 #[derive(Clone, Copy, Debug)]
@@ -20,23 +23,81 @@ pub struct Config {
 
     /// Config for dynamic sub-allocator.
     pub dynamic: Option<DynamicConfig>,
+
+    /// Config for buddy sub-allocator.
+    pub buddy: Option<BuddyConfig>,
+
+    /// Config for ring sub-allocator.
+    pub ring: Option<RingConfig>,
     // chunk: Option<ChunkConfig>,
 }
 
+/// Resource memory block is dedicated to.
+/// Holds raw handle of the resource which is passed to `Device::allocate_dedicated`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dedicated {
+    /// Buffer with raw handle.
+    Buffer(u64),
+
+    /// Image with raw handle.
+    Image(u64),
+}
+
+/// Optional attributes of the block being allocated.
+/// Default value describes untagged block of normal priority
+/// that is sub-allocated as usual.
+#[derive(Clone, Debug, Default)]
+pub struct AllocationInfo {
+    /// Tag that is shown in leak report.
+    pub tag: Option<Tag>,
+
+    /// Resource the block is dedicated to.
+    /// Block gets memory object of its own.
+    /// Should be set when driver prefers or requires dedicated allocation for the resource.
+    pub dedicated: Option<Dedicated>,
+
+    /// Priority of the block.
+    /// On fitness ties blocks with priority lower than `Priority::NORMAL`
+    /// are placed to less contended heaps.
+    pub priority: Priority,
+}
+
+/// Callback invoked when allocation doesn't fit into heap budget.
+/// Receives heap index and number of bytes that should be freed.
+/// Returns blocks released by the user to be freed.
+pub type Eviction<T> = Box<dyn FnMut(u32, u64) -> Vec<MemoryBlock<T>> + Send>;
+
 /// Heaps available on particular physical device.
-#[derive(Debug)]
+#[derive(Derivative)]
+#[derivative(Debug)]
 pub struct Heaps<T> {
     types: Vec<MemoryType<T>>,
     heaps: Vec<MemoryHeap>,
+
+    /// Number of blocks not yet freed per tag, memory type and size.
+    outstanding: BTreeMap<(Option<Tag>, u32, u64), u64>,
+
+    /// `nonCoherentAtomSize` of the device.
+    non_coherent_atom_size: u64,
+
+    #[derivative(Debug = "ignore")]
+    eviction: Option<Eviction<T>>,
 }
 
 impl<T: 'static> Heaps<T> {
     /// This must be called with `Properties` fetched from physical device.
-    pub unsafe fn new<P, H>(types: P, heaps: H) -> Self
+    /// `non_coherent_atom_size` is the limit of the device with the same name
+    /// and must be power of two.
+    pub unsafe fn new<P, H>(types: P, heaps: H, non_coherent_atom_size: u64) -> Self
     where
         P: IntoIterator<Item = (Properties, u32, Config)>,
         H: IntoIterator<Item = u64>,
     {
+        assert_eq!(
+            non_coherent_atom_size.count_ones(),
+            1,
+            "Non-coherent atom size must be power of two"
+        );
         let heaps = heaps
             .into_iter()
             .map(|size| MemoryHeap::new(size))
@@ -57,12 +118,65 @@ impl<T: 'static> Heaps<T> {
                     let memory_type = index as u32;
                     let heap_index = heap_index as usize;
                     assert!(heap_index < heaps.len());
-                    MemoryType::new(memory_type, heap_index, properties, config)
+                    MemoryType::new(
+                        memory_type,
+                        heap_index,
+                        properties,
+                        config,
+                        non_coherent_atom_size,
+                    )
                 }).collect(),
             heaps,
+            outstanding: BTreeMap::new(),
+            non_coherent_atom_size,
+            eviction: None,
         }
     }
 
+    /// Get `nonCoherentAtomSize` blocks of non-coherent memory types are aligned to.
+    pub fn non_coherent_atom_size(&self) -> u64 {
+        self.non_coherent_atom_size
+    }
+
+    /// Set memory budget for the heap.
+    /// Memory types of the heap are not used for allocation
+    /// when it would make total size of memory allocated from heap exceed budget.
+    /// Budget is clamped to heap size.
+    pub fn set_budget(&mut self, heap_index: u32, budget: u64) {
+        assert!(fits_usize(heap_index));
+        let ref mut memory_heap = self.heaps[heap_index as usize];
+        memory_heap.budget = ::std::cmp::min(budget, memory_heap.size);
+    }
+
+    /// Get memory budget of the heap.
+    pub fn budget(&self, heap_index: u32) -> u64 {
+        assert!(fits_usize(heap_index));
+        self.heaps[heap_index as usize].budget
+    }
+
+    /// Get available bytes and budget of the heap memory type belongs to.
+    pub(crate) fn type_budget(&self, memory_index: u32) -> (u64, u64) {
+        assert!(fits_usize(memory_index));
+        let ref memory_heap = self.heaps[self.types[memory_index as usize].heap_index];
+        (memory_heap.available(), memory_heap.budget)
+    }
+
+    /// Register callback that is invoked when allocation doesn't fit into heap budget.
+    /// Blocks returned from callback are freed
+    /// and allocation is retried before falling back to less preferred memory type.
+    /// Without callback allocation falls back immediately.
+    pub fn set_eviction<F>(&mut self, eviction: F)
+    where
+        F: FnMut(u32, u64) -> Vec<MemoryBlock<T>> + Send + 'static,
+    {
+        self.eviction = Some(Box::new(eviction));
+    }
+
+    /// Remove eviction callback.
+    pub fn clear_eviction(&mut self) {
+        self.eviction = None;
+    }
+
     /// Allocate memory block
     /// from one of memory types specified by `mask`,
     /// for intended `usage`,
@@ -76,36 +190,216 @@ impl<T: 'static> Heaps<T> {
         size: u64,
         align: u64,
     ) -> Result<MemoryBlock<T>, MemoryError>
+    where
+        D: Device<Memory = T>,
+        U: Usage,
+    {
+        self.allocate_with(device, mask, usage, size, align, AllocationInfo::default())
+    }
+
+    /// Allocate memory block
+    /// from one of memory types specified by `mask`,
+    /// for intended `usage`,
+    /// with `size`
+    /// and `align` requirements.
+    /// Block is marked with `tag` that is shown in leak report.
+    /// Shortcut for `allocate_with` that sets only the tag.
+    pub fn allocate_tagged<D, U>(
+        &mut self,
+        device: &D,
+        mask: u32,
+        usage: U,
+        size: u64,
+        align: u64,
+        tag: Option<Tag>,
+    ) -> Result<MemoryBlock<T>, MemoryError>
+    where
+        D: Device<Memory = T>,
+        U: Usage,
+    {
+        let info = AllocationInfo {
+            tag,
+            ..AllocationInfo::default()
+        };
+        self.allocate_with(device, mask, usage, size, align, info)
+    }
+
+    /// Allocate memory block
+    /// from one of memory types specified by `mask`,
+    /// for intended `usage`,
+    /// with `size`
+    /// and `align` requirements.
+    /// Block is marked with `priority`.
+    /// Shortcut for `allocate_with` that sets only the priority.
+    pub fn allocate_prioritized<D, U>(
+        &mut self,
+        device: &D,
+        mask: u32,
+        usage: U,
+        size: u64,
+        align: u64,
+        priority: Priority,
+    ) -> Result<MemoryBlock<T>, MemoryError>
+    where
+        D: Device<Memory = T>,
+        U: Usage,
+    {
+        let info = AllocationInfo {
+            priority,
+            ..AllocationInfo::default()
+        };
+        self.allocate_with(device, mask, usage, size, align, info)
+    }
+
+    /// Allocate memory block with memory object of its own
+    /// from one of memory types specified by `mask`,
+    /// for intended `usage`,
+    /// with `size`
+    /// and `align` requirements.
+    /// Block records resource it is `dedicated` to.
+    /// Shortcut for `allocate_with` that sets only the dedicated resource.
+    pub fn allocate_dedicated<D, U>(
+        &mut self,
+        device: &D,
+        mask: u32,
+        usage: U,
+        size: u64,
+        align: u64,
+        dedicated: Dedicated,
+    ) -> Result<MemoryBlock<T>, MemoryError>
+    where
+        D: Device<Memory = T>,
+        U: Usage,
+    {
+        let info = AllocationInfo {
+            dedicated: Some(dedicated),
+            ..AllocationInfo::default()
+        };
+        self.allocate_with(device, mask, usage, size, align, info)
+    }
+
+    /// Allocate `count` memory tiles for sparse resources
+    /// from one of memory types specified by `mask`,
+    /// for intended `usage`.
+    /// Each tile is `tile_size` bytes and aligned to `tile_size`.
+    ///
+    /// Either all tiles are allocated or none.
+    pub fn allocate_tiles<D, U>(
+        &mut self,
+        device: &D,
+        mask: u32,
+        usage: U,
+        tile_size: u64,
+        count: usize,
+    ) -> Result<Vec<MemoryBlock<T>>, MemoryError>
+    where
+        D: Device<Memory = T>,
+        U: Usage,
+    {
+        let usage = usage.value();
+        let mut tiles = Vec::with_capacity(count);
+        for _ in 0..count {
+            match self.allocate(device, mask, usage, tile_size, tile_size) {
+                Ok(tile) => tiles.push(tile),
+                Err(error) => {
+                    for tile in tiles {
+                        self.free(device, tile);
+                    }
+                    return Err(error);
+                }
+            }
+        }
+        Ok(tiles)
+    }
+
+    /// Allocate memory block
+    /// from one of memory types specified by `mask`,
+    /// for intended `usage`,
+    /// with `size`
+    /// and `align` requirements.
+    /// Block gets tag, priority and dedicated resource from `info`.
+    ///
+    /// Memory types are tried in order of `Usage::memory_fitness`.
+    /// If heap budget is exceeded eviction callback is invoked
+    /// before falling back to next memory type.
+    pub fn allocate_with<D, U>(
+        &mut self,
+        device: &D,
+        mask: u32,
+        usage: U,
+        size: u64,
+        align: u64,
+        info: AllocationInfo,
+    ) -> Result<MemoryBlock<T>, MemoryError>
     where
         D: Device<Memory = T>,
         U: Usage,
     {
         debug_assert!(fits_u32(self.types.len()));
 
-        let (memory_index, _, _) = {
-            let suitable_types = self
-                .types
-                .iter()
-                .enumerate()
-                .filter(|(index, _)| (mask & (1u32 << index)) != 0)
-                .filter_map(|(index, mt)| {
-                    usage
-                        .memory_fitness(mt.properties)
-                        .map(move |fitness| (index, mt, fitness))
-                }).collect::<SmallVec<[_; 64]>>();
-
-            if suitable_types.is_empty() {
-                return Err(AllocationError::NoSuitableMemory(mask, usage.value()).into());
+        let mut suitable_types = self
+            .types
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| (mask & (1u32 << index)) != 0)
+            .filter_map(|(index, mt)| {
+                usage
+                    .memory_fitness(mt.properties)
+                    .map(move |fitness| (index, mt.heap_index, fitness))
+            }).collect::<SmallVec<[_; 64]>>();
+
+        if suitable_types.is_empty() {
+            return Err(AllocationError::NoSuitableMemory(mask, usage.value()).into());
+        }
+
+        // Most fit first. Last one wins on ties.
+        suitable_types.reverse();
+        suitable_types.sort_by(|(_, _, left), (_, _, right)| right.cmp(left));
+
+        if info.priority < Priority::NORMAL {
+            // Less contended heaps first on ties.
+            let ref heaps = self.heaps;
+            suitable_types.sort_by(|&(_, left_heap, left), &(_, right_heap, right)| {
+                right
+                    .cmp(&left)
+                    .then_with(|| heaps[left_heap].contention_cmp(&heaps[right_heap]))
+            });
+        }
+
+        let usage = usage.value();
+        for (memory_index, heap_index, _) in suitable_types {
+            if self.heaps[heap_index].available() <= size + align
+                && !self.evict(device, heap_index, size + align)
+            {
+                continue;
             }
 
-            suitable_types
-                .into_iter()
-                .filter(|(_, mt, _)| self.heaps[mt.heap_index].available() > size + align)
-                .max_by_key(|&(_, _, fitness)| fitness)
-                .ok_or(OutOfMemoryError::HeapsExhausted)?
+            match self.allocate_from(device, memory_index as u32, usage, size, align, &info) {
+                Err(MemoryError::OutOfMemoryError(OutOfMemoryError::HeapsExhausted)) => continue,
+                result => return result,
+            }
+        }
+
+        Err(OutOfMemoryError::HeapsExhausted.into())
+    }
+
+    /// Invoke eviction callback to make heap have more than `required` bytes available.
+    /// Returns `true` if succeeded.
+    fn evict<D>(&mut self, device: &D, heap_index: usize, required: u64) -> bool
+    where
+        D: Device<Memory = T>,
+    {
+        let needed = required + 1 - self.heaps[heap_index].available();
+        let blocks = match self.eviction {
+            Some(ref mut eviction) => eviction(heap_index as u32, needed),
+            None => return false,
         };
 
-        self.allocate_from::<D, U>(device, memory_index as u32, usage, size, align)
+        for block in blocks {
+            self.free(device, block);
+        }
+
+        self.heaps[heap_index].available() > required
     }
 
     /// Allocate memory block
@@ -113,13 +407,16 @@ impl<T: 'static> Heaps<T> {
     /// for intended `usage`,
     /// with `size`
     /// and `align` requirements.
-    fn allocate_from<D, U>(
+    /// Block is marked with tag and priority from `info`.
+    /// Block gets memory object of its own if it is dedicated to a resource.
+    pub(crate) fn allocate_from<D, U>(
         &mut self,
         device: &D,
         memory_index: u32,
         usage: U,
         size: u64,
         align: u64,
+        info: &AllocationInfo,
     ) -> Result<MemoryBlock<T>, MemoryError>
     where
         D: Device<Memory = T>,
@@ -134,12 +431,30 @@ impl<T: 'static> Heaps<T> {
             return Err(OutOfMemoryError::HeapsExhausted.into());
         }
 
-        let (block, allocated) = memory_type.alloc(device, usage, size, align)?;
+        let (block, allocated) = memory_type.alloc(device, usage, size, align, info.dedicated)?;
+        if allocated > 0 && memory_heap.used + allocated > memory_heap.budget {
+            // Sub-allocator allocated more memory than budget allows.
+            let freed = memory_type.free(device, block);
+            memory_heap.used = memory_heap.used + allocated - freed;
+            return Err(OutOfMemoryError::HeapsExhausted.into());
+        }
+
+        let size = block_size(&block);
         memory_heap.used += allocated;
+        memory_heap.effective += size;
+        *memory_heap.priorities.bytes_mut(info.priority) += size;
+        *self
+            .outstanding
+            .entry((info.tag.clone(), memory_index, size))
+            .or_insert(0) += 1;
 
         Ok(MemoryBlock {
             block,
             memory_index,
+            pinned: false,
+            tag: info.tag.clone(),
+            dedicated: info.dedicated,
+            priority: info.priority,
         })
     }
 
@@ -153,19 +468,195 @@ impl<T: 'static> Heaps<T> {
         let memory_index = block.memory_index;
         debug_assert!(fits_usize(memory_index));
 
+        let size = block_size(&block.block);
+        self.release_outstanding((block.tag, memory_index, size));
+
         let ref mut memory_type = self.types[memory_index as usize];
         let ref mut memory_heap = self.heaps[memory_type.heap_index];
+        memory_heap.effective -= size;
+        *memory_heap.priorities.bytes_mut(block.priority) -= size;
         let freed = memory_type.free(device, block.block);
         memory_heap.used -= freed;
     }
 
+    /// Plan relocation of memory `blocks` to reduce fragmentation.
+    /// Blocks that can be moved to better place are replaced with new blocks in `blocks`.
+    /// Pinned blocks are never moved.
+    ///
+    /// Caller must copy content of every relocated block from old block to new one
+    /// and then pass returned plan to `complete_defragmentation`
+    /// which frees old blocks.
+    pub fn defragment(&mut self, blocks: &mut [MemoryBlock<T>]) -> Defragmentation<T> {
+        let mut relocations = Vec::new();
+        for (index, block) in blocks.iter_mut().enumerate() {
+            if block.pinned {
+                continue;
+            }
+
+            let memory_index = block.memory_index;
+            debug_assert!(fits_usize(memory_index));
+
+            let ref mut memory_type = self.types[memory_index as usize];
+            let ref mut memory_heap = self.heaps[memory_type.heap_index];
+            if let Some(new) = memory_type.relocate(&block.block) {
+                let size = block_size(&new);
+                memory_heap.effective += size;
+                *memory_heap.priorities.bytes_mut(block.priority) += size;
+                *self
+                    .outstanding
+                    .entry((block.tag.clone(), memory_index, size))
+                    .or_insert(0) += 1;
+                let old = ::std::mem::replace(
+                    block,
+                    MemoryBlock {
+                        block: new,
+                        memory_index,
+                        pinned: false,
+                        tag: block.tag.clone(),
+                        dedicated: block.dedicated,
+                        priority: block.priority,
+                    },
+                );
+                relocations.push(Relocation { index, old, size });
+            }
+        }
+
+        Defragmentation { relocations }
+    }
+
+    /// Complete defragmentation.
+    /// Frees old blocks of all relocations.
+    ///
+    /// Content of all relocated blocks must be copied
+    /// and no commands that access old blocks may be pending.
+    pub fn complete_defragmentation<D>(&mut self, device: &D, defragmentation: Defragmentation<T>)
+    where
+        D: Device<Memory = T>,
+    {
+        for relocation in defragmentation.relocations {
+            self.free(device, relocation.old);
+        }
+    }
+
+    /// Start new frame.
+    /// Blocks allocated with `Stream` usage from now on are tagged with `frame`.
+    /// Frame indices must not decrease.
+    pub fn begin_frame(&mut self, frame: u64) {
+        for memory_type in &mut self.types {
+            if let Some(ref mut ring) = memory_type.ring {
+                ring.begin_frame(frame);
+            }
+        }
+    }
+
+    /// Report that GPU finished all work of frames up to `frame` inclusive.
+    /// Memory of blocks allocated with `Stream` usage in those frames is reused afterwards.
+    /// Those blocks still must be freed, but may be freed at any time.
+    pub fn complete_frame(&mut self, frame: u64) {
+        for memory_type in &mut self.types {
+            if let Some(ref mut ring) = memory_type.ring {
+                ring.complete_frame(frame);
+            }
+        }
+    }
+
+    /// Get properties of memory types
+    /// with block size granularity and maximum block size of their dynamic allocators.
+    pub(crate) fn memory_types<'a>(
+        &'a self,
+    ) -> impl Iterator<Item = (Properties, Option<(u64, u64)>)> + 'a {
+        self.types.iter().map(|memory_type| {
+            (
+                memory_type.properties,
+                memory_type
+                    .dynamic
+                    .as_ref()
+                    .map(|dynamic| (dynamic.block_size_granularity(), dynamic.max_allocation())),
+            )
+        })
+    }
+
+    /// Get memory utilization.
+    pub fn utilization(&self) -> TotalMemoryUtilization {
+        TotalMemoryUtilization {
+            heaps: self
+                .heaps
+                .iter()
+                .map(|heap| MemoryHeapUtilization {
+                    size: heap.size,
+                    budget: heap.budget,
+                    utilization: MemoryUtilization {
+                        used: heap.used,
+                        effective: heap.effective,
+                    },
+                    priorities: heap.priorities,
+                }).collect(),
+            types: self
+                .types
+                .iter()
+                .map(|memory_type| MemoryTypeUtilization {
+                    properties: memory_type.properties,
+                    heap_index: memory_type.heap_index,
+                    utilization: memory_type.utilization,
+                    blocks: memory_type.blocks,
+                }).collect(),
+        }
+    }
+
+    /// Replace tag of the memory block.
+    /// Leak report will list the block under the new tag.
+    ///
+    /// Memory block must be allocated from this heap.
+    pub fn set_tag(&mut self, block: &mut MemoryBlock<T>, tag: Option<Tag>) {
+        let size = block_size(&block.block);
+        let old = replace(&mut block.tag, tag.clone());
+        self.release_outstanding((old, block.memory_index, size));
+        *self
+            .outstanding
+            .entry((tag, block.memory_index, size))
+            .or_insert(0) += 1;
+    }
+
+    /// Get report of memory blocks not yet freed.
+    /// Blocks are grouped by tag, memory type and size.
+    pub fn leaks(&self) -> LeakReport {
+        LeakReport {
+            leaks: self
+                .outstanding
+                .iter()
+                .map(|(&(ref tag, memory_type, size), &count)| Leak {
+                    tag: tag.clone(),
+                    memory_type,
+                    size,
+                    count,
+                }).collect(),
+        }
+    }
+
+    fn release_outstanding(&mut self, key: (Option<Tag>, u32, u64)) {
+        let last = {
+            let count = self
+                .outstanding
+                .get_mut(&key)
+                .expect("Block must be allocated from this heap");
+            *count -= 1;
+            *count == 0
+        };
+        if last {
+            self.outstanding.remove(&key);
+        }
+    }
+
     /// Dispose of allocator.
     /// Cleanup allocators before dropping.
-    /// Will panic if memory instances are left allocated.
+    /// Will panic with leak report if memory blocks are left allocated.
     pub fn dispose<D>(self, device: &D)
     where
         D: Device<Memory = T>,
     {
+        let leaks = self.leaks();
+        assert!(leaks.is_empty(), "{}", leaks);
+
         for mt in self.types {
             mt.dispose(device)
         }
@@ -177,6 +668,10 @@ impl<T: 'static> Heaps<T> {
 pub struct MemoryBlock<T> {
     block: BlockFlavor<T>,
     memory_index: u32,
+    pinned: bool,
+    tag: Option<Tag>,
+    dedicated: Option<Dedicated>,
+    priority: Priority,
 }
 
 impl<T> MemoryBlock<T> {
@@ -184,6 +679,90 @@ impl<T> MemoryBlock<T> {
     pub fn memory_type(&self) -> u32 {
         self.memory_index
     }
+
+    /// Get tag of the block.
+    pub fn tag(&self) -> Option<Tag> {
+        self.tag.clone()
+    }
+
+    /// Get resource block is dedicated to.
+    pub fn dedicated(&self) -> Option<Dedicated> {
+        self.dedicated
+    }
+
+    /// Get priority block was allocated with.
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    /// Check if block is pinned.
+    /// Pinned blocks are not relocated by defragmentation.
+    pub fn pinned(&self) -> bool {
+        self.pinned
+    }
+
+    /// Pin block so it is not relocated by defragmentation.
+    pub fn pin(&mut self) {
+        self.pinned = true;
+    }
+
+    /// Unpin block so it can be relocated by defragmentation.
+    pub fn unpin(&mut self) {
+        self.pinned = false;
+    }
+
+    /// Check if block is allocated by `DynamicAllocator`.
+    pub(crate) fn is_dynamic(&self) -> bool {
+        match self.block {
+            BlockFlavor::Dynamic(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Relocation of the memory block planned by defragmentation.
+#[derive(Debug)]
+pub struct Relocation<T> {
+    index: usize,
+    old: MemoryBlock<T>,
+    size: u64,
+}
+
+impl<T> Relocation<T> {
+    /// Index of the relocated block in slice passed to `Heaps::defragment`.
+    /// New block is placed there.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Old block to copy content from.
+    pub fn old(&self) -> &MemoryBlock<T> {
+        &self.old
+    }
+
+    /// Number of bytes to copy.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+/// Relocation plan produced by `Heaps::defragment`.
+/// Old blocks are kept alive until plan is passed to `Heaps::complete_defragmentation`.
+#[derive(Debug)]
+pub struct Defragmentation<T> {
+    relocations: Vec<Relocation<T>>,
+}
+
+impl<T> Defragmentation<T> {
+    /// Get all relocations.
+    pub fn relocations(&self) -> &[Relocation<T>] {
+        &self.relocations
+    }
+
+    /// Check if there is nothing to relocate.
+    pub fn is_empty(&self) -> bool {
+        self.relocations.is_empty()
+    }
 }
 
 #[derive(Debug)]
@@ -191,6 +770,8 @@ enum BlockFlavor<T> {
     Dedicated(DedicatedBlock<T>),
     Arena(ArenaBlock<T>),
     Dynamic(DynamicBlock<T>),
+    Buddy(BuddyBlock<T>),
+    Ring(RingBlock<T>),
     // Chunk(ChunkBlock<T>),
 }
 
@@ -201,6 +782,8 @@ macro_rules! any_block {
             Dedicated($block) => $expr,
             Arena($block) => $expr,
             Dynamic($block) => $expr,
+            Buddy($block) => $expr,
+            Ring($block) => $expr,
             // Chunk($block) => $expr,
         }
     }};
@@ -210,6 +793,8 @@ macro_rules! any_block {
             Dedicated($block) => $expr,
             Arena($block) => $expr,
             Dynamic($block) => $expr,
+            Buddy($block) => $expr,
+            Ring($block) => $expr,
             // Chunk($block) => $expr,
         }
     }};
@@ -219,11 +804,43 @@ macro_rules! any_block {
             Dedicated($block) => $expr,
             Arena($block) => $expr,
             Dynamic($block) => $expr,
+            Buddy($block) => $expr,
+            Ring($block) => $expr,
             // Chunk($block) => $expr,
         }
     }};
 }
 
+/// Get size and alignment of the block for the memory type.
+/// Blocks of non-coherent memory occupy whole atoms
+/// so flushing or invalidating block never touches another block.
+pub(crate) fn non_coherent_block(
+    properties: Properties,
+    non_coherent_atom_size: u64,
+    size: u64,
+    align: u64,
+) -> (u64, u64) {
+    if properties.host_visible() && !properties.host_coherent() {
+        (
+            aligned(size, non_coherent_atom_size),
+            ::std::cmp::max(align, non_coherent_atom_size),
+        )
+    } else {
+        (size, align)
+    }
+}
+
+fn block_size<T: 'static>(block: &BlockFlavor<T>) -> u64 {
+    let range = match block {
+        BlockFlavor::Dedicated(block) => block.range(),
+        BlockFlavor::Arena(block) => block.range(),
+        BlockFlavor::Dynamic(block) => block.range(),
+        BlockFlavor::Buddy(block) => block.range(),
+        BlockFlavor::Ring(block) => block.range(),
+    };
+    range.end - range.start
+}
+
 impl<T: 'static> Block for MemoryBlock<T> {
     type Memory = T;
 
@@ -264,16 +881,30 @@ impl<T: 'static> Block for MemoryBlock<T> {
 #[derive(Debug)]
 struct MemoryHeap {
     size: u64,
+    budget: u64,
     used: u64,
+    effective: u64,
+    priorities: PriorityUtilization,
 }
 
 impl MemoryHeap {
     fn new(size: u64) -> Self {
-        MemoryHeap { size, used: 0 }
+        MemoryHeap {
+            size,
+            budget: size,
+            used: 0,
+            effective: 0,
+            priorities: PriorityUtilization::default(),
+        }
     }
 
     fn available(&self) -> u64 {
-        self.size - self.used
+        self.budget.saturating_sub(self.used)
+    }
+
+    /// Compare ratios of used memory to budget.
+    fn contention_cmp(&self, other: &Self) -> Ordering {
+        (self.used as u128 * other.budget as u128).cmp(&(other.used as u128 * self.budget as u128))
     }
 }
 
@@ -284,26 +915,51 @@ struct MemoryType<T> {
     dedicated: DedicatedAllocator<T>,
     arena: Option<ArenaAllocator<T>>,
     dynamic: Option<DynamicAllocator<T>>,
+    buddy: Option<BuddyAllocator<T>>,
+    ring: Option<RingAllocator<T>>,
     // chunk: Option<ChunkAllocator<T>>,
+    utilization: MemoryUtilization,
+    blocks: BlockCounts,
+    non_coherent_atom_size: u64,
 }
 
 impl<T: 'static> MemoryType<T> {
-    fn new(memory_type: u32, heap_index: usize, properties: Properties, config: Config) -> Self {
+    fn new(
+        memory_type: u32,
+        heap_index: usize,
+        properties: Properties,
+        config: Config,
+        non_coherent_atom_size: u64,
+    ) -> Self {
         MemoryType {
             properties,
             heap_index,
-            dedicated: DedicatedAllocator::new(memory_type, properties),
+            dedicated: DedicatedAllocator::new(memory_type, properties, non_coherent_atom_size),
             arena: if properties.contains(ArenaAllocator::<T>::properties_required()) {
-                config
-                    .arena
-                    .map(|config| ArenaAllocator::new(memory_type, properties, config))
+                config.arena.map(|config| {
+                    ArenaAllocator::new(memory_type, properties, config, non_coherent_atom_size)
+                })
             } else {
                 None
             },
             dynamic: if properties.contains(DynamicAllocator::<T>::properties_required()) {
-                config
-                    .dynamic
-                    .map(|config| DynamicAllocator::new(memory_type, properties, config))
+                config.dynamic.map(|config| {
+                    DynamicAllocator::new(memory_type, properties, config, non_coherent_atom_size)
+                })
+            } else {
+                None
+            },
+            buddy: if properties.contains(BuddyAllocator::<T>::properties_required()) {
+                config.buddy.map(|config| {
+                    BuddyAllocator::new(memory_type, properties, config, non_coherent_atom_size)
+                })
+            } else {
+                None
+            },
+            ring: if properties.contains(RingAllocator::<T>::properties_required()) {
+                config.ring.map(|config| {
+                    RingAllocator::new(memory_type, properties, config, non_coherent_atom_size)
+                })
             } else {
                 None
             },
@@ -312,6 +968,9 @@ impl<T: 'static> MemoryType<T> {
             // } else {
             //     None
             // },
+            utilization: MemoryUtilization::default(),
+            blocks: BlockCounts::default(),
+            non_coherent_atom_size,
         }
     }
 
@@ -321,30 +980,85 @@ impl<T: 'static> MemoryType<T> {
         usage: U,
         size: u64,
         align: u64,
+        dedicated: Option<Dedicated>,
     ) -> Result<(BlockFlavor<T>, u64), MemoryError>
     where
         D: Device<Memory = T>,
         U: Usage,
     {
-        match (usage.value(), self.arena.as_mut(), self.dynamic.as_mut()) {
-            (UsageValue::Upload, Some(ref mut arena), _)
-            | (UsageValue::Download, Some(ref mut arena), _)
+        let (block, allocated) = if let Some(dedicated) = dedicated {
+            let (size, align) =
+                non_coherent_block(self.properties, self.non_coherent_atom_size, size, align);
+            self.dedicated
+                .alloc_dedicated(device, size, align, dedicated)
+                .map(|(block, allocated)| (BlockFlavor::Dedicated(block), allocated))?
+        } else {
+            self.alloc_impl(device, usage, size, align)?
+        };
+        self.utilization.used += allocated;
+        self.utilization.effective += block_size(&block);
+        *self.block_count(&block) += 1;
+        Ok((block, allocated))
+    }
+
+    fn alloc_impl<D, U>(
+        &mut self,
+        device: &D,
+        usage: U,
+        size: u64,
+        align: u64,
+    ) -> Result<(BlockFlavor<T>, u64), MemoryError>
+    where
+        D: Device<Memory = T>,
+        U: Usage,
+    {
+        let (size, align) =
+            non_coherent_block(self.properties, self.non_coherent_atom_size, size, align);
+
+        match (
+            usage.value(),
+            self.arena.as_mut(),
+            self.dynamic.as_mut(),
+            self.buddy.as_mut(),
+            self.ring.as_mut(),
+        ) {
+            (UsageValue::Stream, _, _, _, Some(ref mut ring)) if size <= ring.max_allocation() => {
+                ring.alloc(device, size, align)
+                    .map(|(block, allocated)| (BlockFlavor::Ring(block), allocated))
+            }
+            (UsageValue::Upload, Some(ref mut arena), _, _, _)
+            | (UsageValue::Download, Some(ref mut arena), _, _, _)
+            | (UsageValue::Stream, Some(ref mut arena), _, _, _)
                 if size <= arena.max_allocation() =>
             {
                 arena
                     .alloc(device, size, align)
                     .map(|(block, allocated)| (BlockFlavor::Arena(block), allocated))
             }
-            (UsageValue::Dynamic, _, Some(ref mut dynamic)) if size <= dynamic.max_allocation() => {
+            (UsageValue::Dynamic, _, Some(ref mut dynamic), _, _)
+                if size <= dynamic.max_allocation() =>
+            {
                 dynamic
                     .alloc(device, size, align)
                     .map(|(block, allocated)| (BlockFlavor::Dynamic(block), allocated))
             }
-            (UsageValue::Data, _, Some(ref mut dynamic)) if size <= dynamic.max_allocation() => {
+            (UsageValue::Data, _, Some(ref mut dynamic), _, _)
+                if size <= dynamic.max_allocation() =>
+            {
                 dynamic
                     .alloc(device, size, align)
                     .map(|(block, allocated)| (BlockFlavor::Dynamic(block), allocated))
             }
+            // Buddy blocks are aligned to their size.
+            (UsageValue::Data, _, _, Some(ref mut buddy), _)
+                if ::std::cmp::max(size, align) <= buddy.max_allocation() =>
+            {
+                buddy
+                    .alloc(device, size, align)
+                    .map(|(block, allocated)| (BlockFlavor::Buddy(block), allocated))
+            }
+            // `Transient` blocks are always dedicated
+            // since lazily allocated memory is committed per memory object.
             _ => self
                 .dedicated
                 .alloc(device, size, align)
@@ -353,6 +1067,17 @@ impl<T: 'static> MemoryType<T> {
     }
 
     fn free<D>(&mut self, device: &D, block: BlockFlavor<T>) -> u64
+    where
+        D: Device<Memory = T>,
+    {
+        self.utilization.effective -= block_size(&block);
+        *self.block_count(&block) -= 1;
+        let freed = self.free_impl(device, block);
+        self.utilization.used -= freed;
+        freed
+    }
+
+    fn free_impl<D>(&mut self, device: &D, block: BlockFlavor<T>) -> u64
     where
         D: Device<Memory = T>,
     {
@@ -360,10 +1085,37 @@ impl<T: 'static> MemoryType<T> {
             BlockFlavor::Dedicated(block) => self.dedicated.free(device, block),
             BlockFlavor::Arena(block) => self.arena.as_mut().unwrap().free(device, block),
             BlockFlavor::Dynamic(block) => self.dynamic.as_mut().unwrap().free(device, block),
+            BlockFlavor::Buddy(block) => self.buddy.as_mut().unwrap().free(device, block),
+            BlockFlavor::Ring(block) => self.ring.as_mut().unwrap().free(device, block),
             // BlockFlavor::Chunk(block) => self.chunk.free(device, block),
         }
     }
 
+    fn relocate(&mut self, block: &BlockFlavor<T>) -> Option<BlockFlavor<T>> {
+        let new = match block {
+            BlockFlavor::Dynamic(block) => self
+                .dynamic
+                .as_mut()
+                .unwrap()
+                .relocate(block)
+                .map(BlockFlavor::Dynamic),
+            _ => None,
+        }?;
+        self.utilization.effective += block_size(&new);
+        *self.block_count(&new) += 1;
+        Some(new)
+    }
+
+    fn block_count(&mut self, block: &BlockFlavor<T>) -> &mut u64 {
+        match block {
+            BlockFlavor::Dedicated(_) => &mut self.blocks.dedicated,
+            BlockFlavor::Arena(_) => &mut self.blocks.arena,
+            BlockFlavor::Dynamic(_) => &mut self.blocks.dynamic,
+            BlockFlavor::Buddy(_) => &mut self.blocks.buddy,
+            BlockFlavor::Ring(_) => &mut self.blocks.ring,
+        }
+    }
+
     fn dispose<D>(self, device: &D)
     where
         D: Device<Memory = T>,
@@ -371,5 +1123,14 @@ impl<T: 'static> MemoryType<T> {
         if let Some(arena) = self.arena {
             arena.dispose(device);
         }
+        if let Some(dynamic) = self.dynamic {
+            dynamic.dispose(device);
+        }
+        if let Some(buddy) = self.buddy {
+            buddy.dispose();
+        }
+        if let Some(ring) = self.ring {
+            ring.dispose(device);
+        }
     }
 }
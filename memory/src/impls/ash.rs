@@ -4,12 +4,26 @@ use ash::{
 };
 use device::Device;
 use error::*;
+use heaps::Dedicated;
 use smallvec::SmallVec;
 use std::{
     ops::Range,
+    os::raw::c_void,
     ptr::{null, null_mut, NonNull},
 };
 
+/// `VK_STRUCTURE_TYPE_MEMORY_DEDICATED_ALLOCATE_INFO`.
+const STRUCTURE_TYPE_MEMORY_DEDICATED_ALLOCATE_INFO: i32 = 1000127001;
+
+/// `VkMemoryDedicatedAllocateInfo` from Vulkan 1.1 (`VK_KHR_dedicated_allocation`).
+#[repr(C)]
+struct MemoryDedicatedAllocateInfo {
+    s_type: i32,
+    p_next: *const c_void,
+    image: u64,
+    buffer: u64,
+}
+
 impl From<ash::vk::Result> for OutOfMemoryError {
     fn from(result: ash::vk::Result) -> OutOfMemoryError {
         match result {
@@ -28,6 +42,7 @@ impl From<ash::vk::Result> for MappingError {
             ash::vk::Result::ErrorOutOfHostMemory => OutOfMemoryError::OutOfHostMemory.into(),
             ash::vk::Result::ErrorOutOfDeviceMemory => OutOfMemoryError::OutOfDeviceMemory.into(),
             ash::vk::Result::ErrorMemoryMapFailed => MappingError::MappingFailed,
+            ash::vk::Result::ErrorDeviceLost => MappingError::DeviceLost,
             _ => panic!("unexpected error"),
         }
     }
@@ -39,6 +54,8 @@ impl From<ash::vk::Result> for AllocationError {
             ash::vk::Result::Success => panic!("Unexpected success"),
             ash::vk::Result::ErrorOutOfHostMemory => OutOfMemoryError::OutOfHostMemory.into(),
             ash::vk::Result::ErrorOutOfDeviceMemory => OutOfMemoryError::OutOfDeviceMemory.into(),
+            ash::vk::Result::ErrorTooManyObjects => AllocationError::TooManyObjects,
+            ash::vk::Result::ErrorDeviceLost => AllocationError::DeviceLost,
             _ => panic!("unexpected error"),
         }
     }
@@ -51,6 +68,7 @@ impl From<ash::vk::Result> for MemoryError {
             ash::vk::Result::ErrorOutOfHostMemory => OutOfMemoryError::OutOfHostMemory.into(),
             ash::vk::Result::ErrorOutOfDeviceMemory => OutOfMemoryError::OutOfDeviceMemory.into(),
             ash::vk::Result::ErrorMemoryMapFailed => MappingError::MappingFailed.into(),
+            ash::vk::Result::ErrorDeviceLost => AllocationError::DeviceLost.into(),
             _ => panic!("unexpected error"),
         }
     }
@@ -79,6 +97,35 @@ where
         )?)
     }
 
+    /// Chains `VkMemoryDedicatedAllocateInfo`.
+    /// Device must be created with `VK_KHR_dedicated_allocation` enabled or support Vulkan 1.1.
+    unsafe fn allocate_dedicated(
+        &self,
+        index: u32,
+        size: u64,
+        dedicated: Dedicated,
+    ) -> Result<ash::vk::DeviceMemory, AllocationError> {
+        let (image, buffer) = match dedicated {
+            Dedicated::Image(image) => (image, 0),
+            Dedicated::Buffer(buffer) => (0, buffer),
+        };
+        let dedicated_info = MemoryDedicatedAllocateInfo {
+            s_type: STRUCTURE_TYPE_MEMORY_DEDICATED_ALLOCATE_INFO,
+            p_next: null(),
+            image,
+            buffer,
+        };
+        Ok(self.allocate_memory(
+            &ash::vk::MemoryAllocateInfo {
+                s_type: ash::vk::StructureType::MemoryAllocateInfo,
+                p_next: &dedicated_info as *const _ as *const _,
+                allocation_size: size,
+                memory_type_index: index,
+            },
+            None,
+        )?)
+    }
+
     unsafe fn free(&self, memory: ash::vk::DeviceMemory) {
         self.free_memory(memory, None);
     }
@@ -3,6 +3,7 @@
 use hal::{self, Device as HalDevice};
 use std::{borrow::Borrow, marker::PhantomData, ops::Range, ptr::NonNull};
 
+use config::ConfigBuilder;
 use device::Device;
 use error::*;
 use heaps::*;
@@ -75,6 +76,8 @@ impl From<hal::memory::Properties> for Properties {
     }
 }
 
+/// `gfx-hal` doesn't support dedicated allocations.
+/// `Device::allocate_dedicated` allocates regular memory object.
 impl<D, B> Device for (D, PhantomData<B>)
 where
     B: hal::Backend,
@@ -133,17 +136,21 @@ where
 #[allow(unused)]
 unsafe fn heaps_from_physical_device<B>(
     physical: &B::PhysicalDevice,
-    config: Config,
+    config: &ConfigBuilder,
 ) -> Heaps<B::Memory>
 where
     B: hal::Backend,
 {
     let memory_properties = ::hal::PhysicalDevice::memory_properties(physical);
     Heaps::new(
-        memory_properties
-            .memory_types
-            .into_iter()
-            .map(|mt| (mt.properties.into(), mt.heap_index as u32, config)),
+        config.build(
+            memory_properties
+                .memory_types
+                .into_iter()
+                .map(|mt| (mt.properties.into(), mt.heap_index as u32)),
+            &memory_properties.memory_heaps,
+        ),
         memory_properties.memory_heaps,
+        ::hal::PhysicalDevice::limits(physical).non_coherent_atom_size as u64,
     )
 }
@@ -0,0 +1,114 @@
+use std::{fmt, sync::Arc};
+
+/// Tag attached to memory block on allocation.
+/// Allows to find out which subsystem leaks memory blocks.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Tag {
+    /// Static name.
+    Name(&'static str),
+
+    /// Caller-provided id.
+    Id(u64),
+
+    /// Name built at runtime. For example debug name of the resource.
+    Owned(Arc<str>),
+}
+
+impl From<&'static str> for Tag {
+    fn from(name: &'static str) -> Self {
+        Tag::Name(name)
+    }
+}
+
+impl From<String> for Tag {
+    fn from(name: String) -> Self {
+        Tag::Owned(name.into())
+    }
+}
+
+impl From<Arc<str>> for Tag {
+    fn from(name: Arc<str>) -> Self {
+        Tag::Owned(name)
+    }
+}
+
+impl From<u64> for Tag {
+    fn from(id: u64) -> Self {
+        Tag::Id(id)
+    }
+}
+
+impl fmt::Display for Tag {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Tag::Name(name) => write!(fmt, "{:?}", name),
+            Tag::Id(id) => write!(fmt, "#{}", id),
+            Tag::Owned(ref name) => write!(fmt, "{:?}", name),
+        }
+    }
+}
+
+/// Group of memory blocks not yet freed
+/// with same tag, memory type and size.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Leak {
+    /// Tag of the blocks.
+    pub tag: Option<Tag>,
+
+    /// Memory type of the blocks.
+    pub memory_type: u32,
+
+    /// Size of the blocks.
+    pub size: u64,
+
+    /// Number of blocks.
+    pub count: u64,
+}
+
+/// Report of memory blocks not yet freed.
+#[derive(Clone, Debug, Default)]
+pub struct LeakReport {
+    /// Groups of blocks not yet freed.
+    /// Sorted by tag, memory type and size.
+    pub leaks: Vec<Leak>,
+}
+
+impl LeakReport {
+    /// Check if there are no blocks left.
+    pub fn is_empty(&self) -> bool {
+        self.leaks.is_empty()
+    }
+
+    /// Total number of blocks.
+    pub fn count(&self) -> u64 {
+        self.leaks.iter().map(|leak| leak.count).sum()
+    }
+
+    /// Total size of blocks.
+    pub fn size(&self) -> u64 {
+        self.leaks.iter().map(|leak| leak.size * leak.count).sum()
+    }
+}
+
+impl fmt::Display for LeakReport {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            fmt,
+            "{} memory blocks of {} bytes total are not freed",
+            self.count(),
+            self.size()
+        )?;
+        for leak in &self.leaks {
+            write!(
+                fmt,
+                "  {} x {} bytes from memory type {}",
+                leak.count, leak.size, leak.memory_type
+            )?;
+            match leak.tag {
+                Some(ref tag) => writeln!(fmt, " tagged {}", tag)?,
+                None => writeln!(fmt, " untagged")?,
+            }
+        }
+        Ok(())
+    }
+}
@@ -38,28 +38,55 @@ extern crate smallvec;
 #[cfg(test)]
 extern crate rand;
 
+#[cfg(all(test, feature = "serde"))]
+extern crate ron;
+
+#[cfg(all(test, feature = "serde"))]
+extern crate toml;
+
 #[cfg(test)]
 mod test;
 
+mod aliasing;
 mod block;
+mod config;
 mod device;
 mod error;
 mod heaps;
 mod impls;
+mod leak;
 mod mapping;
 mod memory;
+mod priority;
+mod shared;
 mod util;
+mod utilization;
 
 pub mod allocator;
+#[cfg(any(test, feature = "mock"))]
+pub mod mock;
 pub mod usage;
 
+pub use aliasing::{AliasingGroup, AliasingPlan, AliasingRequest, Placement};
 pub use block::Block;
+pub use config::{ConfigBuilder, ConfigOverride};
 pub use device::Device;
 pub use error::{AllocationError, MappingError, MemoryError, OutOfMemoryError};
-pub use heaps::{Config, Heaps, MemoryBlock};
-pub use mapping::{write::Write, Coherent, MappedRange, MaybeCoherent, NonCoherent};
+pub use heaps::{
+    AllocationInfo, Config, Dedicated, Defragmentation, Eviction, Heaps, MemoryBlock, Relocation,
+};
+pub use leak::{Leak, LeakReport, Tag};
+pub use mapping::{
+    write::Write, Coherent, MappedBuffer, MappedRange, MaybeCoherent, NonCoherent, Pod,
+};
 pub use memory::{Memory, Properties};
+pub use priority::Priority;
+pub use shared::{CacheConfig, HeapsCache, SharedHeaps};
 pub use usage::Usage;
+pub use utilization::{
+    BlockCounts, MemoryHeapUtilization, MemoryTypeUtilization, MemoryUtilization,
+    PriorityUtilization, TotalMemoryUtilization,
+};
 
 #[cfg(feature = "gfx-hal")]
 extern crate gfx_hal as hal;
@@ -0,0 +1,236 @@
+use std::{
+    cmp::{max, min},
+    fmt::Debug,
+    marker::PhantomData,
+    mem::{align_of, size_of},
+    ops::Range,
+    ptr::NonNull,
+    slice::{from_raw_parts, from_raw_parts_mut},
+};
+
+use device::Device;
+use error::{MappingError, OutOfMemoryError};
+use mapping::{pod::Pod, MappedRange};
+use memory::Memory;
+
+/// Typed persistent mapping of the memory range.
+/// Built on `MappedRange` and provides safe access to the mapped elements.
+/// Doesn't borrow the block, so block can be used while mapping is alive.
+///
+/// Writes are tracked and flushed with single `Device::flush` call
+/// for non-coherent memory.
+/// Ranges passed to the device are aligned to `nonCoherentAtomSize`.
+///
+/// Host access is not synchronized with device.
+/// User must ensure that device doesn't access elements while host reads or writes them.
+#[derive(Debug)]
+pub struct MappedBuffer<T, M: 'static> {
+    /// Memory object that is mapped.
+    memory: *const Memory<M>,
+
+    /// Pointer to the first element.
+    ptr: NonNull<T>,
+
+    /// Range of mapped memory.
+    range: Range<u64>,
+
+    /// Number of elements.
+    len: usize,
+
+    /// Memory is coherent and doesn't require flushing or invalidating.
+    coherent: bool,
+
+    /// Sorted non-overlapping ranges written since last flush.
+    /// In memory object space.
+    dirty: Vec<Range<u64>>,
+
+    marker: PhantomData<*mut [T]>,
+}
+
+unsafe impl<T: Send, M: Sync> Send for MappedBuffer<T, M> {}
+unsafe impl<T: Sync, M: Sync> Sync for MappedBuffer<T, M> {}
+
+impl<T, M> MappedBuffer<T, M>
+where
+    T: Pod,
+    M: Debug + 'static,
+{
+    /// Wrap mapped range.
+    /// Only pointer to the memory object and the mapping are kept,
+    /// so block is not borrowed after `mapping` is consumed.
+    /// Trailing bytes that don't fit whole element are not accessible.
+    ///
+    /// # Safety
+    ///
+    /// Block `mapping` is created from must stay allocated and mapped while buffer is alive.
+    /// Mapped range must not be accessed through other mappings while buffer is alive.
+    pub unsafe fn new(mapping: MappedRange<'_, M>) -> Result<Self, MappingError> {
+        assert_ne!(size_of::<T>(), 0, "Zero-sized types can't be mapped");
+
+        let offset = mapping.ptr.as_ptr() as usize;
+        if offset % align_of::<T>() > 0 {
+            return Err(MappingError::Unaligned {
+                align: align_of::<T>(),
+                offset,
+            });
+        }
+
+        let size = (mapping.range.end - mapping.range.start) as usize;
+        Ok(MappedBuffer {
+            memory: mapping.memory,
+            ptr: mapping.ptr.cast(),
+            len: size / size_of::<T>(),
+            range: mapping.range,
+            coherent: mapping.coherent.0,
+            dirty: Vec::new(),
+            marker: PhantomData,
+        })
+    }
+
+    /// Number of elements.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if there are no elements.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Check if there are writes not yet flushed.
+    pub fn is_dirty(&self) -> bool {
+        !self.dirty.is_empty()
+    }
+
+    /// Get ranges written since last flush in memory object space.
+    /// Always empty for coherent memory.
+    pub fn dirty(&self) -> &[Range<u64>] {
+        &self.dirty
+    }
+
+    /// Get elements.
+    /// Call `invalidate` before reading elements written by device
+    /// to make them visible.
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+
+    /// Get mutable sub-slice of the elements.
+    /// Range is marked as written.
+    pub fn slice_mut(&mut self, range: Range<usize>) -> &mut [T] {
+        assert!(
+            range.start <= range.end && range.end <= self.len,
+            "Range {:?} is out of bounds 0..{}",
+            range,
+            self.len
+        );
+        self.mark_dirty(range.clone());
+        unsafe { from_raw_parts_mut(self.ptr.as_ptr().add(range.start), range.end - range.start) }
+    }
+
+    /// Write `data` starting from element `offset`.
+    pub fn write(&mut self, offset: usize, data: &[T]) {
+        let end = offset
+            .checked_add(data.len())
+            .expect("Range end must fit usize");
+        self.slice_mut(offset..end).copy_from_slice(data);
+    }
+
+    /// Flush all ranges written since last flush with single `Device::flush` call.
+    /// No-op for coherent memory.
+    pub fn flush<D>(&mut self, device: &D) -> Result<(), OutOfMemoryError>
+    where
+        D: Device<Memory = M>,
+    {
+        if self.dirty.is_empty() {
+            return Ok(());
+        }
+
+        let memory = self.memory().raw();
+        let regions = self.non_coherent_ranges(&self.dirty);
+        unsafe {
+            device.flush(regions.into_iter().map(|range| (memory, range)))?;
+        }
+        self.dirty.clear();
+        Ok(())
+    }
+
+    /// Invalidate ranges of elements with single `Device::invalidate` call
+    /// making device writes visible to the host.
+    /// No-op for coherent memory.
+    pub fn invalidate<D, I>(&mut self, device: &D, ranges: I) -> Result<(), OutOfMemoryError>
+    where
+        D: Device<Memory = M>,
+        I: IntoIterator<Item = Range<usize>>,
+    {
+        if self.coherent {
+            return Ok(());
+        }
+
+        let mut bytes = Vec::new();
+        for range in ranges {
+            assert!(
+                range.start <= range.end && range.end <= self.len,
+                "Range {:?} is out of bounds 0..{}",
+                range,
+                self.len
+            );
+            merge_range(&mut bytes, self.byte_range(range));
+        }
+
+        if bytes.is_empty() {
+            return Ok(());
+        }
+
+        let memory = self.memory().raw();
+        let regions = self.non_coherent_ranges(&bytes);
+        unsafe { device.invalidate(regions.into_iter().map(|range| (memory, range))) }
+    }
+
+    fn memory(&self) -> &Memory<M> {
+        // Memory object lives as long as the block it's mapped from.
+        unsafe { &*self.memory }
+    }
+
+    /// Get elements range in memory object space.
+    fn byte_range(&self, range: Range<usize>) -> Range<u64> {
+        let size = size_of::<T>() as u64;
+        self.range.start + range.start as u64 * size..self.range.start + range.end as u64 * size
+    }
+
+    /// Align ranges to `nonCoherentAtomSize` merging ranges that overlap after alignment.
+    fn non_coherent_ranges(&self, ranges: &[Range<u64>]) -> Vec<Range<u64>> {
+        let memory = self.memory();
+        let mut aligned = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            merge_range(&mut aligned, memory.non_coherent_range(range.clone()));
+        }
+        aligned
+    }
+
+    fn mark_dirty(&mut self, range: Range<usize>) {
+        if !self.coherent && range.start < range.end {
+            let range = self.byte_range(range);
+            merge_range(&mut self.dirty, range);
+        }
+    }
+}
+
+/// Insert `range` into sorted list of non-overlapping ranges
+/// merging it with overlapping and adjacent ones.
+fn merge_range(ranges: &mut Vec<Range<u64>>, mut range: Range<u64>) {
+    let first = ranges
+        .iter()
+        .position(|other| other.end >= range.start)
+        .unwrap_or(ranges.len());
+    let last = ranges[first..]
+        .iter()
+        .position(|other| other.start > range.end)
+        .map_or(ranges.len(), |index| first + index);
+
+    if first < last {
+        range.start = min(range.start, ranges[first].start);
+        range.end = max(range.end, ranges[last - 1].end);
+    }
+    ranges.splice(first..last, Some(range));
+}
@@ -1,3 +1,5 @@
+mod buffer;
+mod pod;
 mod range;
 pub(crate) mod write;
 
@@ -12,6 +14,7 @@ pub(crate) use self::range::{
     mapped_fitting_range, mapped_slice, mapped_slice_mut, mapped_sub_range,
 };
 use self::write::{Write, WriteFlush};
+pub use self::{buffer::MappedBuffer, pod::Pod};
 
 /// Non-coherent marker.
 #[derive(Clone, Copy, Debug)]
@@ -30,7 +33,7 @@ pub struct MaybeCoherent(bool);
 #[derive(Debug)]
 pub struct MappedRange<'a, T: 'static, C = MaybeCoherent> {
     /// Memory object that is mapped.
-    memory: &'a T,
+    memory: &'a Memory<T>,
 
     /// Pointer to range mapped memory.
     ptr: NonNull<u8>,
@@ -70,7 +73,7 @@ impl<'a, T: 'static> MappedRange<'a, T, MaybeCoherent> {
 
         let ptr = device.map(memory.raw(), range.clone())?;
         assert!(
-            (ptr.as_ptr() as usize).wrapping_neg() <= (range.end - range.start) as usize,
+            (ptr.as_ptr() as usize).wrapping_neg() >= (range.end - range.start) as usize,
             "Resulting pointer value + range length must fit in usize",
         );
 
@@ -82,7 +85,7 @@ impl<'a, T: 'static> MappedRange<'a, T, MaybeCoherent> {
         MappedRange {
             ptr,
             range,
-            memory: memory.raw(),
+            memory,
             coherent: MaybeCoherent(memory.host_coherent()),
         }
     }
@@ -99,6 +102,7 @@ impl<'a, T: 'static> MappedRange<'a, T, MaybeCoherent> {
 
     /// Fetch readable slice of sub-range to be read.
     /// Invalidating range if memory is not coherent.
+    /// Invalidated range is aligned to `nonCoherentAtomSize`.
     /// `range.end - range.start` must be multiple of `size_of::<T>()`.
     /// `mapping offset + range.start` must be multiple of `align_of::<T>()`.
     ///
@@ -120,8 +124,11 @@ impl<'a, T: 'static> MappedRange<'a, T, MaybeCoherent> {
         let (ptr, range) = mapped_sub_range(self.ptr, self.range.clone(), range)
             .ok_or_else(|| MappingError::OutOfBounds)?;
 
-        if self.coherent.0 {
-            device.invalidate(Some((self.memory, range.clone())))?;
+        if !self.coherent.0 {
+            device.invalidate(Some((
+                self.memory.raw(),
+                self.memory.non_coherent_range(range.clone()),
+            )))?;
         }
 
         let slice = mapped_slice::<U>(ptr, range)?;
@@ -129,7 +136,8 @@ impl<'a, T: 'static> MappedRange<'a, T, MaybeCoherent> {
     }
 
     /// Fetch writer to the sub-region.
-    /// This writer will flush data on drop if written at least once.
+    /// This writer will flush data on drop if memory is not coherent.
+    /// Flushed range is aligned to `nonCoherentAtomSize`.
     ///
     /// # Safety
     ///
@@ -148,16 +156,17 @@ impl<'a, T: 'static> MappedRange<'a, T, MaybeCoherent> {
         let (ptr, range) = mapped_sub_range(self.ptr, self.range.clone(), range)
             .ok_or_else(|| MappingError::OutOfBounds)?;
 
-        if self.coherent.0 {
-            device.invalidate(Some((self.memory, range.clone())))?;
+        let aligned = self.memory.non_coherent_range(range.clone());
+        if !self.coherent.0 {
+            device.invalidate(Some((self.memory.raw(), aligned.clone())))?;
         }
 
-        let slice = mapped_slice_mut::<U>(ptr, range.clone())?;
+        let slice = mapped_slice_mut::<U>(ptr, range)?;
 
         Ok(WriteFlush {
             slice,
-            flush: if self.coherent.0 {
-                Some((device, self.memory, range))
+            flush: if !self.coherent.0 {
+                Some((device, self.memory.raw(), aligned))
             } else {
                 None
             },
@@ -0,0 +1,30 @@
+/// Plain-old-data type.
+/// Any bit pattern is valid value of the type
+/// so it can be safely read from and written to mapped memory.
+///
+/// # Safety
+///
+/// Implementor must ensure that type has no padding bytes,
+/// has no invalid bit patterns
+/// and contains no pointers or references.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($type:ty),*) => {
+        $(
+            unsafe impl Pod for $type {}
+        )*
+    };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+macro_rules! impl_pod_array {
+    ($($size:expr),*) => {
+        $(
+            unsafe impl<T: Pod> Pod for [T; $size] {}
+        )*
+    };
+}
+
+impl_pod_array!(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 32, 64, 128, 256, 512, 1024);
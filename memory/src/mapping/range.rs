@@ -71,7 +71,10 @@ pub(crate) unsafe fn mapped_slice_mut<'a, U>(
         });
     }
 
-    Ok(from_raw_parts_mut(ptr.as_ptr() as *mut U, size))
+    Ok(from_raw_parts_mut(
+        ptr.as_ptr() as *mut U,
+        size / size_of::<U>(),
+    ))
 }
 
 /// # Safety
@@ -96,5 +99,8 @@ pub(crate) unsafe fn mapped_slice<'a, U>(
         });
     }
 
-    Ok(from_raw_parts(ptr.as_ptr() as *const U, size))
+    Ok(from_raw_parts(
+        ptr.as_ptr() as *const U,
+        size / size_of::<U>(),
+    ))
 }
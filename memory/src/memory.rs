@@ -1,5 +1,9 @@
+use std::ops::Range;
+
 use relevant::Relevant;
 
+use util::aligned;
+
 bitflags! {
     /// Memory property flags.
     /// Bitmask specifying properties for a memory type.
@@ -83,6 +87,7 @@ pub struct Memory<T> {
     raw: T,
     size: u64,
     properties: Properties,
+    non_coherent_atom_size: u64,
     relevant: Relevant,
 }
 
@@ -113,16 +118,40 @@ impl<T> Memory<T> {
         self.raw
     }
 
+    /// Get `nonCoherentAtomSize` of the device memory was allocated from.
+    /// Flushed and invalidated ranges of non-coherent memory are aligned to this value.
+    pub fn non_coherent_atom_size(&self) -> u64 {
+        self.non_coherent_atom_size
+    }
+
     /// Create memory from raw object.
-    pub unsafe fn from_raw(raw: T, size: u64, properties: Properties) -> Self {
+    /// `non_coherent_atom_size` must be power of two.
+    pub unsafe fn from_raw(
+        raw: T,
+        size: u64,
+        properties: Properties,
+        non_coherent_atom_size: u64,
+    ) -> Self {
+        debug_assert_eq!(non_coherent_atom_size.count_ones(), 1);
         Memory {
             properties,
             raw,
             size,
+            non_coherent_atom_size,
             relevant: Relevant,
         }
     }
 
+    /// Expand `range` to the smallest range that can be flushed or invalidated.
+    /// Range is aligned to `nonCoherentAtomSize` and clamped to memory size.
+    pub fn non_coherent_range(&self, range: Range<u64>) -> Range<u64> {
+        debug_assert!(range.start <= range.end && range.end <= self.size);
+        let atom = self.non_coherent_atom_size;
+        let start = range.start - range.start % atom;
+        let end = ::std::cmp::min(aligned(range.end, atom), self.size);
+        start..end
+    }
+
     /// Check if this memory is host-visible and can be mapped.
     /// `memory.host_visible()` is equivalent to `memory.properties().contains(Properties::HOST_VISIBLE)`
     pub fn host_visible(&self) -> bool {
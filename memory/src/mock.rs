@@ -0,0 +1,464 @@
+//! Mock device backed by host memory.
+//! Allows to test memory management without GPU.
+//! Faults can be injected into device operations
+//! to test how errors are handled.
+
+use std::{
+    alloc::{alloc_zeroed, dealloc, Layout},
+    collections::{HashMap, HashSet, VecDeque},
+    ops::Range,
+    ptr::NonNull,
+    sync::Mutex,
+};
+
+use device::Device;
+use error::*;
+use heaps::Dedicated;
+use util::*;
+
+/// Alignment of the host memory backing memory objects.
+pub const MOCK_MEMORY_ALIGN: usize = 256;
+
+/// Memory object allocated by `MockDevice`.
+/// Backed by zero-initialized host memory.
+/// Not `Copy` as it is consumed by `Device::free`.
+#[allow(missing_copy_implementations)]
+#[derive(Debug)]
+pub struct MockMemory {
+    id: u64,
+    memory_type: u32,
+    ptr: NonNull<u8>,
+    size: u64,
+}
+
+unsafe impl Send for MockMemory {}
+unsafe impl Sync for MockMemory {}
+
+impl MockMemory {
+    /// Unique identifier of the memory object.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    /// Memory type index memory object was allocated from.
+    pub fn memory_type(&self) -> u32 {
+        self.memory_type
+    }
+
+    /// Size of the memory object.
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Get content of the memory object.
+    ///
+    /// # Safety
+    ///
+    /// No writes to the memory object may happen until borrow ends.
+    pub unsafe fn content(&self) -> &[u8] {
+        ::std::slice::from_raw_parts(self.ptr.as_ptr(), self.size as usize)
+    }
+
+    fn layout(size: u64) -> Layout {
+        Layout::from_size_align(size as usize, MOCK_MEMORY_ALIGN).expect("Invalid layout")
+    }
+}
+
+impl PartialEq for MockMemory {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for MockMemory {}
+
+/// Operation of the `MockDevice` that can fail.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Operation {
+    /// `Device::allocate`.
+    Allocate,
+
+    /// `Device::map`.
+    Map,
+
+    /// `Device::flush`.
+    Flush,
+
+    /// `Device::invalidate`.
+    Invalidate,
+}
+
+/// Fault that can be injected into `MockDevice` operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fault {
+    /// Operation fails with `OutOfMemoryError::OutOfHostMemory`.
+    OutOfHostMemory,
+
+    /// Operation fails with `OutOfMemoryError::OutOfDeviceMemory`.
+    OutOfDeviceMemory,
+
+    /// Allocation fails with `AllocationError::TooManyObjects`.
+    TooManyObjects,
+
+    /// Mapping fails with `MappingError::MappingFailed`.
+    MappingFailed,
+
+    /// Allocation fails with `AllocationError::DeviceLost`
+    /// and mapping fails with `MappingError::DeviceLost`.
+    DeviceLost,
+}
+
+impl Fault {
+    fn applicable(&self, operation: Operation) -> bool {
+        match (*self, operation) {
+            (Fault::OutOfHostMemory, _) | (Fault::OutOfDeviceMemory, _) => true,
+            (Fault::TooManyObjects, Operation::Allocate) => true,
+            (Fault::MappingFailed, Operation::Map) => true,
+            (Fault::DeviceLost, Operation::Allocate) | (Fault::DeviceLost, Operation::Map) => true,
+            _ => false,
+        }
+    }
+
+    fn out_of_memory(&self) -> OutOfMemoryError {
+        match *self {
+            Fault::OutOfHostMemory => OutOfMemoryError::OutOfHostMemory,
+            Fault::OutOfDeviceMemory => OutOfMemoryError::OutOfDeviceMemory,
+            _ => unreachable!(),
+        }
+    }
+
+    fn allocation_error(&self) -> AllocationError {
+        match *self {
+            Fault::TooManyObjects => AllocationError::TooManyObjects,
+            Fault::DeviceLost => AllocationError::DeviceLost,
+            _ => self.out_of_memory().into(),
+        }
+    }
+
+    fn mapping_error(&self) -> MappingError {
+        match *self {
+            Fault::MappingFailed => MappingError::MappingFailed,
+            Fault::DeviceLost => MappingError::DeviceLost,
+            _ => self.out_of_memory().into(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next: u64,
+    live: HashMap<u64, u64>,
+    dedicated: HashMap<u64, Dedicated>,
+    mapped: HashSet<u64>,
+    calls: HashMap<Operation, u64>,
+    scripted: HashMap<Operation, VecDeque<(u64, Fault)>>,
+    persistent: HashMap<Operation, Fault>,
+    non_coherent_atom_size: u64,
+    flushed: Vec<(u64, Range<u64>)>,
+    invalidated: Vec<(u64, Range<u64>)>,
+}
+
+impl State {
+    /// Count call and find fault injected into it.
+    fn call(&mut self, operation: Operation) -> Option<Fault> {
+        let call = {
+            let calls = self.calls.entry(operation).or_insert(0);
+            *calls += 1;
+            *calls
+        };
+
+        let scripted = self.scripted.get_mut(&operation).and_then(|scripted| {
+            match scripted.front() {
+                Some(&(nth, _)) if nth == call => {}
+                _ => return None,
+            }
+            scripted.pop_front().map(|(_, fault)| fault)
+        });
+
+        scripted.or_else(|| self.persistent.get(&operation).cloned())
+    }
+
+    /// Check that flushed or invalidated range is within bounds and aligned to atom size.
+    fn check_range(&self, memory: &MockMemory, range: &Range<u64>) {
+        assert!(
+            range.start <= range.end && range.end <= memory.size,
+            "Range {:?} is out of memory object bounds 0..{}",
+            range,
+            memory.size
+        );
+        let atom = self.non_coherent_atom_size;
+        if atom > 1 {
+            assert_eq!(
+                range.start % atom,
+                0,
+                "Range {:?} start is not aligned to atom size {}",
+                range,
+                atom
+            );
+            assert!(
+                range.end % atom == 0 || range.end == memory.size,
+                "Range {:?} end is not aligned to atom size {} nor equal to memory object size {}",
+                range,
+                atom,
+                memory.size
+            );
+        }
+    }
+}
+
+/// Device that allocates memory objects from host memory.
+///
+/// Memory objects are zero-initialized and always mappable,
+/// so content written through mapping can be checked byte-for-byte.
+/// Misuse (mapping memory twice, unmapping or freeing foreign memory, mapping out of bounds,
+/// flushing or invalidating ranges not aligned to `nonCoherentAtomSize`)
+/// results in panic.
+#[derive(Debug, Default)]
+pub struct MockDevice {
+    state: Mutex<State>,
+}
+
+impl MockDevice {
+    /// Create new mock device.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create new mock device that requires flushed and invalidated ranges
+    /// to be aligned to `non_coherent_atom_size`.
+    pub fn with_non_coherent_atom_size(non_coherent_atom_size: u64) -> Self {
+        assert_eq!(
+            non_coherent_atom_size.count_ones(),
+            1,
+            "Non-coherent atom size must be power of two"
+        );
+        let device = Self::default();
+        device.state().non_coherent_atom_size = non_coherent_atom_size;
+        device
+    }
+
+    /// Inject `fault` into `nth` call of the `operation` counting from this moment.
+    /// `1` is the next call.
+    pub fn fail_nth(&self, operation: Operation, nth: u64, fault: Fault) {
+        assert_ne!(nth, 0, "Calls are counted from 1");
+        assert!(
+            fault.applicable(operation),
+            "{:?} can't be injected into {:?}",
+            fault,
+            operation
+        );
+        let mut state = self.state();
+        let call = state.calls.get(&operation).cloned().unwrap_or(0) + nth;
+        let ref mut scripted = state
+            .scripted
+            .entry(operation)
+            .or_insert_with(VecDeque::new);
+        let index = scripted
+            .iter()
+            .position(|&(other, _)| other >= call)
+            .unwrap_or(scripted.len());
+        if scripted
+            .get(index)
+            .map_or(false, |&(other, _)| other == call)
+        {
+            scripted[index] = (call, fault);
+        } else {
+            scripted.insert(index, (call, fault));
+        }
+    }
+
+    /// Inject `fault` into all following calls of the `operation`
+    /// until `clear_faults` is called.
+    /// Can be used to emulate device that stopped responding.
+    /// See also `lose`.
+    pub fn fail_all(&self, operation: Operation, fault: Fault) {
+        assert!(
+            fault.applicable(operation),
+            "{:?} can't be injected into {:?}",
+            fault,
+            operation
+        );
+        self.state().persistent.insert(operation, fault);
+    }
+
+    /// Emulate lost device.
+    /// All following allocations and mappings fail with `Fault::DeviceLost`
+    /// until `clear_faults` is called.
+    pub fn lose(&self) {
+        self.fail_all(Operation::Allocate, Fault::DeviceLost);
+        self.fail_all(Operation::Map, Fault::DeviceLost);
+    }
+
+    /// Remove all injected faults.
+    pub fn clear_faults(&self) {
+        let mut state = self.state();
+        state.scripted.clear();
+        state.persistent.clear();
+    }
+
+    /// Number of calls of the `operation` performed so far.
+    /// Failed calls are counted too.
+    pub fn calls(&self, operation: Operation) -> u64 {
+        self.state().calls.get(&operation).cloned().unwrap_or(0)
+    }
+
+    /// Number of memory objects allocated and not yet freed.
+    pub fn allocated(&self) -> usize {
+        self.state().live.len()
+    }
+
+    /// Total size of memory objects allocated and not yet freed.
+    pub fn allocated_bytes(&self) -> u64 {
+        self.state().live.values().sum()
+    }
+
+    /// Resources live memory objects are dedicated to.
+    pub fn dedicated(&self) -> Vec<Dedicated> {
+        self.state().dedicated.values().cloned().collect()
+    }
+
+    /// Number of memory objects currently mapped.
+    pub fn mapped(&self) -> usize {
+        self.state().mapped.len()
+    }
+
+    /// Ids of memory objects and ranges flushed so far, in call order.
+    pub fn flushed(&self) -> Vec<(u64, Range<u64>)> {
+        self.state().flushed.clone()
+    }
+
+    /// Ids of memory objects and ranges invalidated so far, in call order.
+    pub fn invalidated(&self) -> Vec<(u64, Range<u64>)> {
+        self.state().invalidated.clone()
+    }
+
+    fn state(&self) -> ::std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("Mock device state is poisoned")
+    }
+}
+
+impl Device for MockDevice {
+    type Memory = MockMemory;
+
+    unsafe fn allocate(&self, index: u32, size: u64) -> Result<MockMemory, AllocationError> {
+        assert_ne!(size, 0, "Memory object size can't be 0");
+        assert!(fits_usize(size), "Memory object size must fit usize");
+
+        let mut state = self.state();
+        if let Some(fault) = state.call(Operation::Allocate) {
+            return Err(fault.allocation_error());
+        }
+
+        let ptr = NonNull::new(alloc_zeroed(MockMemory::layout(size)))
+            .ok_or(OutOfMemoryError::OutOfHostMemory)?;
+
+        let id = state.next;
+        state.next += 1;
+        state.live.insert(id, size);
+
+        Ok(MockMemory {
+            id,
+            memory_type: index,
+            ptr,
+            size,
+        })
+    }
+
+    unsafe fn allocate_dedicated(
+        &self,
+        index: u32,
+        size: u64,
+        dedicated: Dedicated,
+    ) -> Result<MockMemory, AllocationError> {
+        let memory = self.allocate(index, size)?;
+        self.state().dedicated.insert(memory.id, dedicated);
+        Ok(memory)
+    }
+
+    unsafe fn free(&self, memory: MockMemory) {
+        let mut state = self.state();
+        assert!(
+            state.live.remove(&memory.id).is_some(),
+            "Memory object is not allocated by this device"
+        );
+        state.dedicated.remove(&memory.id);
+        // Freeing memory implicitly unmaps it.
+        state.mapped.remove(&memory.id);
+        dealloc(memory.ptr.as_ptr(), MockMemory::layout(memory.size));
+    }
+
+    unsafe fn map(
+        &self,
+        memory: &MockMemory,
+        range: Range<u64>,
+    ) -> Result<NonNull<u8>, MappingError> {
+        let mut state = self.state();
+        assert!(
+            state.live.contains_key(&memory.id),
+            "Memory object is not allocated by this device"
+        );
+        assert!(
+            range.start <= range.end && range.end <= memory.size,
+            "Mapping range {:?} is out of memory object bounds 0..{}",
+            range,
+            memory.size
+        );
+
+        if let Some(fault) = state.call(Operation::Map) {
+            return Err(fault.mapping_error());
+        }
+
+        assert!(
+            state.mapped.insert(memory.id),
+            "Memory object is already mapped"
+        );
+        Ok(NonNull::new_unchecked(
+            memory.ptr.as_ptr().add(range.start as usize),
+        ))
+    }
+
+    unsafe fn unmap(&self, memory: &MockMemory) {
+        assert!(
+            self.state().mapped.remove(&memory.id),
+            "Memory object is not mapped"
+        );
+    }
+
+    unsafe fn invalidate<'a>(
+        &self,
+        regions: impl IntoIterator<Item = (&'a MockMemory, Range<u64>)>,
+    ) -> Result<(), OutOfMemoryError> {
+        let mut state = self.state();
+        for (memory, range) in regions {
+            assert!(
+                state.mapped.contains(&memory.id),
+                "Invalidated memory object must be mapped"
+            );
+            state.check_range(memory, &range);
+            state.invalidated.push((memory.id, range));
+        }
+        match state.call(Operation::Invalidate) {
+            Some(fault) => Err(fault.out_of_memory()),
+            None => Ok(()),
+        }
+    }
+
+    unsafe fn flush<'a>(
+        &self,
+        regions: impl IntoIterator<Item = (&'a MockMemory, Range<u64>)>,
+    ) -> Result<(), OutOfMemoryError> {
+        let mut state = self.state();
+        for (memory, range) in regions {
+            assert!(
+                state.mapped.contains(&memory.id),
+                "Flushed memory object must be mapped"
+            );
+            state.check_range(memory, &range);
+            state.flushed.push((memory.id, range));
+        }
+        match state.call(Operation::Flush) {
+            Some(fault) => Err(fault.out_of_memory()),
+            None => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,25 @@
+/// Priority of memory block.
+/// Blocks with low priority, like shadow maps and streaming caches,
+/// can tolerate being demoted or evicted under memory pressure.
+///
+/// Maps to `VK_EXT_memory_priority` value as `priority / 255`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Priority(pub u8);
+
+impl Priority {
+    /// Lowest priority.
+    pub const LOW: Priority = Priority(0);
+
+    /// Priority of blocks allocated without explicit priority.
+    pub const NORMAL: Priority = Priority(128);
+
+    /// Highest priority.
+    pub const HIGH: Priority = Priority(255);
+}
+
+impl Default for Priority {
+    fn default() -> Self {
+        Priority::NORMAL
+    }
+}
@@ -0,0 +1,298 @@
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{Mutex, MutexGuard},
+};
+
+use relevant::Relevant;
+use smallvec::SmallVec;
+
+use block::Block;
+use device::Device;
+use error::*;
+use heaps::{non_coherent_block, AllocationInfo, Heaps, MemoryBlock};
+use memory::*;
+use priority::Priority;
+use usage::{Usage, UsageValue};
+use util::*;
+use utilization::TotalMemoryUtilization;
+
+/// Config for per-thread caches of `SharedHeaps`.
+#[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CacheConfig {
+    /// Number of blocks allocated from shared `Heaps` at once
+    /// when cache has no block of requested size.
+    /// Blocks beyond requested one are allocated only while heap has at least half of its budget available.
+    pub refill: u32,
+
+    /// Maximum number of free blocks of each size kept in cache.
+    /// Blocks freed beyond this limit are returned to shared `Heaps`.
+    pub max_cached: u32,
+}
+
+/// Thread-safe wrapper around `Heaps`.
+/// Threads allocate through `HeapsCache` instances
+/// that keep blocks of `DynamicAllocator` and lock `Heaps` only to get more blocks or return them.
+/// Other allocations lock `Heaps` for each allocation.
+#[derive(Debug)]
+pub struct SharedHeaps<T> {
+    heaps: Mutex<Heaps<T>>,
+    types: Vec<(Properties, Option<(u64, u64)>)>,
+    non_coherent_atom_size: u64,
+    config: CacheConfig,
+}
+
+impl<T: 'static> SharedHeaps<T> {
+    /// Wrap `Heaps` to share it between threads.
+    pub fn new(heaps: Heaps<T>, config: CacheConfig) -> Self {
+        assert_ne!(config.refill, 0, "Cache refill can't be 0");
+        SharedHeaps {
+            types: heaps.memory_types().collect(),
+            non_coherent_atom_size: heaps.non_coherent_atom_size(),
+            heaps: Mutex::new(heaps),
+            config,
+        }
+    }
+
+    /// Create new cache.
+    /// Cache is intended to be used by single thread.
+    pub fn cache(&self) -> HeapsCache<'_, T> {
+        HeapsCache {
+            shared: self,
+            blocks: HashMap::new(),
+            relevant: Relevant,
+        }
+    }
+
+    /// Lock `Heaps`.
+    pub fn lock(&self) -> MutexGuard<'_, Heaps<T>> {
+        self.heaps.lock().expect("Heaps mutex is poisoned")
+    }
+
+    /// Allocate memory block bypassing caches.
+    /// See `Heaps::allocate`.
+    pub fn allocate<D, U>(
+        &self,
+        device: &D,
+        mask: u32,
+        usage: U,
+        size: u64,
+        align: u64,
+    ) -> Result<MemoryBlock<T>, MemoryError>
+    where
+        D: Device<Memory = T>,
+        U: Usage,
+    {
+        self.lock().allocate(device, mask, usage, size, align)
+    }
+
+    /// Free memory block bypassing caches.
+    /// See `Heaps::free`.
+    pub fn free<D>(&self, device: &D, block: MemoryBlock<T>)
+    where
+        D: Device<Memory = T>,
+    {
+        self.lock().free(device, block)
+    }
+
+    /// Get memory utilization.
+    /// Blocks kept in caches are counted as used.
+    pub fn utilization(&self) -> TotalMemoryUtilization {
+        self.lock().utilization()
+    }
+
+    /// Unwrap `Heaps`.
+    /// All caches must be disposed before.
+    pub fn into_inner(self) -> Heaps<T> {
+        self.heaps.into_inner().expect("Heaps mutex is poisoned")
+    }
+
+    /// Dispose of allocator.
+    /// All caches must be disposed before.
+    pub fn dispose<D>(self, device: &D)
+    where
+        D: Device<Memory = T>,
+    {
+        self.into_inner().dispose(device)
+    }
+
+    /// Find memory type and block size for request that can be served from cache.
+    fn cached_block_size(
+        &self,
+        mask: u32,
+        usage: UsageValue,
+        size: u64,
+        align: u64,
+    ) -> Option<(u32, u64)> {
+        match usage {
+            UsageValue::Data | UsageValue::Dynamic => {}
+            _ => return None,
+        }
+
+        let (memory_index, _, (granularity, max_block_size)) = self
+            .types
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| (mask & (1u32 << index)) != 0)
+            .filter_map(|(index, &(properties, dynamic))| {
+                usage
+                    .memory_fitness(properties)
+                    .map(move |fitness| (index, fitness, dynamic))
+            }).collect::<SmallVec<[_; 64]>>()
+            .into_iter()
+            .max_by_key(|&(_, fitness, _)| fitness)
+            .and_then(|(index, fitness, dynamic)| {
+                dynamic.map(|dynamic| (index, fitness, dynamic))
+            })?;
+
+        // Same rounding as in `Heaps` and `DynamicAllocator`.
+        let (size, align) = non_coherent_block(
+            self.types[memory_index].0,
+            self.non_coherent_atom_size,
+            size,
+            align,
+        );
+        let size = ::std::cmp::max(size, align);
+        let block_size = ((size + granularity - 1) / granularity) * granularity;
+        if block_size > max_block_size {
+            return None;
+        }
+
+        debug_assert!(fits_u32(memory_index));
+        Some((memory_index as u32, block_size))
+    }
+}
+
+/// Per-thread cache of memory blocks allocated from `SharedHeaps`.
+/// Blocks can be freed through any cache created from the same `SharedHeaps`.
+/// Cache must be disposed with `HeapsCache::dispose` to return blocks to `SharedHeaps`.
+#[derive(Derivative)]
+#[derivative(Debug(bound = "T: Debug"))]
+pub struct HeapsCache<'a, T: 'a> {
+    shared: &'a SharedHeaps<T>,
+    blocks: HashMap<(u32, u64), Vec<MemoryBlock<T>>>,
+    #[derivative(Debug = "ignore")]
+    relevant: Relevant,
+}
+
+impl<'a, T: 'static> HeapsCache<'a, T> {
+    /// Allocate memory block
+    /// from one of memory types specified by `mask`,
+    /// for intended `usage`,
+    /// with `size`
+    /// and `align` requirements.
+    /// Shared `Heaps` are locked only if there is no suitable block in cache.
+    pub fn allocate<D, U>(
+        &mut self,
+        device: &D,
+        mask: u32,
+        usage: U,
+        size: u64,
+        align: u64,
+    ) -> Result<MemoryBlock<T>, MemoryError>
+    where
+        D: Device<Memory = T>,
+        U: Usage,
+    {
+        let usage = usage.value();
+        let (memory_index, block_size) =
+            match self.shared.cached_block_size(mask, usage, size, align) {
+                Some(key) => key,
+                None => return self.shared.allocate(device, mask, usage, size, align),
+            };
+
+        let ref mut cached = self
+            .blocks
+            .entry((memory_index, block_size))
+            .or_insert_with(Vec::new);
+
+        if let Some(block) = cached.pop() {
+            return Ok(block);
+        }
+
+        // Requested block is allocated as usual
+        // so that memory type preferences, budgets and eviction apply.
+        let mut heaps = self.shared.lock();
+        let block = heaps.allocate(device, mask, usage, size, align)?;
+        let range = block.range();
+        if block.memory_type() != memory_index
+            || !block.is_dynamic()
+            || range.end - range.start != block_size
+        {
+            return Ok(block);
+        }
+
+        // Extra blocks are allocated only while heap has at least half of its budget available
+        // so that blocks nobody uses yet can't exhaust it.
+        let info = AllocationInfo::default();
+        for _ in 1..self.shared.config.refill {
+            let (available, budget) = heaps.type_budget(memory_index);
+            if available < budget / 2 + block_size {
+                break;
+            }
+            match heaps.allocate_from(device, memory_index, usage, block_size, 1, &info) {
+                Ok(block) => {
+                    debug_assert!(block.is_dynamic());
+                    cached.push(block);
+                }
+                Err(_) => break,
+            }
+        }
+
+        Ok(block)
+    }
+
+    /// Free memory block.
+    /// Block may be allocated by any cache of the same `SharedHeaps`.
+    /// Shared `Heaps` are locked only if block can't be kept in cache.
+    /// Tagged and pinned blocks and blocks with priority other than `Priority::NORMAL`
+    /// are never kept in cache.
+    pub fn free<D>(&mut self, device: &D, block: MemoryBlock<T>)
+    where
+        D: Device<Memory = T>,
+    {
+        if !block.is_dynamic()
+            || block.tag().is_some()
+            || block.pinned()
+            || block.priority() != Priority::NORMAL
+        {
+            return self.shared.free(device, block);
+        }
+
+        let range = block.range();
+        let ref mut cached = self
+            .blocks
+            .entry((block.memory_type(), range.end - range.start))
+            .or_insert_with(Vec::new);
+
+        if cached.len() < self.shared.config.max_cached as usize {
+            cached.push(block);
+        } else {
+            self.shared.free(device, block);
+        }
+    }
+
+    /// Return all cached blocks to shared `Heaps`.
+    pub fn flush<D>(&mut self, device: &D)
+    where
+        D: Device<Memory = T>,
+    {
+        let mut heaps = self.shared.lock();
+        for (_, blocks) in self.blocks.drain() {
+            for block in blocks {
+                heaps.free(device, block);
+            }
+        }
+    }
+
+    /// Dispose of cache.
+    /// Returns all cached blocks to shared `Heaps`.
+    pub fn dispose<D>(mut self, device: &D)
+    where
+        D: Device<Memory = T>,
+    {
+        self.flush(device);
+        self.relevant.dispose();
+    }
+}
@@ -0,0 +1,163 @@
+use aliasing::{AliasingPlan, AliasingRequest, Placement};
+
+fn request(lifetime: ::std::ops::Range<u64>, size: u64, align: u64, mask: u32) -> AliasingRequest {
+    AliasingRequest {
+        lifetime,
+        size,
+        align,
+        mask,
+        linear: false,
+    }
+}
+
+#[test]
+fn aliasing_disjoint_test() {
+    // Chain of passes where each resource lives for two passes.
+    let requests = vec![
+        request(0..2, 1024, 256, 0b11),
+        request(1..3, 512, 256, 0b11),
+        request(2..4, 1024, 256, 0b01),
+        request(3..5, 256, 256, 0b11),
+    ];
+    let plan = AliasingPlan::new(&requests, 1);
+
+    assert_eq!(plan.groups.len(), 1);
+    assert_eq!(plan.groups[0].mask, 0b01);
+    assert_eq!(plan.groups[0].align, 256);
+
+    // Disjoint lifetimes share memory.
+    assert_eq!(plan.placements[0].offset, 0);
+    assert_eq!(plan.placements[2].offset, 0);
+    assert_eq!(plan.placements[1].offset, 1024);
+    assert_eq!(plan.placements[3].offset, 1024);
+    assert_eq!(plan.size(), 1536);
+    check(&requests, &plan, 1);
+}
+
+#[test]
+fn aliasing_groups_test() {
+    let requests = vec![
+        request(0..4, 4096, 4096, 0b001),
+        request(0..4, 100, 4, 0b110),
+        request(4..8, 4096, 1024, 0b011),
+        request(2..6, 64, 64, 0b100),
+    ];
+    let plan = AliasingPlan::new(&requests, 1);
+
+    // Incompatible memory types get separate blocks.
+    assert_eq!(plan.groups.len(), 2);
+    assert_eq!(
+        plan.placements[2],
+        Placement {
+            group: 0,
+            offset: 0,
+        }
+    );
+    assert_eq!(plan.placements[1].group, 1);
+    assert_eq!(plan.placements[3].group, 1);
+
+    // Overlapping lifetimes respect alignment.
+    assert_eq!(plan.placements[3].offset, 128);
+    assert_eq!(plan.groups[1].size, 192);
+    check(&requests, &plan, 1);
+}
+
+#[test]
+fn aliasing_random_test() {
+    use rand::{thread_rng, Rng};
+
+    let mut rng = thread_rng();
+    for _ in 0..16 {
+        let requests = (0..32)
+            .map(|_| {
+                let start = rng.gen_range(0, 16);
+                let end = start + rng.gen_range(1, 8);
+                let size = rng.gen_range(1, 1024 * 1024);
+                let align = 1 << rng.gen_range(0, 16);
+                let mask = rng.gen_range(1, 8);
+                AliasingRequest {
+                    linear: rng.gen(),
+                    ..request(start..end, size, align, mask)
+                }
+            }).collect::<Vec<_>>();
+        let granularity = 1 << rng.gen_range(0, 16);
+        let plan = AliasingPlan::new(&requests, granularity);
+        check(&requests, &plan, granularity);
+
+        // Never worse than placing all resources sequentially.
+        let total: u64 = requests
+            .iter()
+            .map(|request| request.size + request.align + granularity)
+            .sum();
+        assert!(plan.size() <= total);
+    }
+}
+
+#[test]
+fn aliasing_granularity_test() {
+    let image = request(0..2, 1000, 256, 0b1);
+    let buffer = AliasingRequest {
+        linear: true,
+        ..request(0..2, 512, 256, 0b1)
+    };
+    let later = request(2..4, 512, 256, 0b1);
+
+    // Without granularity buffer is placed right after the image.
+    let requests = vec![image.clone(), buffer.clone()];
+    let plan = AliasingPlan::new(&requests, 1);
+    assert_eq!(plan.placements[1].offset, 1024);
+    assert_eq!(plan.groups[0].align, 256);
+
+    // Buffer is moved to the next page.
+    let plan = AliasingPlan::new(&requests, 4096);
+    assert_eq!(plan.placements[1].offset, 4096);
+    assert_eq!(plan.groups[0].align, 4096);
+    check(&requests, &plan, 4096);
+
+    // Resources with disjoint lifetimes aren't padded.
+    let requests = vec![image, buffer, later];
+    let plan = AliasingPlan::new(&requests, 4096);
+    assert_eq!(plan.placements[2].offset, 0);
+    check(&requests, &plan, 4096);
+}
+
+/// Check that plan is valid.
+fn check(requests: &[AliasingRequest], plan: &AliasingPlan, granularity: u64) {
+    for (index, request) in requests.iter().enumerate() {
+        let placement = plan.placements[index];
+        let ref group = plan.groups[placement.group];
+        assert!(group.requests.contains(&index));
+        assert_eq!(group.mask & request.mask, group.mask);
+        assert_eq!(placement.offset % request.align, 0);
+        assert_eq!(group.align % request.align, 0);
+        assert!(placement.offset + request.size <= group.size);
+
+        for (other_index, other) in requests.iter().enumerate().skip(index + 1) {
+            let other_placement = plan.placements[other_index];
+            if other_placement.group != placement.group
+                || other.lifetime.start >= request.lifetime.end
+                || request.lifetime.start >= other.lifetime.end
+            {
+                continue;
+            }
+            assert!(
+                placement.offset + request.size <= other_placement.offset
+                    || other_placement.offset + other.size <= placement.offset,
+                "Resources {} and {} alive at the same time overlap",
+                index,
+                other_index
+            );
+            if request.linear != other.linear {
+                let page = |offset: u64| offset / granularity;
+                assert!(
+                    page(placement.offset + request.size - 1) < page(other_placement.offset)
+                        || page(other_placement.offset + other.size - 1) < page(placement.offset),
+                    "Resources {} and {} share a page",
+                    index,
+                    other_index
+                );
+                assert_eq!(group.align % granularity, 0);
+            }
+        }
+    }
+}
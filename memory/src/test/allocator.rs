@@ -1,69 +1,25 @@
 #![allow(dead_code)]
 
-use std::{cell::RefCell, collections::HashSet, ops::Range, ptr::NonNull};
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+};
 
 use rand;
 use veclist::VecList;
 
-use allocator::{ArenaConfig, DynamicConfig};
+use allocator::{ArenaConfig, BuddyConfig, DynamicConfig, RingConfig};
 use block::Block;
-use device::Device;
-use error::{AllocationError, MappingError, MemoryError, OutOfMemoryError};
-use heaps::{Config, Heaps, MemoryBlock};
+use error::{MemoryError, OutOfMemoryError};
+use heaps::{AllocationInfo, Config, Dedicated, Heaps, MemoryBlock};
+use leak::{Leak, Tag};
 use memory::Properties;
+use mock::{MockDevice, MockMemory};
+use priority::Priority;
+use shared::{CacheConfig, SharedHeaps};
 use usage::*;
 
-struct Inner {
-    freed: HashSet<u64>,
-    next: u64,
-}
-
-struct MockDevice(RefCell<Inner>);
-
-impl MockDevice {
-    fn new() -> Self {
-        MockDevice(RefCell::new(Inner {
-            freed: HashSet::new(),
-            next: 0,
-        }))
-    }
-}
-
-impl Device for MockDevice {
-    type Memory = u64;
-
-    unsafe fn allocate(&self, _index: u32, _size: u64) -> Result<u64, AllocationError> {
-        let mut inner = self.0.borrow_mut();
-        let id = inner.next;
-        inner.next = id + 1;
-        Ok(id)
-    }
-
-    unsafe fn free(&self, memory: u64) {
-        assert!(self.0.borrow_mut().freed.insert(memory), "Double-free");
-    }
-
-    unsafe fn map(&self, _memory: &u64, _range: Range<u64>) -> Result<NonNull<u8>, MappingError> {
-        Ok(NonNull::dangling())
-    }
-
-    unsafe fn unmap(&self, _memory: &u64) {}
-
-    unsafe fn invalidate<'a>(
-        &self,
-        _regions: impl IntoIterator<Item = (&'a u64, Range<u64>)>,
-    ) -> Result<(), OutOfMemoryError> {
-        unimplemented!()
-    }
-    unsafe fn flush<'a>(
-        &self,
-        _regions: impl IntoIterator<Item = (&'a u64, Range<u64>)>,
-    ) -> Result<(), OutOfMemoryError> {
-        unimplemented!()
-    }
-}
-
-fn init() -> Heaps<u64> {
+fn init() -> Heaps<MockMemory> {
     let arena_config = ArenaConfig {
         arena_size: 32 * 1024,
     };
@@ -77,6 +33,13 @@ fn init() -> Heaps<u64> {
         block_size_granularity: 32,
         max_block_size: 1024,
     };
+    let buddy_config = BuddyConfig {
+        chunk_size: 4 * 1024 * 1024,
+        min_block_size: 64 * 1024,
+    };
+    let ring_config = RingConfig {
+        ring_size: 64 * 1024,
+    };
 
     unsafe {
         Heaps::new(
@@ -87,6 +50,8 @@ fn init() -> Heaps<u64> {
                     Config {
                         arena: None,
                         dynamic: Some(dynamic_config),
+                        buddy: Some(buddy_config),
+                        ring: None,
                     },
                 ),
                 (
@@ -95,6 +60,8 @@ fn init() -> Heaps<u64> {
                     Config {
                         arena: None,
                         dynamic: Some(small_dynamic_config),
+                        buddy: None,
+                        ring: None,
                     },
                 ),
                 (
@@ -103,6 +70,8 @@ fn init() -> Heaps<u64> {
                     Config {
                         arena: Some(arena_config),
                         dynamic: Some(dynamic_config),
+                        buddy: None,
+                        ring: Some(ring_config),
                     },
                 ),
                 (
@@ -111,10 +80,13 @@ fn init() -> Heaps<u64> {
                     Config {
                         arena: Some(arena_config),
                         dynamic: Some(dynamic_config),
+                        buddy: None,
+                        ring: Some(ring_config),
                     },
                 ),
             ],
             vec![16 * 1024 * 1024, 1 * 1024 * 1024, 32 * 1024 * 1024],
+            1,
         )
     }
 }
@@ -158,9 +130,9 @@ impl Allocation {
 
     fn allocate(
         &self,
-        heaps: &mut Heaps<u64>,
+        heaps: &mut Heaps<MockMemory>,
         device: &MockDevice,
-    ) -> Result<MemoryBlock<u64>, MemoryError> {
+    ) -> Result<MemoryBlock<MockMemory>, MemoryError> {
         let block = heaps.allocate(device, self.mask, self.usage, self.size, self.align)?;
 
         assert!(block.range().end - block.range().start >= self.size);
@@ -226,3 +198,695 @@ fn blocks_test() {
     println!("Dropping Heaps");
     heaps.dispose(device);
 }
+
+#[test]
+fn buddy_test() {
+    let mut heaps = init();
+    let ref device = MockDevice::new();
+    let mut blocks = Vec::new();
+
+    // Sizes above dynamic allocator limit and below buddy allocator limit.
+    for i in 0..32u64 {
+        let size = 33 * 1024 + i * 7 * 1024;
+        let block = heaps
+            .allocate(device, 1, Data, size, 256)
+            .expect("Allocation must succeed");
+        assert!(block.range().end - block.range().start >= size);
+        assert_eq!(block.range().start % 256, 0);
+        blocks.push(block);
+    }
+
+    // All blocks must be sub-allocated from few memory objects.
+    assert!(device.allocated() < blocks.len() / 4);
+
+    for (index, left) in blocks.iter().enumerate() {
+        for right in &blocks[index + 1..] {
+            if left.memory() == right.memory() {
+                assert!(
+                    left.range().end <= right.range().start
+                        || right.range().end <= left.range().start,
+                    "Blocks {:#?} and {:#?} overlap",
+                    left,
+                    right
+                );
+            }
+        }
+    }
+
+    for block in blocks {
+        heaps.free(device, block);
+    }
+
+    // Empty chunks are returned to the device.
+    assert_eq!(device.allocated(), 0);
+
+    // Alignment above buddy allocator limit falls back to dedicated allocation.
+    let block = heaps
+        .allocate(device, 1, Data, 40 * 1024, 8 * 1024 * 1024)
+        .expect("Allocation must succeed");
+    assert_eq!(block.range().start % (8 * 1024 * 1024), 0);
+    assert_eq!(device.allocated(), 1);
+    heaps.free(device, block);
+
+    heaps.dispose(device);
+}
+
+#[test]
+fn dynamic_unused_chunks_test() {
+    let mut heaps = init();
+    let ref device = MockDevice::new();
+
+    // Alloc/free churn must not allocate memory objects again.
+    for _ in 0..16 {
+        let block = heaps.allocate(device, 1, Data, 1024, 1).unwrap();
+        heaps.free(device, block);
+        assert_eq!(device.allocated(), 1);
+    }
+
+    // Fill two chunks.
+    let blocks = (0..128)
+        .map(|_| heaps.allocate(device, 1, Data, 1024, 1).unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(device.allocated(), 2);
+
+    // One chunk is returned to the device, another one is kept.
+    for block in blocks {
+        heaps.free(device, block);
+    }
+    assert_eq!(device.allocated(), 1);
+
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
+
+#[test]
+fn utilization_test() {
+    let mut heaps = init();
+    let ref device = MockDevice::new();
+
+    // Rounded up to power of two by buddy allocator.
+    let buddy = heaps.allocate(device, 1, Data, 100 * 1024, 1).unwrap();
+    let utilization = heaps.utilization();
+    let ref memory_type = utilization.types[0];
+    assert_eq!(memory_type.blocks.buddy, 1);
+    assert_eq!(memory_type.blocks.total(), 1);
+    assert_eq!(memory_type.utilization.used, 4 * 1024 * 1024);
+    assert_eq!(memory_type.utilization.effective, 128 * 1024);
+    assert!(memory_type.utilization.fragmentation() > 0.9);
+
+    let dedicated = heaps.allocate(device, 1, Data, 8 * 1024 * 1024, 1).unwrap();
+    let dynamic = heaps.allocate(device, 1, Data, 1024, 1).unwrap();
+    let utilization = heaps.utilization();
+    let ref memory_type = utilization.types[0];
+    let ref memory_heap = utilization.heaps[0];
+    assert_eq!(memory_type.blocks.dedicated, 1);
+    assert_eq!(memory_type.blocks.dynamic, 1);
+    assert_eq!(memory_type.blocks.total(), 3);
+    assert_eq!(
+        memory_type.utilization.effective,
+        8 * 1024 * 1024 + 128 * 1024 + 1024
+    );
+    assert!(memory_type.utilization.used >= memory_type.utilization.effective);
+    assert_eq!(memory_heap.utilization.used, memory_type.utilization.used);
+    assert_eq!(
+        memory_heap.utilization.effective,
+        memory_type.utilization.effective
+    );
+    assert_eq!(
+        memory_heap.available(),
+        16 * 1024 * 1024 - memory_heap.utilization.used
+    );
+
+    // Other memory types are untouched.
+    for memory_type in &utilization.types[1..] {
+        assert_eq!(memory_type.blocks.total(), 0);
+        assert_eq!(memory_type.utilization.used, 0);
+    }
+
+    heaps.free(device, buddy);
+    heaps.free(device, dedicated);
+    heaps.free(device, dynamic);
+    let utilization = heaps.utilization();
+    let ref memory_type = utilization.types[0];
+    assert_eq!(memory_type.blocks.total(), 0);
+    assert_eq!(memory_type.utilization.effective, 0);
+
+    heaps.dispose(device);
+}
+
+#[test]
+fn defragmentation_test() {
+    let mut heaps = init();
+    let ref device = MockDevice::new();
+
+    // Fill two chunks and free every other block.
+    let mut blocks = Vec::new();
+    let all = (0..128)
+        .map(|_| heaps.allocate(device, 1, Data, 1024, 1).unwrap())
+        .collect::<Vec<_>>();
+    for (index, block) in all.into_iter().enumerate() {
+        if index % 2 == 0 {
+            blocks.push(block);
+        } else {
+            heaps.free(device, block);
+        }
+    }
+    assert_eq!(device.allocated(), 2);
+
+    let pinned = blocks.len() - 1;
+    blocks[pinned].pin();
+    let pinned_range = blocks[pinned].range();
+    let effective = heaps.utilization().types[0].utilization.effective;
+
+    let defragmentation = heaps.defragment(&mut blocks);
+
+    // Blocks from second chunk are moved into first one, except pinned one.
+    assert_eq!(defragmentation.relocations().len(), 31);
+    for relocation in defragmentation.relocations() {
+        assert_ne!(relocation.index(), pinned);
+        assert_eq!(relocation.size(), 1024);
+        let ref new = blocks[relocation.index()];
+        assert_ne!(relocation.old().memory(), new.memory());
+        assert_eq!(new.memory(), blocks[0].memory());
+    }
+    assert_eq!(blocks[pinned].range(), pinned_range);
+
+    for (index, left) in blocks.iter().enumerate() {
+        for right in &blocks[index + 1..] {
+            if left.memory() == right.memory() {
+                assert!(
+                    left.range().end <= right.range().start
+                        || right.range().end <= left.range().start
+                );
+            }
+        }
+    }
+
+    heaps.complete_defragmentation(device, defragmentation);
+    assert_eq!(
+        heaps.utilization().types[0].utilization.effective,
+        effective
+    );
+
+    // Once pinned block is freed second chunk is unused.
+    let block = blocks.pop().unwrap();
+    heaps.free(device, block);
+    assert!(heaps.defragment(&mut blocks).is_empty());
+
+    for block in blocks {
+        heaps.free(device, block);
+    }
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
+
+#[test]
+fn shared_heaps_test() {
+    let shared = Arc::new(SharedHeaps::new(
+        init(),
+        CacheConfig {
+            refill: 8,
+            max_cached: 16,
+        },
+    ));
+    let device = Arc::new(MockDevice::new());
+
+    let workers = (0..4)
+        .map(|_| {
+            let shared = shared.clone();
+            let device = device.clone();
+            thread::spawn(move || {
+                let ref device = *device;
+                let mut cache = shared.cache();
+                let mut blocks = Vec::new();
+                for i in 0..64u64 {
+                    let size = 256 + (i % 4) * 1024;
+                    blocks.push(cache.allocate(device, 1, Data, size, 1).unwrap());
+                }
+                for i in 0..4u64 {
+                    // Too large to be cached.
+                    let size = 64 * 1024 + i;
+                    blocks.push(cache.allocate(device, 1, Data, size, 1).unwrap());
+                }
+
+                // Free half of blocks here and let other thread free the rest.
+                let rest = blocks.split_off(32);
+                for block in blocks {
+                    cache.free(device, block);
+                }
+                cache.dispose(device);
+                rest
+            })
+        }).collect::<Vec<_>>();
+
+    let blocks = workers
+        .into_iter()
+        .flat_map(|worker| worker.join().unwrap())
+        .collect::<Vec<_>>();
+
+    for (index, left) in blocks.iter().enumerate() {
+        for right in &blocks[index + 1..] {
+            if left.memory() == right.memory() {
+                assert!(
+                    left.range().end <= right.range().start
+                        || right.range().end <= left.range().start
+                );
+            }
+        }
+    }
+
+    let ref device = *device;
+    let mut cache = shared.cache();
+    for block in blocks {
+        cache.free(device, block);
+    }
+    assert!(shared.utilization().types[0].blocks.dynamic > 0);
+    cache.dispose(device);
+    assert_eq!(shared.utilization().types[0].blocks.total(), 0);
+
+    match Arc::try_unwrap(shared) {
+        Ok(shared) => shared.dispose(device),
+        Err(_) => panic!("All workers are finished"),
+    }
+    assert_eq!(device.allocated(), 0);
+}
+
+#[test]
+fn cache_pinned_test() {
+    let shared = SharedHeaps::new(
+        init(),
+        CacheConfig {
+            refill: 1,
+            max_cached: 16,
+        },
+    );
+    let ref device = MockDevice::new();
+    let mut cache = shared.cache();
+
+    let mut block = cache.allocate(device, 1, Data, 1024, 1).unwrap();
+    block.pin();
+
+    // Pinned block is returned to shared `Heaps` instead of being cached.
+    cache.free(device, block);
+    assert_eq!(shared.utilization().types[0].blocks.dynamic, 0);
+
+    cache.dispose(device);
+    shared.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
+
+#[test]
+fn cache_priority_test() {
+    let shared = SharedHeaps::new(
+        init(),
+        CacheConfig {
+            refill: 1,
+            max_cached: 16,
+        },
+    );
+    let ref device = MockDevice::new();
+    let mut cache = shared.cache();
+
+    let low = shared
+        .lock()
+        .allocate_prioritized(device, 1, Data, 1024, 1, Priority::LOW)
+        .unwrap();
+    let size = low.range().end - low.range().start;
+    assert_eq!(shared.utilization().heaps[0].priorities.low, size);
+
+    // Low priority block is returned to shared `Heaps` instead of being cached
+    // so priority stats stay correct and block is not handed out with stale priority.
+    cache.free(device, low);
+    let utilization = shared.utilization();
+    assert_eq!(utilization.heaps[0].priorities.low, 0);
+    assert_eq!(utilization.types[0].blocks.dynamic, 0);
+
+    let block = cache.allocate(device, 1, Data, 1024, 1).unwrap();
+    assert_eq!(block.priority(), Priority::NORMAL);
+    assert_eq!(shared.utilization().heaps[0].priorities.normal, size);
+
+    cache.free(device, block);
+    cache.dispose(device);
+    shared.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
+
+#[test]
+fn cache_budget_test() {
+    let mut heaps = init();
+    let ref device = MockDevice::new();
+    heaps.set_budget(0, 512 * 1024);
+    let shared = SharedHeaps::new(
+        heaps,
+        CacheConfig {
+            refill: 128,
+            max_cached: 128,
+        },
+    );
+    let mut cache = shared.cache();
+
+    // Refill stops at half of the budget instead of taking all of it.
+    let block = cache.allocate(device, 1, Data, 4096, 1).unwrap();
+    assert_eq!(block.memory_type(), 0);
+    let used = shared.utilization().heaps[0].utilization.used;
+    assert!(used > 0 && used <= 256 * 1024, "{}", used);
+
+    // Heap near its budget still serves requests. Blocks are allocated one by one.
+    let blocks = (0..64)
+        .map(|_| cache.allocate(device, 1, Data, 4096, 1).unwrap())
+        .collect::<Vec<_>>();
+    assert!(blocks.iter().all(|block| block.memory_type() == 0));
+    assert!(shared.utilization().heaps[0].utilization.used <= 512 * 1024);
+
+    cache.free(device, block);
+    for block in blocks {
+        cache.free(device, block);
+    }
+    cache.dispose(device);
+    shared.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
+
+#[test]
+fn leak_report_test() {
+    let mut heaps = init();
+    let ref device = MockDevice::new();
+
+    let textures = (0..3)
+        .map(|_| {
+            heaps
+                .allocate_tagged(device, 1, Data, 1024, 1, Some("textures".into()))
+                .unwrap()
+        }).collect::<Vec<_>>();
+    let meshes = (0..2)
+        .map(|_| {
+            heaps
+                .allocate_tagged(device, 1, Data, 100 * 1024, 1, Some(42.into()))
+                .unwrap()
+        }).collect::<Vec<_>>();
+    let mut untagged = heaps.allocate(device, 1, Data, 1024, 1).unwrap();
+    assert_eq!(textures[0].tag(), Some(Tag::Name("textures")));
+    assert_eq!(untagged.tag(), None);
+
+    let report = heaps.leaks();
+    assert_eq!(report.count(), 6);
+    assert_eq!(
+        report.leaks,
+        vec![
+            Leak {
+                tag: None,
+                memory_type: 0,
+                size: 1024,
+                count: 1,
+            },
+            Leak {
+                tag: Some(Tag::Name("textures")),
+                memory_type: 0,
+                size: 1024,
+                count: 3,
+            },
+            Leak {
+                tag: Some(Tag::Id(42)),
+                memory_type: 0,
+                size: 128 * 1024,
+                count: 2,
+            },
+        ]
+    );
+    let text = report.to_string();
+    assert!(text.contains("3 x 1024 bytes from memory type 0 tagged \"textures\""));
+    assert!(text.contains("2 x 131072 bytes from memory type 0 tagged #42"));
+
+    heaps.set_tag(&mut untagged, Some(String::from("staging").into()));
+    let report = heaps.leaks();
+    assert_eq!(report.count(), 6);
+    assert_eq!(report.leaks[0].tag, Some(Tag::Name("textures")));
+    let staging = Some(Tag::from(String::from("staging")));
+    assert_eq!(report.leaks[2].tag, staging);
+    let text = report.to_string();
+    assert!(text.contains("1 x 1024 bytes from memory type 0 tagged \"staging\""));
+
+    heaps.free(device, untagged);
+    for block in textures {
+        heaps.free(device, block);
+    }
+    let report = heaps.leaks();
+    assert_eq!(report.leaks.len(), 1);
+    assert_eq!(report.leaks[0].tag, Some(Tag::Id(42)));
+
+    for block in meshes {
+        heaps.free(device, block);
+    }
+    assert!(heaps.leaks().is_empty());
+    heaps.dispose(device);
+}
+
+#[test]
+fn budget_test() {
+    let mut heaps = init();
+    let ref device = MockDevice::new();
+    let mib = 1024 * 1024;
+
+    heaps.set_budget(0, 4 * mib);
+    assert_eq!(heaps.budget(0), 4 * mib);
+
+    // Fits budget of the most preferred memory type.
+    let first = heaps.allocate(device, 3, Data, 3 * mib, 1).unwrap();
+    assert_eq!(first.memory_type(), 0);
+
+    // Doesn't fit budget and there is no fallback.
+    match heaps.allocate(device, 3, Data, 3 * mib, 1) {
+        Err(MemoryError::OutOfMemoryError(OutOfMemoryError::HeapsExhausted)) => {}
+        other => panic!("Unexpected result {:?}", other),
+    }
+
+    // Buddy allocator would allocate chunk that exceeds budget.
+    // Falls back to less preferred memory type.
+    let second = heaps.allocate(device, 3, Data, 512 * 1024, 1).unwrap();
+    assert_eq!(second.memory_type(), 1);
+    assert_eq!(heaps.utilization().heaps[0].utilization.used, 3 * mib);
+    assert_eq!(heaps.utilization().heaps[0].available(), mib);
+
+    let evictable = Arc::new(Mutex::new(Some(first)));
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    {
+        let evictable = evictable.clone();
+        let requests = requests.clone();
+        heaps.set_eviction(move |heap_index, size| {
+            requests.lock().unwrap().push((heap_index, size));
+            evictable.lock().unwrap().take().into_iter().collect()
+        });
+    }
+
+    // First block is evicted to make room.
+    let third = heaps.allocate(device, 3, Data, 3 * mib, 1).unwrap();
+    assert_eq!(third.memory_type(), 0);
+    assert_eq!(requests.lock().unwrap().len(), 1);
+    assert_eq!(requests.lock().unwrap()[0].0, 0);
+    assert!(requests.lock().unwrap()[0].1 > 2 * mib);
+    assert!(evictable.lock().unwrap().is_none());
+
+    // Nothing left to evict.
+    match heaps.allocate(device, 1, Data, 3 * mib, 1) {
+        Err(MemoryError::OutOfMemoryError(OutOfMemoryError::HeapsExhausted)) => {}
+        other => panic!("Unexpected result {:?}", other),
+    }
+    assert_eq!(requests.lock().unwrap().len(), 2);
+
+    heaps.clear_eviction();
+    heaps.free(device, second);
+    heaps.free(device, third);
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
+
+#[test]
+fn ring_test() {
+    let mut heaps = init();
+    let ref device = MockDevice::new();
+    let size = 10 * 1024;
+
+    heaps.begin_frame(0);
+    let first = (0..3)
+        .map(|_| heaps.allocate(device, 1 << 2, Stream, size, 256).unwrap())
+        .collect::<Vec<_>>();
+    heaps.begin_frame(1);
+    let second = (0..3)
+        .map(|_| heaps.allocate(device, 1 << 2, Stream, size, 256).unwrap())
+        .collect::<Vec<_>>();
+
+    // All blocks are allocated sequentially from single memory object.
+    assert_eq!(device.allocated(), 1);
+    for (index, block) in first.iter().chain(&second).enumerate() {
+        assert_eq!(block.memory_type(), 2);
+        assert_eq!(block.range().start, index as u64 * size);
+    }
+    let utilization = heaps.utilization();
+    assert_eq!(utilization.types[2].blocks.ring, 6);
+    assert_eq!(utilization.types[2].utilization.used, 64 * 1024);
+
+    // Ring is full until first frame is completed.
+    heaps.begin_frame(2);
+    match heaps.allocate(device, 1 << 2, Stream, size, 256) {
+        Err(MemoryError::OutOfMemoryError(OutOfMemoryError::HeapsExhausted)) => {}
+        other => panic!("Unexpected result {:?}", other),
+    }
+
+    // Freeing blocks doesn't make their memory available.
+    for block in first {
+        heaps.free(device, block);
+    }
+    assert!(heaps.allocate(device, 1 << 2, Stream, size, 256).is_err());
+
+    // Memory of the first frame is reused, allocation wraps around.
+    heaps.complete_frame(0);
+    let third = heaps.allocate(device, 1 << 2, Stream, size, 256).unwrap();
+    assert_eq!(third.range(), 0..size);
+    assert_eq!(device.allocated(), 1);
+
+    heaps.free(device, third);
+    for block in second {
+        heaps.free(device, block);
+    }
+    heaps.complete_frame(2);
+    assert_eq!(heaps.utilization().types[2].blocks.ring, 0);
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
+
+#[test]
+fn dedicated_test() {
+    let mut heaps = init();
+    let ref device = MockDevice::new();
+
+    // Small block that would be sub-allocated gets memory object of its own.
+    let shared = heaps.allocate(device, 1, Data, 1024, 1).unwrap();
+    let dedicated = heaps
+        .allocate_dedicated(device, 1, Data, 1024, 1, Dedicated::Image(7))
+        .unwrap();
+    assert_eq!(shared.dedicated(), None);
+    assert_eq!(dedicated.dedicated(), Some(Dedicated::Image(7)));
+    assert_eq!(dedicated.range(), 0..1024);
+    assert_eq!(device.allocated(), 2);
+    // Resource handle reaches the device.
+    assert_eq!(device.dedicated(), vec![Dedicated::Image(7)]);
+
+    let utilization = heaps.utilization();
+    assert_eq!(utilization.types[0].blocks.dedicated, 1);
+    assert_eq!(utilization.types[0].blocks.dynamic, 1);
+
+    heaps.free(device, dedicated);
+    assert_eq!(device.allocated(), 1);
+    assert!(device.dedicated().is_empty());
+
+    heaps.free(device, shared);
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
+
+#[test]
+fn allocation_info_test() {
+    let mut heaps = init();
+    let ref device = MockDevice::new();
+
+    // All attributes are applied at once.
+    let info = AllocationInfo {
+        tag: Some("texture".into()),
+        dedicated: Some(Dedicated::Image(7)),
+        priority: Priority::LOW,
+    };
+    let block = heaps.allocate_with(device, 1, Data, 1024, 1, info).unwrap();
+    assert_eq!(block.tag(), Some("texture".into()));
+    assert_eq!(block.dedicated(), Some(Dedicated::Image(7)));
+    assert_eq!(block.priority(), Priority::LOW);
+    assert_eq!(device.dedicated(), vec![Dedicated::Image(7)]);
+    assert_eq!(heaps.leaks().leaks.len(), 1);
+    assert_eq!(heaps.leaks().leaks[0].tag, Some("texture".into()));
+
+    heaps.free(device, block);
+    assert!(heaps.leaks().leaks.is_empty());
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
+
+#[test]
+fn tiles_test() {
+    let mut heaps = init();
+    let ref device = MockDevice::new();
+
+    let tiles = heaps.allocate_tiles(device, 1, Data, 64 * 1024, 8).unwrap();
+    assert_eq!(tiles.len(), 8);
+    for tile in &tiles {
+        let range = tile.range();
+        assert_eq!(range.start % (64 * 1024), 0);
+        assert_eq!(range.end - range.start, 64 * 1024);
+    }
+
+    // Nothing is allocated if some tile doesn't fit.
+    let used = heaps.utilization().heaps[0].utilization.effective;
+    match heaps.allocate_tiles(device, 1, Data, 4 * 1024 * 1024, 8) {
+        Err(MemoryError::OutOfMemoryError(OutOfMemoryError::HeapsExhausted)) => {}
+        other => panic!("Unexpected result {:?}", other),
+    }
+    assert_eq!(heaps.utilization().heaps[0].utilization.effective, used);
+
+    for tile in tiles {
+        heaps.free(device, tile);
+    }
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
+
+#[test]
+fn priority_test() {
+    let config = Config {
+        arena: None,
+        dynamic: None,
+        buddy: None,
+        ring: None,
+    };
+    let mut heaps = unsafe {
+        Heaps::new(
+            vec![
+                (Properties::DEVICE_LOCAL, 0, config),
+                (Properties::DEVICE_LOCAL, 1, config),
+            ],
+            vec![16 * 1024 * 1024, 16 * 1024 * 1024],
+            1,
+        )
+    };
+    let ref device = MockDevice::new();
+    let size = 1024 * 1024;
+
+    // Last memory type wins on ties.
+    let normal = heaps.allocate(device, 0b11, Data, size, 1).unwrap();
+    assert_eq!(normal.memory_type(), 1);
+    assert_eq!(normal.priority(), Priority::NORMAL);
+
+    // Low priority block goes to less contended heap.
+    let low = heaps
+        .allocate_prioritized(device, 0b11, Data, size, 1, Priority::LOW)
+        .unwrap();
+    assert_eq!(low.memory_type(), 0);
+    assert_eq!(low.priority(), Priority::LOW);
+
+    // High priority block is placed as usual.
+    let high = heaps
+        .allocate_prioritized(device, 0b11, Data, size, 1, Priority::HIGH)
+        .unwrap();
+    assert_eq!(high.memory_type(), 1);
+
+    let utilization = heaps.utilization();
+    assert_eq!(utilization.heaps[0].priorities.low, size);
+    assert_eq!(utilization.heaps[0].priorities.normal, 0);
+    assert_eq!(utilization.heaps[1].priorities.normal, size);
+    assert_eq!(utilization.heaps[1].priorities.high, size);
+
+    heaps.free(device, low);
+    assert_eq!(heaps.utilization().heaps[0].priorities.low, 0);
+
+    heaps.free(device, normal);
+    heaps.free(device, high);
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
@@ -0,0 +1,159 @@
+use config::ConfigBuilder;
+use heaps::{Config, Heaps};
+use memory::Properties;
+use mock::MockDevice;
+use usage::*;
+
+const MIB: u64 = 1024 * 1024;
+
+fn types() -> Vec<(Properties, u32)> {
+    vec![
+        (Properties::DEVICE_LOCAL, 0),
+        (Properties::DEVICE_LOCAL | Properties::LAZILY_ALLOCATED, 0),
+        (Properties::HOST_VISIBLE | Properties::HOST_COHERENT, 1),
+        (
+            Properties::DEVICE_LOCAL | Properties::HOST_VISIBLE | Properties::HOST_COHERENT,
+            2,
+        ),
+    ]
+}
+
+fn heaps() -> Vec<u64> {
+    vec![4096 * MIB, 1024 * MIB, 256 * MIB]
+}
+
+#[test]
+fn config_heuristics_test() {
+    let configs = ConfigBuilder::new().build(types(), &heaps());
+    assert_eq!(configs.len(), 4);
+
+    // Device-local memory gets dynamic and buddy allocators with chunk size clamped to maximum.
+    let config = configs[0].2;
+    assert!(config.arena.is_none());
+    assert!(config.ring.is_none());
+    assert_eq!(config.dynamic.unwrap().max_block_size, 64 * 1024);
+    assert_eq!(config.buddy.unwrap().chunk_size, 64 * MIB);
+
+    // Lazily allocated memory is allocated only with dedicated memory objects.
+    let config = configs[1].2;
+    assert!(config.arena.is_none());
+    assert!(config.dynamic.is_none());
+    assert!(config.buddy.is_none());
+    assert!(config.ring.is_none());
+
+    // Host-visible memory gets arena and ring.
+    let config = configs[2].2;
+    assert_eq!(config.arena.unwrap().arena_size, 16 * MIB);
+    assert_eq!(config.ring.unwrap().ring_size, 16 * MIB);
+    assert!(config.dynamic.is_some());
+    assert!(config.buddy.is_none());
+
+    let config = configs[3].2;
+    assert_eq!(config.arena.unwrap().arena_size, 4 * MIB);
+    assert_eq!(config.buddy.unwrap().chunk_size, 4 * MIB);
+
+    // Chunks of small heaps are limited by heap size.
+    let config = ConfigBuilder::new().config(0, Properties::DEVICE_LOCAL, 512 * 1024);
+    assert_eq!(config.buddy.unwrap().chunk_size, 512 * 1024);
+    assert_eq!(config.dynamic.unwrap().max_block_size, 8 * 1024);
+
+    let builder = ConfigBuilder::new().with_override(
+        2,
+        Config {
+            arena: None,
+            dynamic: None,
+            buddy: None,
+            ring: None,
+        },
+    );
+    let configs = builder.build(types(), &heaps());
+    assert!(configs[2].2.arena.is_none());
+    assert!(configs[3].2.arena.is_some());
+
+    let mut heaps = unsafe { Heaps::new(configs, heaps(), 1) };
+    let ref device = MockDevice::new();
+    let data = heaps.allocate(device, 1, Data, 1024, 1).unwrap();
+    let stream = heaps.allocate(device, 1 << 3, Stream, 1024, 1).unwrap();
+    heaps.free(device, data);
+    heaps.free(device, stream);
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
+
+#[test]
+fn transient_test() {
+    let configs = ConfigBuilder::new().build(types(), &heaps());
+    let mut heaps = unsafe { Heaps::new(configs, heaps(), 1) };
+    let ref device = MockDevice::new();
+
+    // Lazily allocated memory is preferred.
+    let lazy = heaps.allocate(device, 0b1111, Transient, 1024, 1).unwrap();
+    assert_eq!(lazy.memory_type(), 1);
+    assert_eq!(device.allocated(), 1);
+
+    // Falls back to device-local memory.
+    let fallback = heaps.allocate(device, 0b1101, Transient, 1024, 1).unwrap();
+    assert_eq!(fallback.memory_type(), 0);
+
+    // Never allocated from memory without device-local property.
+    assert!(heaps.allocate(device, 0b0100, Transient, 1024, 1).is_err());
+
+    // Data usage avoids lazily allocated memory.
+    let data = heaps.allocate(device, 0b0011, Data, 1024, 1).unwrap();
+    assert_eq!(data.memory_type(), 0);
+
+    heaps.free(device, lazy);
+    heaps.free(device, fallback);
+    heaps.free(device, data);
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn config_deserialize_test() {
+    use ron;
+    use toml;
+
+    let builder: ConfigBuilder = ron::de::from_str(
+        "(
+            chunk_fraction: 32,
+            ring: false,
+            overrides: [
+                (
+                    memory_type: 0,
+                    config: (
+                        buddy: Some((chunk_size: 1048576, min_block_size: 4096)),
+                    ),
+                ),
+            ],
+        )",
+    ).unwrap();
+    assert_eq!(builder.chunk_fraction, 32);
+    assert_eq!(
+        builder.max_chunk_size,
+        ConfigBuilder::default().max_chunk_size
+    );
+    let configs = builder.build(types(), &heaps());
+    assert_eq!(configs[0].2.buddy.unwrap().min_block_size, 4096);
+    assert!(configs[0].2.dynamic.is_none());
+    assert!(configs[2].2.ring.is_none());
+    assert_eq!(configs[2].2.arena.unwrap().arena_size, 32 * MIB);
+
+    let builder: ConfigBuilder = toml::from_str(
+        "
+        max_chunk_size = 8388608
+
+        [[overrides]]
+        memory_type = 2
+
+        [overrides.config]
+        arena = { arena_size = 65536 }
+        ",
+    ).unwrap();
+    let configs = builder.build(types(), &heaps());
+    assert_eq!(configs[0].2.buddy.unwrap().chunk_size, 8 * MIB);
+    assert_eq!(configs[2].2.arena.unwrap().arena_size, 65536);
+    assert!(configs[2].2.ring.is_none());
+    assert!(configs[3].2.ring.is_some());
+}
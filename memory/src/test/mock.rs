@@ -0,0 +1,321 @@
+use allocator::{ArenaConfig, DynamicConfig};
+use block::Block;
+use error::{AllocationError, MappingError, MemoryError, OutOfMemoryError};
+use heaps::{Config, Heaps};
+use mapping::{write::Write, MappedBuffer};
+use memory::Properties;
+use mock::{Fault, MockDevice, MockMemory, Operation};
+use usage::*;
+
+fn init() -> Heaps<MockMemory> {
+    unsafe {
+        Heaps::new(
+            vec![
+                (
+                    Properties::DEVICE_LOCAL,
+                    0,
+                    Config {
+                        arena: None,
+                        dynamic: Some(DynamicConfig {
+                            blocks_per_chunk: 64,
+                            block_size_granularity: 256,
+                            max_block_size: 32 * 1024,
+                        }),
+                        buddy: None,
+                        ring: None,
+                    },
+                ),
+                (
+                    Properties::HOST_VISIBLE | Properties::HOST_COHERENT,
+                    1,
+                    Config {
+                        arena: Some(ArenaConfig {
+                            arena_size: 32 * 1024,
+                        }),
+                        dynamic: None,
+                        buddy: None,
+                        ring: None,
+                    },
+                ),
+                (
+                    Properties::HOST_VISIBLE | Properties::HOST_CACHED,
+                    1,
+                    Config {
+                        arena: Some(ArenaConfig {
+                            arena_size: 32 * 1024,
+                        }),
+                        dynamic: None,
+                        buddy: None,
+                        ring: None,
+                    },
+                ),
+            ],
+            vec![16 * 1024 * 1024, 16 * 1024 * 1024],
+            64,
+        )
+    }
+}
+
+#[test]
+fn mapping_test() {
+    let mut heaps = init();
+    let ref device = MockDevice::new();
+
+    let data = (0..=255u8).collect::<Vec<_>>();
+    let mut block = heaps.allocate(device, 2, Upload, 256, 1).unwrap();
+    {
+        let mut mapping = block.map(device, 0..256).unwrap();
+        let mut writer = unsafe { mapping.write::<_, u8>(device, 0..256).unwrap() };
+        writer.write(&data);
+    }
+
+    let range = block.range();
+    let content = unsafe { block.memory().content() };
+    assert_eq!(
+        &content[range.start as usize..range.end as usize],
+        &data[..]
+    );
+
+    {
+        let mut mapping = block.map(device, 0..256).unwrap();
+        let read = unsafe { mapping.read::<_, u8>(device, 16..32).unwrap() };
+        assert_eq!(read, &data[16..32]);
+    }
+
+    heaps.free(device, block);
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+    assert_eq!(device.mapped(), 0);
+}
+
+#[test]
+fn allocation_fault_test() {
+    let mut heaps = init();
+    let ref device = MockDevice::new();
+    device.fail_nth(Operation::Allocate, 2, Fault::OutOfDeviceMemory);
+
+    // Dedicated allocations.
+    let size = 1024 * 1024;
+    let first = heaps.allocate(device, 1, Data, size, 1).unwrap();
+    match heaps.allocate(device, 1, Data, size, 1) {
+        Err(MemoryError::AllocationError(AllocationError::OutOfMemoryError(
+            OutOfMemoryError::OutOfDeviceMemory,
+        ))) => {}
+        other => panic!("Unexpected result {:?}", other),
+    }
+    assert_eq!(device.allocated(), 1);
+    assert_eq!(heaps.utilization().heaps[0].utilization.used, size);
+
+    // Allocator recovers after failure.
+    let second = heaps.allocate(device, 1, Data, size, 1).unwrap();
+    assert_eq!(device.calls(Operation::Allocate), 3);
+
+    device.fail_all(Operation::Allocate, Fault::TooManyObjects);
+    for _ in 0..4 {
+        match heaps.allocate(device, 1, Data, 1024, 1) {
+            Err(MemoryError::AllocationError(AllocationError::TooManyObjects)) => {}
+            other => panic!("Unexpected result {:?}", other),
+        }
+    }
+    device.clear_faults();
+    let third = heaps.allocate(device, 1, Data, 1024, 1).unwrap();
+
+    heaps.free(device, first);
+    heaps.free(device, second);
+    heaps.free(device, third);
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
+
+#[test]
+fn mapping_fault_test() {
+    let mut heaps = init();
+    let ref device = MockDevice::new();
+    device.fail_nth(Operation::Map, 1, Fault::MappingFailed);
+
+    // Arena maps memory right after allocation.
+    match heaps.allocate(device, 2, Upload, 1024, 1) {
+        Err(MemoryError::MappingError(MappingError::MappingFailed)) => {}
+        other => panic!("Unexpected result {:?}", other),
+    }
+
+    // Memory object that failed to map is freed.
+    assert_eq!(device.allocated(), 0);
+    assert_eq!(heaps.utilization().heaps[1].utilization.used, 0);
+
+    let block = heaps.allocate(device, 2, Upload, 1024, 1).unwrap();
+    assert_eq!(device.allocated(), 1);
+    assert_eq!(device.mapped(), 1);
+
+    heaps.free(device, block);
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
+
+#[test]
+fn device_lost_test() {
+    let mut heaps = init();
+    let ref device = MockDevice::new();
+    device.fail_nth(Operation::Allocate, 1, Fault::DeviceLost);
+
+    match heaps.allocate(device, 1, Data, 1024, 1) {
+        Err(MemoryError::AllocationError(AllocationError::DeviceLost)) => {}
+        other => panic!("Unexpected result {:?}", other),
+    }
+
+    let block = heaps.allocate(device, 1, Data, 1024, 1).unwrap();
+
+    device.lose();
+    match heaps.allocate(device, 1, Data, 1024 * 1024, 1) {
+        Err(MemoryError::AllocationError(AllocationError::DeviceLost)) => {}
+        other => panic!("Unexpected result {:?}", other),
+    }
+    match heaps.allocate(device, 2, Upload, 1024, 1) {
+        Err(MemoryError::AllocationError(AllocationError::DeviceLost)) => {}
+        other => panic!("Unexpected result {:?}", other),
+    }
+
+    // Nothing is leaked and existing blocks can still be freed.
+    assert_eq!(device.allocated(), 1);
+    assert_eq!(heaps.utilization().heaps[1].utilization.used, 0);
+    heaps.free(device, block);
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+
+    // Memory allocated before device was lost can't be mapped.
+    // Block is too large for arena so it is not mapped persistently.
+    let mut heaps = init();
+    device.clear_faults();
+    let size = 1024 * 1024;
+    let mut block = heaps.allocate(device, 1 << 2, Download, size, 1).unwrap();
+    device.lose();
+    match block.map(device, 0..size) {
+        Err(MappingError::DeviceLost) => {}
+        other => panic!("Unexpected result {:?}", other.map(|_| ())),
+    }
+
+    heaps.free(device, block);
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
+
+#[test]
+fn non_coherent_test() {
+    let mut heaps = init();
+    let ref device = MockDevice::with_non_coherent_atom_size(64);
+
+    // Blocks in non-coherent memory never share an atom.
+    let mut blocks = (0..3)
+        .map(|_| heaps.allocate(device, 1 << 2, Download, 100, 4).unwrap())
+        .collect::<Vec<_>>();
+    for block in &blocks {
+        let range = block.range();
+        assert_eq!(range.start % 64, 0);
+        assert_eq!(range.end % 64, 0);
+    }
+    assert!(blocks[0].range().end <= blocks[1].range().start);
+    assert!(blocks[1].range().end <= blocks[2].range().start);
+
+    let start = blocks[1].range().start;
+    let id = blocks[1].memory().id();
+    {
+        let mut mapping = blocks[1].map(device, 0..100).unwrap();
+        let mut writer = unsafe { mapping.write::<_, u8>(device, 10..20).unwrap() };
+        writer.write(&[1; 10]);
+    }
+    assert_eq!(device.flushed(), vec![(id, start..start + 64)]);
+
+    {
+        let mut mapping = blocks[1].map(device, 0..100).unwrap();
+        let read = unsafe { mapping.read::<_, u8>(device, 70..80).unwrap() };
+        assert_eq!(read, &[0; 10]);
+    }
+    assert_eq!(
+        device.invalidated().last(),
+        Some(&(id, start + 64..start + 128))
+    );
+
+    for block in blocks.drain(..) {
+        heaps.free(device, block);
+    }
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
+
+#[test]
+fn mapped_buffer_test() {
+    let mut heaps = init();
+    let ref device = MockDevice::with_non_coherent_atom_size(64);
+
+    // Non-coherent memory.
+    let mut block = heaps.allocate(device, 1 << 2, Download, 1024, 256).unwrap();
+    let offset = block.range().start;
+    let id = block.memory().id();
+    {
+        let mapping = block.map(device, 0..1024).unwrap();
+        let mut buffer = unsafe { MappedBuffer::<u32, _>::new(mapping) }.unwrap();
+        assert_eq!(buffer.len(), 256);
+
+        // Block is not borrowed by the buffer.
+        assert_eq!(block.range().start, offset);
+
+        buffer.write(0, &[1, 2, 3]);
+        buffer.write(100, &[4]);
+        buffer.slice_mut(3..5).copy_from_slice(&[5, 6]);
+        assert_eq!(
+            buffer.dirty(),
+            &[offset..offset + 20, offset + 400..offset + 404][..]
+        );
+
+        buffer.flush(device).unwrap();
+        assert!(!buffer.is_dirty());
+        assert_eq!(device.calls(Operation::Flush), 1);
+        assert_eq!(
+            device.flushed(),
+            vec![(id, offset..offset + 64), (id, offset + 384..offset + 448)]
+        );
+
+        buffer.flush(device).unwrap();
+        assert_eq!(device.calls(Operation::Flush), 1);
+
+        buffer
+            .invalidate(device, vec![0..4, 8..16, 200..256])
+            .unwrap();
+        assert_eq!(device.calls(Operation::Invalidate), 1);
+        assert_eq!(
+            device.invalidated(),
+            vec![(id, offset..offset + 64), (id, offset + 768..offset + 1024)]
+        );
+        assert_eq!(&buffer.as_slice()[..5], &[1, 2, 3, 5, 6]);
+        assert_eq!(buffer.as_slice()[100], 4);
+    }
+
+    let content = unsafe { block.memory().content() };
+    assert_eq!(
+        &content[offset as usize + 400..offset as usize + 404],
+        &[4, 0, 0, 0]
+    );
+
+    // Coherent memory doesn't require flushing.
+    let mut coherent = heaps.allocate(device, 1 << 1, Upload, 1024, 256).unwrap();
+    {
+        // Disjoint ranges of the block can be mapped at the same time.
+        let mapping = coherent.map(device, 0..512).unwrap();
+        let mut buffer = unsafe { MappedBuffer::<[f32; 4], _>::new(mapping) }.unwrap();
+        let mapping = coherent.map(device, 512..1024).unwrap();
+        let other = unsafe { MappedBuffer::<[f32; 4], _>::new(mapping) }.unwrap();
+        assert_eq!(buffer.len(), 32);
+        assert_eq!(other.len(), 32);
+        buffer.write(1, &[[1.0, 2.0, 3.0, 4.0]]);
+        assert!(!buffer.is_dirty());
+        buffer.flush(device).unwrap();
+        buffer.invalidate(device, Some(0..32)).unwrap();
+    }
+    assert_eq!(device.calls(Operation::Flush), 1);
+    assert_eq!(device.calls(Operation::Invalidate), 1);
+
+    heaps.free(device, block);
+    heaps.free(device, coherent);
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
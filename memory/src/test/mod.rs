@@ -1 +1,4 @@
+mod aliasing;
 mod allocator;
+mod config;
+mod mock;
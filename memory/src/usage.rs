@@ -137,6 +137,70 @@ impl Usage for Download {
     }
 }
 
+/// CPU to GPU data flow for data written every frame.
+/// Used for per-frame transient uploads consumed by GPU within the frame.
+/// Host access is guaranteed.
+/// Blocks are served from `RingAllocator` when it's configured
+/// and their memory is reused once the frame is completed.
+/// Prefers memory with fast GPU access.
+#[derive(Clone, Copy, Debug)]
+pub struct Stream;
+
+impl Usage for Stream {
+    type Fitness = u8;
+
+    #[inline]
+    fn value(self) -> UsageValue {
+        UsageValue::Stream
+    }
+
+    #[inline]
+    fn memory_fitness(&self, properties: Properties) -> Option<u8> {
+        if !properties.contains(Properties::HOST_VISIBLE) {
+            None
+        } else {
+            assert!(!properties.contains(Properties::LAZILY_ALLOCATED));
+            Some(
+                (properties.contains(Properties::DEVICE_LOCAL) as u8) << 2
+                    | (properties.contains(Properties::HOST_COHERENT) as u8) << 1
+                    | ((!properties.contains(Properties::HOST_CACHED)) as u8) << 0
+                    | 0,
+            )
+        }
+    }
+}
+
+/// GPU-only data that doesn't outlive render pass.
+/// Used for transient attachments such as multisampled color or depth buffers.
+/// Prefers lazily allocated memory that may never be backed by physical memory
+/// and falls back to plain device-local memory.
+#[derive(Clone, Copy, Debug)]
+pub struct Transient;
+
+impl Usage for Transient {
+    type Fitness = u8;
+
+    #[inline]
+    fn value(self) -> UsageValue {
+        UsageValue::Transient
+    }
+
+    #[inline]
+    fn memory_fitness(&self, properties: Properties) -> Option<u8> {
+        if !properties.contains(Properties::DEVICE_LOCAL) {
+            None
+        } else {
+            Some(
+                (properties.contains(Properties::LAZILY_ALLOCATED) as u8) << 3
+                    | ((!properties.contains(Properties::HOST_VISIBLE)) as u8) << 2
+                    | ((!properties.contains(Properties::HOST_CACHED)) as u8) << 1
+                    | ((!properties.contains(Properties::HOST_COHERENT)) as u8) << 0
+                    | 0,
+            )
+        }
+    }
+}
+
 /// Dynamic value that specify memory usage flags.
 #[derive(Clone, Copy, Debug)]
 pub enum UsageValue {
@@ -148,6 +212,10 @@ pub enum UsageValue {
     Upload,
     /// Runtime counterpart for `Download`.
     Download,
+    /// Runtime counterpart for `Stream`.
+    Stream,
+    /// Runtime counterpart for `Transient`.
+    Transient,
 }
 
 impl Usage for UsageValue {
@@ -165,6 +233,8 @@ impl Usage for UsageValue {
             UsageValue::Dynamic => Dynamic.memory_fitness(properties),
             UsageValue::Upload => Upload.memory_fitness(properties),
             UsageValue::Download => Download.memory_fitness(properties),
+            UsageValue::Stream => Stream.memory_fitness(properties),
+            UsageValue::Transient => Transient.memory_fitness(properties),
         }
     }
 }
@@ -0,0 +1,125 @@
+use memory::Properties;
+use priority::Priority;
+
+/// Memory utilization stats.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MemoryUtilization {
+    /// Total number of bytes allocated from the device.
+    pub used: u64,
+
+    /// Number of bytes handed out as memory blocks.
+    pub effective: u64,
+}
+
+impl MemoryUtilization {
+    /// Get ratio of allocated memory not handed out as blocks.
+    /// `0.0` means no memory is wasted.
+    pub fn fragmentation(&self) -> f32 {
+        if self.used == 0 {
+            0.0
+        } else {
+            1.0 - (self.effective as f64 / self.used as f64) as f32
+        }
+    }
+}
+
+/// Number of memory blocks allocated by each sub-allocator.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BlockCounts {
+    /// Blocks allocated by `DedicatedAllocator`.
+    pub dedicated: u64,
+
+    /// Blocks allocated by `ArenaAllocator`.
+    pub arena: u64,
+
+    /// Blocks allocated by `DynamicAllocator`.
+    pub dynamic: u64,
+
+    /// Blocks allocated by `BuddyAllocator`.
+    pub buddy: u64,
+
+    /// Blocks allocated by `RingAllocator`.
+    pub ring: u64,
+}
+
+impl BlockCounts {
+    /// Get total number of blocks.
+    pub fn total(&self) -> u64 {
+        self.dedicated + self.arena + self.dynamic + self.buddy + self.ring
+    }
+}
+
+/// Number of bytes handed out as memory blocks of each priority class.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PriorityUtilization {
+    /// Bytes of blocks with priority lower than `Priority::NORMAL`.
+    /// Those are first candidates for eviction.
+    pub low: u64,
+
+    /// Bytes of blocks with `Priority::NORMAL`.
+    pub normal: u64,
+
+    /// Bytes of blocks with priority higher than `Priority::NORMAL`.
+    pub high: u64,
+}
+
+impl PriorityUtilization {
+    pub(crate) fn bytes_mut(&mut self, priority: Priority) -> &mut u64 {
+        if priority < Priority::NORMAL {
+            &mut self.low
+        } else if priority > Priority::NORMAL {
+            &mut self.high
+        } else {
+            &mut self.normal
+        }
+    }
+}
+
+/// Memory utilization of the memory type.
+#[derive(Clone, Copy, Debug)]
+pub struct MemoryTypeUtilization {
+    /// Memory properties of the memory type.
+    pub properties: Properties,
+
+    /// Index of the heap memory type belongs to.
+    pub heap_index: usize,
+
+    /// Utilization of the memory type.
+    pub utilization: MemoryUtilization,
+
+    /// Blocks allocated from the memory type.
+    pub blocks: BlockCounts,
+}
+
+/// Memory utilization of the memory heap.
+#[derive(Clone, Copy, Debug)]
+pub struct MemoryHeapUtilization {
+    /// Size of the memory heap.
+    pub size: u64,
+
+    /// Memory budget of the memory heap.
+    pub budget: u64,
+
+    /// Utilization of the memory heap.
+    pub utilization: MemoryUtilization,
+
+    /// Utilization of the memory heap by blocks of each priority class.
+    pub priorities: PriorityUtilization,
+}
+
+impl MemoryHeapUtilization {
+    /// Get number of bytes still available in the heap within budget.
+    pub fn available(&self) -> u64 {
+        self.budget.saturating_sub(self.utilization.used)
+    }
+}
+
+/// Memory utilization of all memory types and heaps.
+#[derive(Clone, Debug)]
+pub struct TotalMemoryUtilization {
+    /// Utilization per memory type.
+    pub types: Vec<MemoryTypeUtilization>,
+
+    /// Utilization per memory heap.
+    pub heaps: Vec<MemoryHeapUtilization>,
+}
@@ -0,0 +1,133 @@
+//! Transient resources that share memory when their lifetimes don't overlap.
+
+use std::ops::Range;
+
+use memory::{Block, Heaps, MemoryBlock};
+use relevant::Relevant;
+
+use buffer;
+use device::Device;
+use image;
+
+/// Info of the transient resource to create.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransientInfo {
+    /// Buffer to create.
+    Buffer(buffer::CreateInfo),
+
+    /// Image to create.
+    Image(image::CreateInfo),
+}
+
+/// Transient resource to create with interval during which it is alive.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransientRequest {
+    /// Info of the resource.
+    pub info: TransientInfo,
+
+    /// Interval during which resource is alive.
+    /// Typically range of framegraph pass indices.
+    pub lifetime: Range<u64>,
+}
+
+/// Raw transient resource.
+#[derive(Debug)]
+pub enum TransientResource<B, I> {
+    /// Raw buffer.
+    Buffer(B),
+
+    /// Raw image.
+    Image(I),
+}
+
+/// Transient resources bound to shared memory blocks.
+///
+/// Resources alive at the same time never share memory.
+/// Resources with disjoint lifetimes may be bound to overlapping memory ranges,
+/// so content of the resource is undefined when its lifetime begins.
+#[derive(Debug)]
+pub struct Aliased<M, B, I> {
+    pub(crate) resources: Vec<TransientResource<B, I>>,
+    pub(crate) blocks: Vec<MemoryBlock<M>>,
+    pub(crate) relevant: Relevant,
+}
+
+impl<M, B, I> Aliased<M, B, I>
+where
+    M: 'static,
+{
+    /// Number of resources.
+    pub fn len(&self) -> usize {
+        self.resources.len()
+    }
+
+    /// Check if there are no resources.
+    pub fn is_empty(&self) -> bool {
+        self.resources.is_empty()
+    }
+
+    /// Get resource by index of its request.
+    pub fn resource(&self, index: usize) -> &TransientResource<B, I> {
+        &self.resources[index]
+    }
+
+    /// Get buffer by index of its request.
+    ///
+    /// # Panics
+    ///
+    /// Panics if resource is not a buffer.
+    pub fn buffer(&self, index: usize) -> &B {
+        match self.resources[index] {
+            TransientResource::Buffer(ref buffer) => buffer,
+            TransientResource::Image(_) => panic!("Resource {} is not a buffer", index),
+        }
+    }
+
+    /// Get image by index of its request.
+    ///
+    /// # Panics
+    ///
+    /// Panics if resource is not an image.
+    pub fn image(&self, index: usize) -> &I {
+        match self.resources[index] {
+            TransientResource::Image(ref image) => image,
+            TransientResource::Buffer(_) => panic!("Resource {} is not an image", index),
+        }
+    }
+
+    /// Memory blocks resources are bound to.
+    pub fn blocks(&self) -> &[MemoryBlock<M>] {
+        &self.blocks
+    }
+
+    /// Total size of memory blocks.
+    pub fn memory_size(&self) -> u64 {
+        self.blocks
+            .iter()
+            .map(|block| {
+                let range = block.range();
+                range.end - range.start
+            }).sum()
+    }
+
+    /// Destroy resources and free memory blocks.
+    ///
+    /// # Safety
+    ///
+    /// Resources must not be used by device.
+    pub unsafe fn dispose<D>(self, device: &D, heaps: &mut Heaps<M>)
+    where
+        D: Device<Memory = M, Buffer = B, Image = I>,
+    {
+        for resource in self.resources {
+            match resource {
+                TransientResource::Buffer(buffer) => device.destroy_buffer(buffer),
+                TransientResource::Image(image) => device.destroy_image(image),
+            }
+        }
+        for block in self.blocks {
+            heaps.free(device, block);
+        }
+        self.relevant.dispose();
+    }
+}
@@ -1,12 +1,25 @@
 //! Buffer usage, creation-info and wrappers.
 
 mod usage;
+mod view;
 
 pub use self::usage::*;
-use memory::MemoryBlock;
+pub use self::view::*;
+
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use memory::{
+    usage::{Usage as MemoryUsage, UsageValue},
+    Heaps, MemoryBlock, Tag,
+};
 use relevant::Relevant;
 
+use device::Device;
 use escape::Escape;
+use frame::FrameIndex;
 use SharingMode;
 
 /// Contains information required to create a buffer.
@@ -28,15 +41,81 @@ pub struct CreateInfo {
 ///
 /// `T` - type of the memory object of memory block.
 /// `B` - raw buffer type.
+/// `U` - usage type of the buffer. Zero-sized presets like `VertexBuffer`
+/// allow functions to accept only buffers created for specific usage.
 #[derive(Debug)]
-pub struct Buffer<M, B> {
+pub struct Buffer<M, B, U = (UsageFlags, UsageValue)> {
     pub(crate) inner: Escape<Inner<M, B>>,
     pub(crate) info: CreateInfo,
+    pub(crate) usage: U,
+}
+
+impl<M, B, U> Buffer<M, B, U> {
+    /// Get usage the buffer was created with.
+    pub fn usage(&self) -> &U {
+        &self.usage
+    }
+
+    /// Convert into buffer with value-level usage.
+    pub fn into_dynamic(self) -> Buffer<M, B>
+    where
+        U: Usage,
+    {
+        Buffer {
+            usage: (self.usage.flags(), self.usage.memory().value()),
+            inner: self.inner,
+            info: self.info,
+        }
+    }
+
+    /// Set debug name of the buffer.
+    /// The name is forwarded to the device, shown in `Debug` output
+    /// and tags the memory block so that `Heaps::leaks` reports it.
+    /// Block tagged with anything but previous name keeps its tag.
+    pub fn set_name<D>(&mut self, device: &D, heaps: &mut Heaps<M>, name: &str)
+    where
+        M: 'static,
+        D: Device<Memory = M, Buffer = B>,
+    {
+        device.set_buffer_name(&self.inner.raw, name);
+        let name: Arc<str> = name.into();
+        if self.inner.block.tag() == self.inner.name.clone().map(Tag::from) {
+            heaps.set_tag(&mut self.inner.block, Some(name.clone().into()));
+        }
+        self.inner.name = Some(name);
+    }
+
+    /// Get debug name of the buffer.
+    pub fn name(&self) -> Option<&str> {
+        self.inner.name.as_ref().map(|name| &**name)
+    }
+
+    /// Mark the buffer as used by commands submitted in the `frame`.
+    /// Dropped buffer is destroyed only after the last frame that used it is complete.
+    pub fn mark_used(&self, frame: FrameIndex) {
+        self.inner
+            .last_use
+            .fetch_max(frame.value(), Ordering::Relaxed);
+    }
+
+    /// Get index of the last frame that used the buffer.
+    pub fn last_use(&self) -> Option<FrameIndex> {
+        match self.inner.last_use.load(Ordering::Relaxed) {
+            0 => None,
+            index => Some(unsafe { FrameIndex::new(index) }),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub(crate) struct Inner<M, B> {
     pub(crate) block: MemoryBlock<M>,
     pub(crate) raw: B,
+    /// Cloned by views of the buffer.
+    pub(crate) views: Arc<()>,
+    /// Index of the last frame that used the buffer. `0` if never used.
+    pub(crate) last_use: AtomicU64,
+    /// Debug name of the buffer.
+    pub(crate) name: Option<Arc<str>>,
     pub(crate) relevant: Relevant,
 }
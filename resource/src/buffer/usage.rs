@@ -146,3 +146,21 @@ impl Usage for DownloadBuffer {
         Download
     }
 }
+
+/// Type that specify that buffer is intended to be used as source of indirect draw and dispatch arguments.
+/// It implies `TRANSFER_DST` because device-local, host-invisible memory should be used
+/// and arguments are either copied into the buffer or written by shaders.
+#[derive(Clone, Copy, Debug)]
+pub struct IndirectBuffer;
+
+impl Usage for IndirectBuffer {
+    type MemoryUsage = Data;
+
+    fn flags(&self) -> UsageFlags {
+        UsageFlags::TRANSFER_DST | UsageFlags::INDIRECT_BUFFER
+    }
+
+    fn memory(&self) -> Data {
+        Data
+    }
+}
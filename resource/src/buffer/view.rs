@@ -0,0 +1,47 @@
+use std::{ops::Range, sync::Arc};
+
+use relevant::Relevant;
+
+use escape::Escape;
+use image::Format;
+
+/// Contains information required to create a buffer view.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ViewCreateInfo {
+    /// Format of texels in the buffer.
+    pub format: Format,
+
+    /// Range of the buffer accessible through the view.
+    pub range: Range<u64>,
+}
+
+/// Generic buffer view object wrapper.
+/// Buffer is not destroyed while its views are alive.
+///
+/// # Parameters
+///
+/// `V` - raw buffer view type.
+#[derive(Debug)]
+pub struct BufferView<V> {
+    pub(crate) inner: Escape<ViewInner<V>>,
+    pub(crate) info: ViewCreateInfo,
+}
+
+impl<V> BufferView<V> {
+    /// Get raw buffer view.
+    pub fn raw(&self) -> &V {
+        &self.inner.raw
+    }
+
+    /// Get info the view was created with.
+    pub fn info(&self) -> &ViewCreateInfo {
+        &self.info
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ViewInner<V> {
+    pub(crate) raw: V,
+    pub(crate) buffer: Arc<()>,
+    pub(crate) relevant: Relevant,
+}
@@ -2,6 +2,8 @@ use buffer;
 use error;
 use image;
 use memory;
+use sampler;
+use validation::{DeviceLimits, FormatProperties};
 use MemoryRequirements;
 
 /// Trait for resource creation, memory allocation and mapping.
@@ -35,12 +37,21 @@ pub trait Device: memory::Device + Sized {
     fn create_buffer(
         &self,
         info: buffer::CreateInfo,
-    ) -> Result<Self::UnboundBuffer, memory::OutOfMemoryError>;
+    ) -> Result<Self::UnboundBuffer, error::BufferCreationError>;
 
     /// Fetch buffer memory requirements.
     fn buffer_requirements(&self, buffer: &Self::UnboundBuffer) -> MemoryRequirements;
 
+    /// Get raw handle of the buffer.
+    /// Handle is passed to `memory::Device::allocate_dedicated`
+    /// when buffer gets memory object of its own.
+    /// Backends that never prefer nor require dedicated allocation can return `None`.
+    fn buffer_handle(&self, _buffer: &Self::UnboundBuffer) -> Option<u64> {
+        None
+    }
+
     /// Bind memory range to the buffer.
+    /// Buffer is destroyed if binding fails.
     ///
     /// # Safety
     ///
@@ -58,6 +69,19 @@ pub trait Device: memory::Device + Sized {
     /// Destroy buffer object.
     unsafe fn destroy_buffer(&self, buffer: Self::Buffer);
 
+    /// Destroy buffer object that was never bound to memory.
+    unsafe fn destroy_unbound_buffer(&self, buffer: Self::UnboundBuffer);
+
+    /// Create view to the buffer.
+    fn create_buffer_view(
+        &self,
+        buffer: &Self::Buffer,
+        info: buffer::ViewCreateInfo,
+    ) -> Result<Self::BufferView, error::ViewCreationError>;
+
+    /// Destroy buffer view object.
+    unsafe fn destroy_buffer_view(&self, view: Self::BufferView);
+
     /// Create new unbound image object.
     fn create_image(
         &self,
@@ -67,7 +91,16 @@ pub trait Device: memory::Device + Sized {
     /// Fetch image memory requirements.
     fn image_requirements(&self, image: &Self::UnboundImage) -> MemoryRequirements;
 
+    /// Get raw handle of the image.
+    /// Handle is passed to `memory::Device::allocate_dedicated`
+    /// when image gets memory object of its own.
+    /// Backends that never prefer nor require dedicated allocation can return `None`.
+    fn image_handle(&self, _image: &Self::UnboundImage) -> Option<u64> {
+        None
+    }
+
     /// Bind memory to the image.
+    /// Image is destroyed if binding fails.
     ///
     /// # Safety
     ///
@@ -84,4 +117,48 @@ pub trait Device: memory::Device + Sized {
 
     /// Destroy image object.
     unsafe fn destroy_image(&self, image: Self::Image);
+
+    /// Destroy image object that was never bound to memory.
+    unsafe fn destroy_unbound_image(&self, image: Self::UnboundImage);
+
+    /// Create view to the image.
+    fn create_image_view(
+        &self,
+        image: &Self::Image,
+        info: image::ViewCreateInfo,
+    ) -> Result<Self::ImageView, error::ViewCreationError>;
+
+    /// Destroy image view object.
+    unsafe fn destroy_image_view(&self, view: Self::ImageView);
+
+    /// Create new sampler object.
+    fn create_sampler(
+        &self,
+        info: sampler::CreateInfo,
+    ) -> Result<Self::Sampler, error::SamplerCreationError>;
+
+    /// Destroy sampler object.
+    unsafe fn destroy_sampler(&self, sampler: Self::Sampler);
+
+    /// Set debug name of the buffer object.
+    /// Backends can forward it to debug-utils object naming.
+    /// Default implementation ignores the name.
+    fn set_buffer_name(&self, _buffer: &Self::Buffer, _name: &str) {}
+
+    /// Set debug name of the image object.
+    /// Backends can forward it to debug-utils object naming.
+    /// Default implementation ignores the name.
+    fn set_image_name(&self, _image: &Self::Image, _name: &str) {}
+
+    /// Fetch device limits used to validate creation-info.
+    /// Creation-info isn't validated if `None` is returned.
+    fn limits(&self) -> Option<DeviceLimits> {
+        None
+    }
+
+    /// Fetch properties of the format used to validate creation-info.
+    /// Creation-info isn't validated if `None` is returned.
+    fn format_properties(&self, _format: image::Format) -> Option<FormatProperties> {
+        None
+    }
 }
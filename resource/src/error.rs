@@ -1,6 +1,23 @@
+use buffer;
 use image;
 use memory;
 
+/// Buffer creation error.
+#[derive(Clone, Copy, Debug, Fail)]
+pub enum BufferCreationError {
+    /// The usage requested isn't supported.
+    #[fail(display = "Unsupported usage")]
+    UnsupportedUsage(buffer::UsageFlags),
+
+    /// The sharing mode requested isn't supported by the backend.
+    #[fail(display = "Unsupported sharing mode")]
+    UnsupportedSharing,
+
+    /// The memory of the host or device is used up.
+    #[fail(display = "Out of memory")]
+    OutOfMemoryError(memory::OutOfMemoryError),
+}
+
 /// Image creation error.
 #[derive(Clone, Copy, Debug, Fail)]
 pub enum ImageCreationError {
@@ -28,11 +45,117 @@ pub enum ImageCreationError {
     #[fail(display = "Unsupported usage")]
     UnsupportedUsage(image::UsageFlags),
 
+    /// The sharing mode requested isn't supported by the backend.
+    #[fail(display = "Unsupported sharing mode")]
+    UnsupportedSharing,
+
+    /// The memory of the host or device is used up.
+    #[fail(display = "Out of memory")]
+    OutOfMemoryError(memory::OutOfMemoryError),
+}
+
+/// Buffer or image view creation error.
+#[derive(Clone, Copy, Debug, Fail)]
+pub enum ViewCreationError {
+    /// Format is not supported for the view.
+    #[fail(display = "Unsupported format")]
+    UnsupportedFormat(image::Format),
+
+    /// Resource wasn't created with usage required by the view.
+    #[fail(display = "Bad usage")]
+    BadUsage,
+
+    /// Subresource range is out of bounds of the resource.
+    #[fail(display = "Range is out of bounds")]
+    OutOfBounds,
+
+    /// The view isn't supported by the backend.
+    #[fail(display = "Unsupported view")]
+    Unsupported,
+
     /// The memory of the host or device is used up.
     #[fail(display = "Out of memory")]
     OutOfMemoryError(memory::OutOfMemoryError),
 }
 
+/// Sampler creation error.
+#[derive(Clone, Copy, Debug, Fail)]
+pub enum SamplerCreationError {
+    /// The sampler isn't supported by the backend.
+    #[fail(display = "Unsupported sampler")]
+    Unsupported,
+
+    /// The memory of the host or device is used up.
+    #[fail(display = "Out of memory")]
+    OutOfMemoryError(memory::OutOfMemoryError),
+}
+
+/// Error found by validation of creation-info against device limits and format features.
+#[derive(Clone, Copy, Debug, Fail)]
+pub enum ValidationError {
+    /// Format is undefined or not supported for the resource.
+    #[fail(display = "Unsupported format {:?}", _0)]
+    UnsupportedFormat(image::Format),
+
+    /// Image extent has zero dimension or doesn't match the image kind.
+    #[fail(display = "Invalid extent {:?}", _0)]
+    InvalidExtent(image::Extent3D),
+
+    /// Image extent exceeds device limits.
+    #[fail(display = "Extent {:?} is too large", _0)]
+    ExtentTooLarge(image::Extent3D),
+
+    /// Number of image layers is zero or exceeds device limits.
+    #[fail(display = "Unsupported number of layers {}", _0)]
+    TooManyLayers(u32),
+
+    /// Number of mip levels is zero or exceeds number of levels in full mip chain.
+    #[fail(display = "Unsupported number of mip levels {}", _0)]
+    TooManyMips(u32),
+
+    /// Cube-compatible image must be two-dimensional square image with at least six layers.
+    #[fail(display = "Image can't be cube-compatible")]
+    CubeIncompatible,
+
+    /// Sample count is not supported by the device.
+    #[fail(display = "Unsupported samples {:?}", _0)]
+    UnsupportedSamples(image::SampleCountFlags),
+
+    /// Multisampled image must be two-dimensional optimal-tiled image with single mip level
+    /// and can't be cube-compatible.
+    #[fail(display = "Image can't be multisampled")]
+    InvalidMultisampling,
+
+    /// Image usage not supported by the format.
+    #[fail(display = "Unsupported image usage {:?}", _0)]
+    UnsupportedUsage(image::UsageFlags),
+
+    /// Buffer usage required by the view is missing or not supported by the format.
+    #[fail(display = "Unsupported buffer usage {:?}", _0)]
+    UnsupportedBufferUsage(buffer::UsageFlags),
+
+    /// Number of texels in buffer view exceeds device limits.
+    #[fail(display = "Too many texels {}", _0)]
+    TooManyTexels(u64),
+
+    /// View range is out of bounds of the resource.
+    #[fail(display = "Range is out of bounds")]
+    OutOfBounds,
+
+    /// View range includes aspects the image format doesn't have.
+    #[fail(display = "Invalid aspects {:?}", _0)]
+    InvalidAspects(image::ImageAspectFlags),
+
+    /// View kind can't be created for the image.
+    #[fail(display = "Incompatible view kind {:?}", _0)]
+    IncompatibleViewKind(image::ViewKind),
+
+    /// View format differs from the image format and the image isn't mutable-format
+    /// or formats have different texel block layout.
+    #[fail(display = "Incompatible view format {:?}", _0)]
+    IncompatibleFormat(image::Format),
+}
+
 /// Resource binding error.
 #[derive(Clone, Copy, Debug, Fail)]
 pub enum BindError {
@@ -52,10 +175,26 @@ pub enum BindError {
 /// Generic resource error.
 #[derive(Clone, Copy, Debug, Fail)]
 pub enum ResourceError {
+    /// Buffer creation error.
+    #[fail(display = "Buffer creation error")]
+    BufferCreationError(BufferCreationError),
+
     /// Image creation error.
     #[fail(display = "Image creation error")]
     ImageCreationError(ImageCreationError),
 
+    /// View creation error.
+    #[fail(display = "View creation error")]
+    ViewCreationError(ViewCreationError),
+
+    /// Sampler creation error.
+    #[fail(display = "Sampler creation error")]
+    SamplerCreationError(SamplerCreationError),
+
+    /// Validation error.
+    #[fail(display = "Validation error")]
+    ValidationError(ValidationError),
+
     /// Memory error.
     #[fail(display = "Memory error")]
     MemoryError(memory::MemoryError),
@@ -63,6 +202,16 @@ pub enum ResourceError {
     /// Bind error.
     #[fail(display = "Bind error")]
     BindError(BindError),
+
+    /// Driver requires dedicated allocation but backend doesn't provide resource handle.
+    #[fail(display = "Dedicated allocation is required but resource handle is unknown")]
+    UnknownDedicatedResource,
+}
+
+impl From<BufferCreationError> for ResourceError {
+    fn from(error: BufferCreationError) -> Self {
+        ResourceError::BufferCreationError(error)
+    }
 }
 
 impl From<ImageCreationError> for ResourceError {
@@ -71,6 +220,24 @@ impl From<ImageCreationError> for ResourceError {
     }
 }
 
+impl From<ViewCreationError> for ResourceError {
+    fn from(error: ViewCreationError) -> Self {
+        ResourceError::ViewCreationError(error)
+    }
+}
+
+impl From<SamplerCreationError> for ResourceError {
+    fn from(error: SamplerCreationError) -> Self {
+        ResourceError::SamplerCreationError(error)
+    }
+}
+
+impl From<ValidationError> for ResourceError {
+    fn from(error: ValidationError) -> Self {
+        ResourceError::ValidationError(error)
+    }
+}
+
 impl From<memory::MemoryError> for ResourceError {
     fn from(error: memory::MemoryError) -> Self {
         ResourceError::MemoryError(error)
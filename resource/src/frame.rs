@@ -0,0 +1,24 @@
+//! Frame index used to track when resources are last used by the device.
+
+/// Unique index of the frame.
+/// It must be unique per render instance.
+/// Frames are indexed in submission order starting from `1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FrameIndex(u64);
+
+impl FrameIndex {
+    /// Create frame index from its value.
+    ///
+    /// # Safety
+    ///
+    /// Index must be unique per render instance.
+    /// Only frame generator should create indices.
+    pub unsafe fn new(index: u64) -> Self {
+        FrameIndex(index)
+    }
+
+    /// Get value of the index.
+    pub fn value(&self) -> u64 {
+        self.0
+    }
+}
@@ -2,6 +2,42 @@
 
 pub use self::Format::*;
 
+use super::{Extent3D, ImageAspectFlags};
+
+/// Numeric type of the format channels.
+/// See Vulkan docs for details:
+/// <https://www.khronos.org/registry/vulkan/specs/1.1-extensions/html/vkspec.html#_identification_of_formats>
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ChannelType {
+    /// Unsigned normalized values in range `[0, 1]`.
+    Unorm,
+
+    /// Signed normalized values in range `[-1, 1]`.
+    Snorm,
+
+    /// Unsigned integer values converted to floating-point.
+    Uscaled,
+
+    /// Signed integer values converted to floating-point.
+    Sscaled,
+
+    /// Unsigned integer values.
+    Uint,
+
+    /// Signed integer values.
+    Sint,
+
+    /// Unsigned floating-point values.
+    Ufloat,
+
+    /// Signed floating-point values.
+    Sfloat,
+
+    /// Unsigned normalized values with sRGB nonlinear encoding of the color channels.
+    /// Alpha channel, if present, is linear.
+    Srgb,
+}
+
 /// Image format.
 #[allow(bad_style, missing_docs)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -268,3 +304,644 @@ pub enum Format {
     // G16_B16R16_2PLANE_422_UNORM_KHR = Format::G16_B16R16_2PLANE_422_UNORM as isize,
     // G16_B16_R16_3PLANE_444_UNORM_KHR = Format::G16_B16_R16_3PLANE_444_UNORM as isize,
 }
+
+impl Format {
+    /// Size of the texel block in bytes.
+    /// Texel block is a single texel for uncompressed formats.
+    /// For multi-planar formats this is the combined size of the texel in all planes.
+    /// `UNDEFINED` has size `0`.
+    pub fn block_size(&self) -> u32 {
+        match *self {
+            UNDEFINED => 0,
+            R4G4_UNORM_PACK8 | R8_UNORM | R8_SNORM | R8_USCALED | R8_SSCALED | R8_UINT
+            | R8_SINT | R8_SRGB | S8_UINT => 1,
+            R4G4B4A4_UNORM_PACK16
+            | B4G4R4A4_UNORM_PACK16
+            | R5G6B5_UNORM_PACK16
+            | B5G6R5_UNORM_PACK16
+            | R5G5B5A1_UNORM_PACK16
+            | B5G5R5A1_UNORM_PACK16
+            | A1R5G5B5_UNORM_PACK16
+            | R8G8_UNORM
+            | R8G8_SNORM
+            | R8G8_USCALED
+            | R8G8_SSCALED
+            | R8G8_UINT
+            | R8G8_SINT
+            | R8G8_SRGB
+            | R16_UNORM
+            | R16_SNORM
+            | R16_USCALED
+            | R16_SSCALED
+            | R16_UINT
+            | R16_SINT
+            | R16_SFLOAT
+            | D16_UNORM
+            | R10X6_UNORM_PACK16
+            | R12X4_UNORM_PACK16 => 2,
+            R8G8B8_UNORM
+            | R8G8B8_SNORM
+            | R8G8B8_USCALED
+            | R8G8B8_SSCALED
+            | R8G8B8_UINT
+            | R8G8B8_SINT
+            | R8G8B8_SRGB
+            | B8G8R8_UNORM
+            | B8G8R8_SNORM
+            | B8G8R8_USCALED
+            | B8G8R8_SSCALED
+            | B8G8R8_UINT
+            | B8G8R8_SINT
+            | B8G8R8_SRGB
+            | D16_UNORM_S8_UINT
+            | G8_B8_R8_3PLANE_420_UNORM
+            | G8_B8R8_2PLANE_420_UNORM
+            | G8_B8_R8_3PLANE_422_UNORM
+            | G8_B8R8_2PLANE_422_UNORM
+            | G8_B8_R8_3PLANE_444_UNORM => 3,
+            R8G8B8A8_UNORM
+            | R8G8B8A8_SNORM
+            | R8G8B8A8_USCALED
+            | R8G8B8A8_SSCALED
+            | R8G8B8A8_UINT
+            | R8G8B8A8_SINT
+            | R8G8B8A8_SRGB
+            | B8G8R8A8_UNORM
+            | B8G8R8A8_SNORM
+            | B8G8R8A8_USCALED
+            | B8G8R8A8_SSCALED
+            | B8G8R8A8_UINT
+            | B8G8R8A8_SINT
+            | B8G8R8A8_SRGB
+            | A8B8G8R8_UNORM_PACK32
+            | A8B8G8R8_SNORM_PACK32
+            | A8B8G8R8_USCALED_PACK32
+            | A8B8G8R8_SSCALED_PACK32
+            | A8B8G8R8_UINT_PACK32
+            | A8B8G8R8_SINT_PACK32
+            | A8B8G8R8_SRGB_PACK32
+            | A2R10G10B10_UNORM_PACK32
+            | A2R10G10B10_SNORM_PACK32
+            | A2R10G10B10_USCALED_PACK32
+            | A2R10G10B10_SSCALED_PACK32
+            | A2R10G10B10_UINT_PACK32
+            | A2R10G10B10_SINT_PACK32
+            | A2B10G10R10_UNORM_PACK32
+            | A2B10G10R10_SNORM_PACK32
+            | A2B10G10R10_USCALED_PACK32
+            | A2B10G10R10_SSCALED_PACK32
+            | A2B10G10R10_UINT_PACK32
+            | A2B10G10R10_SINT_PACK32
+            | R16G16_UNORM
+            | R16G16_SNORM
+            | R16G16_USCALED
+            | R16G16_SSCALED
+            | R16G16_UINT
+            | R16G16_SINT
+            | R16G16_SFLOAT
+            | R32_UINT
+            | R32_SINT
+            | R32_SFLOAT
+            | B10G11R11_UFLOAT_PACK32
+            | E5B9G9R9_UFLOAT_PACK32
+            | X8_D24_UNORM_PACK32
+            | D32_SFLOAT
+            | D24_UNORM_S8_UINT
+            | G8B8G8R8_422_UNORM
+            | B8G8R8G8_422_UNORM
+            | R10X6G10X6_UNORM_2PACK16
+            | R12X4G12X4_UNORM_2PACK16 => 4,
+            D32_SFLOAT_S8_UINT => 5,
+            R16G16B16_UNORM
+            | R16G16B16_SNORM
+            | R16G16B16_USCALED
+            | R16G16B16_SSCALED
+            | R16G16B16_UINT
+            | R16G16B16_SINT
+            | R16G16B16_SFLOAT
+            | G10X6_B10X6_R10X6_3PLANE_420_UNORM_3PACK16
+            | G10X6_B10X6R10X6_2PLANE_420_UNORM_3PACK16
+            | G10X6_B10X6_R10X6_3PLANE_422_UNORM_3PACK16
+            | G10X6_B10X6R10X6_2PLANE_422_UNORM_3PACK16
+            | G10X6_B10X6_R10X6_3PLANE_444_UNORM_3PACK16
+            | G12X4_B12X4_R12X4_3PLANE_420_UNORM_3PACK16
+            | G12X4_B12X4R12X4_2PLANE_420_UNORM_3PACK16
+            | G12X4_B12X4_R12X4_3PLANE_422_UNORM_3PACK16
+            | G12X4_B12X4R12X4_2PLANE_422_UNORM_3PACK16
+            | G12X4_B12X4_R12X4_3PLANE_444_UNORM_3PACK16
+            | G16_B16_R16_3PLANE_420_UNORM
+            | G16_B16R16_2PLANE_420_UNORM
+            | G16_B16_R16_3PLANE_422_UNORM
+            | G16_B16R16_2PLANE_422_UNORM
+            | G16_B16_R16_3PLANE_444_UNORM => 6,
+            R16G16B16A16_UNORM
+            | R16G16B16A16_SNORM
+            | R16G16B16A16_USCALED
+            | R16G16B16A16_SSCALED
+            | R16G16B16A16_UINT
+            | R16G16B16A16_SINT
+            | R16G16B16A16_SFLOAT
+            | R32G32_UINT
+            | R32G32_SINT
+            | R32G32_SFLOAT
+            | R64_UINT
+            | R64_SINT
+            | R64_SFLOAT
+            | R10X6G10X6B10X6A10X6_UNORM_4PACK16
+            | G10X6B10X6G10X6R10X6_422_UNORM_4PACK16
+            | B10X6G10X6R10X6G10X6_422_UNORM_4PACK16
+            | R12X4G12X4B12X4A12X4_UNORM_4PACK16
+            | G12X4B12X4G12X4R12X4_422_UNORM_4PACK16
+            | B12X4G12X4R12X4G12X4_422_UNORM_4PACK16
+            | G16B16G16R16_422_UNORM
+            | B16G16R16G16_422_UNORM => 8,
+            BC1_RGB_UNORM_BLOCK
+            | BC1_RGB_SRGB_BLOCK
+            | BC1_RGBA_UNORM_BLOCK
+            | BC1_RGBA_SRGB_BLOCK
+            | BC4_UNORM_BLOCK
+            | BC4_SNORM_BLOCK
+            | ETC2_R8G8B8_UNORM_BLOCK
+            | ETC2_R8G8B8_SRGB_BLOCK
+            | ETC2_R8G8B8A1_UNORM_BLOCK
+            | ETC2_R8G8B8A1_SRGB_BLOCK
+            | EAC_R11_UNORM_BLOCK
+            | EAC_R11_SNORM_BLOCK
+            | PVRTC1_2BPP_UNORM_BLOCK_IMG
+            | PVRTC1_4BPP_UNORM_BLOCK_IMG
+            | PVRTC2_2BPP_UNORM_BLOCK_IMG
+            | PVRTC2_4BPP_UNORM_BLOCK_IMG
+            | PVRTC1_2BPP_SRGB_BLOCK_IMG
+            | PVRTC1_4BPP_SRGB_BLOCK_IMG
+            | PVRTC2_2BPP_SRGB_BLOCK_IMG
+            | PVRTC2_4BPP_SRGB_BLOCK_IMG => 8,
+            R32G32B32_UINT | R32G32B32_SINT | R32G32B32_SFLOAT => 12,
+            R32G32B32A32_UINT | R32G32B32A32_SINT | R32G32B32A32_SFLOAT | R64G64_UINT
+            | R64G64_SINT | R64G64_SFLOAT => 16,
+            BC2_UNORM_BLOCK
+            | BC2_SRGB_BLOCK
+            | BC3_UNORM_BLOCK
+            | BC3_SRGB_BLOCK
+            | BC5_UNORM_BLOCK
+            | BC5_SNORM_BLOCK
+            | BC6H_UFLOAT_BLOCK
+            | BC6H_SFLOAT_BLOCK
+            | BC7_UNORM_BLOCK
+            | BC7_SRGB_BLOCK
+            | ETC2_R8G8B8A8_UNORM_BLOCK
+            | ETC2_R8G8B8A8_SRGB_BLOCK
+            | EAC_R11G11_UNORM_BLOCK
+            | EAC_R11G11_SNORM_BLOCK
+            | ASTC_4x4_UNORM_BLOCK
+            | ASTC_4x4_SRGB_BLOCK
+            | ASTC_5x4_UNORM_BLOCK
+            | ASTC_5x4_SRGB_BLOCK
+            | ASTC_5x5_UNORM_BLOCK
+            | ASTC_5x5_SRGB_BLOCK
+            | ASTC_6x5_UNORM_BLOCK
+            | ASTC_6x5_SRGB_BLOCK
+            | ASTC_6x6_UNORM_BLOCK
+            | ASTC_6x6_SRGB_BLOCK
+            | ASTC_8x5_UNORM_BLOCK
+            | ASTC_8x5_SRGB_BLOCK
+            | ASTC_8x6_UNORM_BLOCK
+            | ASTC_8x6_SRGB_BLOCK
+            | ASTC_8x8_UNORM_BLOCK
+            | ASTC_8x8_SRGB_BLOCK
+            | ASTC_10x5_UNORM_BLOCK
+            | ASTC_10x5_SRGB_BLOCK
+            | ASTC_10x6_UNORM_BLOCK
+            | ASTC_10x6_SRGB_BLOCK
+            | ASTC_10x8_UNORM_BLOCK
+            | ASTC_10x8_SRGB_BLOCK
+            | ASTC_10x10_UNORM_BLOCK
+            | ASTC_10x10_SRGB_BLOCK
+            | ASTC_12x10_UNORM_BLOCK
+            | ASTC_12x10_SRGB_BLOCK
+            | ASTC_12x12_UNORM_BLOCK
+            | ASTC_12x12_SRGB_BLOCK => 16,
+            R64G64B64_UINT | R64G64B64_SINT | R64G64B64_SFLOAT => 24,
+            R64G64B64A64_UINT | R64G64B64A64_SINT | R64G64B64A64_SFLOAT => 32,
+        }
+    }
+
+    /// Size of the texel block in texels.
+    /// It is `1x1x1` for all formats except compressed and `_422` formats.
+    pub fn block_extent(&self) -> Extent3D {
+        let (width, height) = match *self {
+            BC1_RGB_UNORM_BLOCK
+            | BC1_RGB_SRGB_BLOCK
+            | BC1_RGBA_UNORM_BLOCK
+            | BC1_RGBA_SRGB_BLOCK
+            | BC2_UNORM_BLOCK
+            | BC2_SRGB_BLOCK
+            | BC3_UNORM_BLOCK
+            | BC3_SRGB_BLOCK
+            | BC4_UNORM_BLOCK
+            | BC4_SNORM_BLOCK
+            | BC5_UNORM_BLOCK
+            | BC5_SNORM_BLOCK
+            | BC6H_UFLOAT_BLOCK
+            | BC6H_SFLOAT_BLOCK
+            | BC7_UNORM_BLOCK
+            | BC7_SRGB_BLOCK
+            | ETC2_R8G8B8_UNORM_BLOCK
+            | ETC2_R8G8B8_SRGB_BLOCK
+            | ETC2_R8G8B8A1_UNORM_BLOCK
+            | ETC2_R8G8B8A1_SRGB_BLOCK
+            | ETC2_R8G8B8A8_UNORM_BLOCK
+            | ETC2_R8G8B8A8_SRGB_BLOCK
+            | EAC_R11_UNORM_BLOCK
+            | EAC_R11_SNORM_BLOCK
+            | EAC_R11G11_UNORM_BLOCK
+            | EAC_R11G11_SNORM_BLOCK
+            | ASTC_4x4_UNORM_BLOCK
+            | ASTC_4x4_SRGB_BLOCK
+            | PVRTC1_4BPP_UNORM_BLOCK_IMG
+            | PVRTC2_4BPP_UNORM_BLOCK_IMG
+            | PVRTC1_4BPP_SRGB_BLOCK_IMG
+            | PVRTC2_4BPP_SRGB_BLOCK_IMG => (4, 4),
+            PVRTC1_2BPP_UNORM_BLOCK_IMG
+            | PVRTC2_2BPP_UNORM_BLOCK_IMG
+            | PVRTC1_2BPP_SRGB_BLOCK_IMG
+            | PVRTC2_2BPP_SRGB_BLOCK_IMG => (8, 4),
+            ASTC_5x4_UNORM_BLOCK | ASTC_5x4_SRGB_BLOCK => (5, 4),
+            ASTC_5x5_UNORM_BLOCK | ASTC_5x5_SRGB_BLOCK => (5, 5),
+            ASTC_6x5_UNORM_BLOCK | ASTC_6x5_SRGB_BLOCK => (6, 5),
+            ASTC_6x6_UNORM_BLOCK | ASTC_6x6_SRGB_BLOCK => (6, 6),
+            ASTC_8x5_UNORM_BLOCK | ASTC_8x5_SRGB_BLOCK => (8, 5),
+            ASTC_8x6_UNORM_BLOCK | ASTC_8x6_SRGB_BLOCK => (8, 6),
+            ASTC_8x8_UNORM_BLOCK | ASTC_8x8_SRGB_BLOCK => (8, 8),
+            ASTC_10x5_UNORM_BLOCK | ASTC_10x5_SRGB_BLOCK => (10, 5),
+            ASTC_10x6_UNORM_BLOCK | ASTC_10x6_SRGB_BLOCK => (10, 6),
+            ASTC_10x8_UNORM_BLOCK | ASTC_10x8_SRGB_BLOCK => (10, 8),
+            ASTC_10x10_UNORM_BLOCK | ASTC_10x10_SRGB_BLOCK => (10, 10),
+            ASTC_12x10_UNORM_BLOCK | ASTC_12x10_SRGB_BLOCK => (12, 10),
+            ASTC_12x12_UNORM_BLOCK | ASTC_12x12_SRGB_BLOCK => (12, 12),
+            G8B8G8R8_422_UNORM
+            | B8G8R8G8_422_UNORM
+            | G10X6B10X6G10X6R10X6_422_UNORM_4PACK16
+            | B10X6G10X6R10X6G10X6_422_UNORM_4PACK16
+            | G12X4B12X4G12X4R12X4_422_UNORM_4PACK16
+            | B12X4G12X4R12X4G12X4_422_UNORM_4PACK16
+            | G16B16G16R16_422_UNORM
+            | B16G16R16G16_422_UNORM => (2, 1),
+            _ => (1, 1),
+        };
+
+        Extent3D {
+            width,
+            height,
+            depth: 1,
+        }
+    }
+
+    /// Size in bytes of the region with specified extent when texel blocks are tightly packed.
+    /// Extent is rounded up to whole texel blocks.
+    /// Returns `None` for depth-stencil and multi-planar formats
+    /// as those are copied one aspect or plane at a time.
+    pub fn packed_size(&self, extent: Extent3D) -> Option<u64> {
+        if self.is_multi_planar()
+            || self.aspects() == ImageAspectFlags::DEPTH | ImageAspectFlags::STENCIL
+        {
+            return None;
+        }
+
+        let block = self.block_extent();
+        let blocks = |size: u32, block: u32| ((size + block - 1) / block) as u64;
+        Some(
+            blocks(extent.width, block.width)
+                * blocks(extent.height, block.height)
+                * blocks(extent.depth, block.depth)
+                * self.block_size() as u64,
+        )
+    }
+
+    /// Aspects of the image with this format.
+    /// `UNDEFINED` has no aspects.
+    pub fn aspects(&self) -> ImageAspectFlags {
+        match *self {
+            UNDEFINED => ImageAspectFlags::empty(),
+            D16_UNORM | X8_D24_UNORM_PACK32 | D32_SFLOAT => ImageAspectFlags::DEPTH,
+            S8_UINT => ImageAspectFlags::STENCIL,
+            D16_UNORM_S8_UINT | D24_UNORM_S8_UINT | D32_SFLOAT_S8_UINT => {
+                ImageAspectFlags::DEPTH | ImageAspectFlags::STENCIL
+            }
+            _ => ImageAspectFlags::COLOR,
+        }
+    }
+
+    /// Check if format is block-compressed.
+    /// `_422` formats are not considered compressed even though their texel blocks are `2x1`.
+    pub fn is_compressed(&self) -> bool {
+        match *self {
+            BC1_RGB_UNORM_BLOCK
+            | BC1_RGB_SRGB_BLOCK
+            | BC1_RGBA_UNORM_BLOCK
+            | BC1_RGBA_SRGB_BLOCK
+            | BC2_UNORM_BLOCK
+            | BC2_SRGB_BLOCK
+            | BC3_UNORM_BLOCK
+            | BC3_SRGB_BLOCK
+            | BC4_UNORM_BLOCK
+            | BC4_SNORM_BLOCK
+            | BC5_UNORM_BLOCK
+            | BC5_SNORM_BLOCK
+            | BC6H_UFLOAT_BLOCK
+            | BC6H_SFLOAT_BLOCK
+            | BC7_UNORM_BLOCK
+            | BC7_SRGB_BLOCK
+            | ETC2_R8G8B8_UNORM_BLOCK
+            | ETC2_R8G8B8_SRGB_BLOCK
+            | ETC2_R8G8B8A1_UNORM_BLOCK
+            | ETC2_R8G8B8A1_SRGB_BLOCK
+            | ETC2_R8G8B8A8_UNORM_BLOCK
+            | ETC2_R8G8B8A8_SRGB_BLOCK
+            | EAC_R11_UNORM_BLOCK
+            | EAC_R11_SNORM_BLOCK
+            | EAC_R11G11_UNORM_BLOCK
+            | EAC_R11G11_SNORM_BLOCK
+            | ASTC_4x4_UNORM_BLOCK
+            | ASTC_4x4_SRGB_BLOCK
+            | ASTC_5x4_UNORM_BLOCK
+            | ASTC_5x4_SRGB_BLOCK
+            | ASTC_5x5_UNORM_BLOCK
+            | ASTC_5x5_SRGB_BLOCK
+            | ASTC_6x5_UNORM_BLOCK
+            | ASTC_6x5_SRGB_BLOCK
+            | ASTC_6x6_UNORM_BLOCK
+            | ASTC_6x6_SRGB_BLOCK
+            | ASTC_8x5_UNORM_BLOCK
+            | ASTC_8x5_SRGB_BLOCK
+            | ASTC_8x6_UNORM_BLOCK
+            | ASTC_8x6_SRGB_BLOCK
+            | ASTC_8x8_UNORM_BLOCK
+            | ASTC_8x8_SRGB_BLOCK
+            | ASTC_10x5_UNORM_BLOCK
+            | ASTC_10x5_SRGB_BLOCK
+            | ASTC_10x6_UNORM_BLOCK
+            | ASTC_10x6_SRGB_BLOCK
+            | ASTC_10x8_UNORM_BLOCK
+            | ASTC_10x8_SRGB_BLOCK
+            | ASTC_10x10_UNORM_BLOCK
+            | ASTC_10x10_SRGB_BLOCK
+            | ASTC_12x10_UNORM_BLOCK
+            | ASTC_12x10_SRGB_BLOCK
+            | ASTC_12x12_UNORM_BLOCK
+            | ASTC_12x12_SRGB_BLOCK
+            | PVRTC1_2BPP_UNORM_BLOCK_IMG
+            | PVRTC1_4BPP_UNORM_BLOCK_IMG
+            | PVRTC2_2BPP_UNORM_BLOCK_IMG
+            | PVRTC2_4BPP_UNORM_BLOCK_IMG
+            | PVRTC1_2BPP_SRGB_BLOCK_IMG
+            | PVRTC1_4BPP_SRGB_BLOCK_IMG
+            | PVRTC2_2BPP_SRGB_BLOCK_IMG
+            | PVRTC2_4BPP_SRGB_BLOCK_IMG => true,
+            _ => false,
+        }
+    }
+
+    /// Check if format stores components in separate planes.
+    pub fn is_multi_planar(&self) -> bool {
+        match *self {
+            G8_B8_R8_3PLANE_420_UNORM
+            | G8_B8R8_2PLANE_420_UNORM
+            | G8_B8_R8_3PLANE_422_UNORM
+            | G8_B8R8_2PLANE_422_UNORM
+            | G8_B8_R8_3PLANE_444_UNORM
+            | G10X6_B10X6_R10X6_3PLANE_420_UNORM_3PACK16
+            | G10X6_B10X6R10X6_2PLANE_420_UNORM_3PACK16
+            | G10X6_B10X6_R10X6_3PLANE_422_UNORM_3PACK16
+            | G10X6_B10X6R10X6_2PLANE_422_UNORM_3PACK16
+            | G10X6_B10X6_R10X6_3PLANE_444_UNORM_3PACK16
+            | G12X4_B12X4_R12X4_3PLANE_420_UNORM_3PACK16
+            | G12X4_B12X4R12X4_2PLANE_420_UNORM_3PACK16
+            | G12X4_B12X4_R12X4_3PLANE_422_UNORM_3PACK16
+            | G12X4_B12X4R12X4_2PLANE_422_UNORM_3PACK16
+            | G12X4_B12X4_R12X4_3PLANE_444_UNORM_3PACK16
+            | G16_B16_R16_3PLANE_420_UNORM
+            | G16_B16R16_2PLANE_420_UNORM
+            | G16_B16_R16_3PLANE_422_UNORM
+            | G16_B16R16_2PLANE_422_UNORM
+            | G16_B16_R16_3PLANE_444_UNORM => true,
+            _ => false,
+        }
+    }
+
+    /// Check if color channels of the format use sRGB nonlinear encoding.
+    pub fn is_srgb(&self) -> bool {
+        self.channel_type() == Some(ChannelType::Srgb)
+    }
+
+    /// Get format that differs from this one only in sRGB encoding.
+    /// Returns sRGB format for linear one and vice versa.
+    /// Returns `None` if there is no such format.
+    pub fn srgb_counterpart(&self) -> Option<Format> {
+        Some(match *self {
+            R8_UNORM => R8_SRGB,
+            R8_SRGB => R8_UNORM,
+            R8G8_UNORM => R8G8_SRGB,
+            R8G8_SRGB => R8G8_UNORM,
+            R8G8B8_UNORM => R8G8B8_SRGB,
+            R8G8B8_SRGB => R8G8B8_UNORM,
+            B8G8R8_UNORM => B8G8R8_SRGB,
+            B8G8R8_SRGB => B8G8R8_UNORM,
+            R8G8B8A8_UNORM => R8G8B8A8_SRGB,
+            R8G8B8A8_SRGB => R8G8B8A8_UNORM,
+            B8G8R8A8_UNORM => B8G8R8A8_SRGB,
+            B8G8R8A8_SRGB => B8G8R8A8_UNORM,
+            A8B8G8R8_UNORM_PACK32 => A8B8G8R8_SRGB_PACK32,
+            A8B8G8R8_SRGB_PACK32 => A8B8G8R8_UNORM_PACK32,
+            BC1_RGB_UNORM_BLOCK => BC1_RGB_SRGB_BLOCK,
+            BC1_RGB_SRGB_BLOCK => BC1_RGB_UNORM_BLOCK,
+            BC1_RGBA_UNORM_BLOCK => BC1_RGBA_SRGB_BLOCK,
+            BC1_RGBA_SRGB_BLOCK => BC1_RGBA_UNORM_BLOCK,
+            BC2_UNORM_BLOCK => BC2_SRGB_BLOCK,
+            BC2_SRGB_BLOCK => BC2_UNORM_BLOCK,
+            BC3_UNORM_BLOCK => BC3_SRGB_BLOCK,
+            BC3_SRGB_BLOCK => BC3_UNORM_BLOCK,
+            BC7_UNORM_BLOCK => BC7_SRGB_BLOCK,
+            BC7_SRGB_BLOCK => BC7_UNORM_BLOCK,
+            ETC2_R8G8B8_UNORM_BLOCK => ETC2_R8G8B8_SRGB_BLOCK,
+            ETC2_R8G8B8_SRGB_BLOCK => ETC2_R8G8B8_UNORM_BLOCK,
+            ETC2_R8G8B8A1_UNORM_BLOCK => ETC2_R8G8B8A1_SRGB_BLOCK,
+            ETC2_R8G8B8A1_SRGB_BLOCK => ETC2_R8G8B8A1_UNORM_BLOCK,
+            ETC2_R8G8B8A8_UNORM_BLOCK => ETC2_R8G8B8A8_SRGB_BLOCK,
+            ETC2_R8G8B8A8_SRGB_BLOCK => ETC2_R8G8B8A8_UNORM_BLOCK,
+            ASTC_4x4_UNORM_BLOCK => ASTC_4x4_SRGB_BLOCK,
+            ASTC_4x4_SRGB_BLOCK => ASTC_4x4_UNORM_BLOCK,
+            ASTC_5x4_UNORM_BLOCK => ASTC_5x4_SRGB_BLOCK,
+            ASTC_5x4_SRGB_BLOCK => ASTC_5x4_UNORM_BLOCK,
+            ASTC_5x5_UNORM_BLOCK => ASTC_5x5_SRGB_BLOCK,
+            ASTC_5x5_SRGB_BLOCK => ASTC_5x5_UNORM_BLOCK,
+            ASTC_6x5_UNORM_BLOCK => ASTC_6x5_SRGB_BLOCK,
+            ASTC_6x5_SRGB_BLOCK => ASTC_6x5_UNORM_BLOCK,
+            ASTC_6x6_UNORM_BLOCK => ASTC_6x6_SRGB_BLOCK,
+            ASTC_6x6_SRGB_BLOCK => ASTC_6x6_UNORM_BLOCK,
+            ASTC_8x5_UNORM_BLOCK => ASTC_8x5_SRGB_BLOCK,
+            ASTC_8x5_SRGB_BLOCK => ASTC_8x5_UNORM_BLOCK,
+            ASTC_8x6_UNORM_BLOCK => ASTC_8x6_SRGB_BLOCK,
+            ASTC_8x6_SRGB_BLOCK => ASTC_8x6_UNORM_BLOCK,
+            ASTC_8x8_UNORM_BLOCK => ASTC_8x8_SRGB_BLOCK,
+            ASTC_8x8_SRGB_BLOCK => ASTC_8x8_UNORM_BLOCK,
+            ASTC_10x5_UNORM_BLOCK => ASTC_10x5_SRGB_BLOCK,
+            ASTC_10x5_SRGB_BLOCK => ASTC_10x5_UNORM_BLOCK,
+            ASTC_10x6_UNORM_BLOCK => ASTC_10x6_SRGB_BLOCK,
+            ASTC_10x6_SRGB_BLOCK => ASTC_10x6_UNORM_BLOCK,
+            ASTC_10x8_UNORM_BLOCK => ASTC_10x8_SRGB_BLOCK,
+            ASTC_10x8_SRGB_BLOCK => ASTC_10x8_UNORM_BLOCK,
+            ASTC_10x10_UNORM_BLOCK => ASTC_10x10_SRGB_BLOCK,
+            ASTC_10x10_SRGB_BLOCK => ASTC_10x10_UNORM_BLOCK,
+            ASTC_12x10_UNORM_BLOCK => ASTC_12x10_SRGB_BLOCK,
+            ASTC_12x10_SRGB_BLOCK => ASTC_12x10_UNORM_BLOCK,
+            ASTC_12x12_UNORM_BLOCK => ASTC_12x12_SRGB_BLOCK,
+            ASTC_12x12_SRGB_BLOCK => ASTC_12x12_UNORM_BLOCK,
+            PVRTC1_2BPP_UNORM_BLOCK_IMG => PVRTC1_2BPP_SRGB_BLOCK_IMG,
+            PVRTC1_2BPP_SRGB_BLOCK_IMG => PVRTC1_2BPP_UNORM_BLOCK_IMG,
+            PVRTC1_4BPP_UNORM_BLOCK_IMG => PVRTC1_4BPP_SRGB_BLOCK_IMG,
+            PVRTC1_4BPP_SRGB_BLOCK_IMG => PVRTC1_4BPP_UNORM_BLOCK_IMG,
+            PVRTC2_2BPP_UNORM_BLOCK_IMG => PVRTC2_2BPP_SRGB_BLOCK_IMG,
+            PVRTC2_2BPP_SRGB_BLOCK_IMG => PVRTC2_2BPP_UNORM_BLOCK_IMG,
+            PVRTC2_4BPP_UNORM_BLOCK_IMG => PVRTC2_4BPP_SRGB_BLOCK_IMG,
+            PVRTC2_4BPP_SRGB_BLOCK_IMG => PVRTC2_4BPP_UNORM_BLOCK_IMG,
+            _ => return None,
+        })
+    }
+
+    /// Numeric type of the format channels.
+    /// For combined depth-stencil formats type of the depth channel is returned,
+    /// stencil is always `Uint`.
+    /// Returns `None` for `UNDEFINED`.
+    pub fn channel_type(&self) -> Option<ChannelType> {
+        Some(match *self {
+            UNDEFINED => return None,
+            R8_SNORM
+            | R8G8_SNORM
+            | R8G8B8_SNORM
+            | B8G8R8_SNORM
+            | R8G8B8A8_SNORM
+            | B8G8R8A8_SNORM
+            | A8B8G8R8_SNORM_PACK32
+            | A2R10G10B10_SNORM_PACK32
+            | A2B10G10R10_SNORM_PACK32
+            | R16_SNORM
+            | R16G16_SNORM
+            | R16G16B16_SNORM
+            | R16G16B16A16_SNORM
+            | BC4_SNORM_BLOCK
+            | BC5_SNORM_BLOCK
+            | EAC_R11_SNORM_BLOCK
+            | EAC_R11G11_SNORM_BLOCK => ChannelType::Snorm,
+            R8_USCALED
+            | R8G8_USCALED
+            | R8G8B8_USCALED
+            | B8G8R8_USCALED
+            | R8G8B8A8_USCALED
+            | B8G8R8A8_USCALED
+            | A8B8G8R8_USCALED_PACK32
+            | A2R10G10B10_USCALED_PACK32
+            | A2B10G10R10_USCALED_PACK32
+            | R16_USCALED
+            | R16G16_USCALED
+            | R16G16B16_USCALED
+            | R16G16B16A16_USCALED => ChannelType::Uscaled,
+            R8_SSCALED
+            | R8G8_SSCALED
+            | R8G8B8_SSCALED
+            | B8G8R8_SSCALED
+            | R8G8B8A8_SSCALED
+            | B8G8R8A8_SSCALED
+            | A8B8G8R8_SSCALED_PACK32
+            | A2R10G10B10_SSCALED_PACK32
+            | A2B10G10R10_SSCALED_PACK32
+            | R16_SSCALED
+            | R16G16_SSCALED
+            | R16G16B16_SSCALED
+            | R16G16B16A16_SSCALED => ChannelType::Sscaled,
+            R8_UINT
+            | R8G8_UINT
+            | R8G8B8_UINT
+            | B8G8R8_UINT
+            | R8G8B8A8_UINT
+            | B8G8R8A8_UINT
+            | A8B8G8R8_UINT_PACK32
+            | A2R10G10B10_UINT_PACK32
+            | A2B10G10R10_UINT_PACK32
+            | R16_UINT
+            | R16G16_UINT
+            | R16G16B16_UINT
+            | R16G16B16A16_UINT
+            | R32_UINT
+            | R32G32_UINT
+            | R32G32B32_UINT
+            | R32G32B32A32_UINT
+            | R64_UINT
+            | R64G64_UINT
+            | R64G64B64_UINT
+            | R64G64B64A64_UINT
+            | S8_UINT => ChannelType::Uint,
+            R8_SINT
+            | R8G8_SINT
+            | R8G8B8_SINT
+            | B8G8R8_SINT
+            | R8G8B8A8_SINT
+            | B8G8R8A8_SINT
+            | A8B8G8R8_SINT_PACK32
+            | A2R10G10B10_SINT_PACK32
+            | A2B10G10R10_SINT_PACK32
+            | R16_SINT
+            | R16G16_SINT
+            | R16G16B16_SINT
+            | R16G16B16A16_SINT
+            | R32_SINT
+            | R32G32_SINT
+            | R32G32B32_SINT
+            | R32G32B32A32_SINT
+            | R64_SINT
+            | R64G64_SINT
+            | R64G64B64_SINT
+            | R64G64B64A64_SINT => ChannelType::Sint,
+            B10G11R11_UFLOAT_PACK32 | E5B9G9R9_UFLOAT_PACK32 | BC6H_UFLOAT_BLOCK => {
+                ChannelType::Ufloat
+            }
+            R16_SFLOAT | R16G16_SFLOAT | R16G16B16_SFLOAT | R16G16B16A16_SFLOAT | R32_SFLOAT
+            | R32G32_SFLOAT | R32G32B32_SFLOAT | R32G32B32A32_SFLOAT | R64_SFLOAT
+            | R64G64_SFLOAT | R64G64B64_SFLOAT | R64G64B64A64_SFLOAT | D32_SFLOAT
+            | D32_SFLOAT_S8_UINT | BC6H_SFLOAT_BLOCK => ChannelType::Sfloat,
+            R8_SRGB
+            | R8G8_SRGB
+            | R8G8B8_SRGB
+            | B8G8R8_SRGB
+            | R8G8B8A8_SRGB
+            | B8G8R8A8_SRGB
+            | A8B8G8R8_SRGB_PACK32
+            | BC1_RGB_SRGB_BLOCK
+            | BC1_RGBA_SRGB_BLOCK
+            | BC2_SRGB_BLOCK
+            | BC3_SRGB_BLOCK
+            | BC7_SRGB_BLOCK
+            | ETC2_R8G8B8_SRGB_BLOCK
+            | ETC2_R8G8B8A1_SRGB_BLOCK
+            | ETC2_R8G8B8A8_SRGB_BLOCK
+            | ASTC_4x4_SRGB_BLOCK
+            | ASTC_5x4_SRGB_BLOCK
+            | ASTC_5x5_SRGB_BLOCK
+            | ASTC_6x5_SRGB_BLOCK
+            | ASTC_6x6_SRGB_BLOCK
+            | ASTC_8x5_SRGB_BLOCK
+            | ASTC_8x6_SRGB_BLOCK
+            | ASTC_8x8_SRGB_BLOCK
+            | ASTC_10x5_SRGB_BLOCK
+            | ASTC_10x6_SRGB_BLOCK
+            | ASTC_10x8_SRGB_BLOCK
+            | ASTC_10x10_SRGB_BLOCK
+            | ASTC_12x10_SRGB_BLOCK
+            | ASTC_12x12_SRGB_BLOCK
+            | PVRTC1_2BPP_SRGB_BLOCK_IMG
+            | PVRTC1_4BPP_SRGB_BLOCK_IMG
+            | PVRTC2_2BPP_SRGB_BLOCK_IMG
+            | PVRTC2_4BPP_SRGB_BLOCK_IMG => ChannelType::Srgb,
+            _ => ChannelType::Unorm,
+        })
+    }
+}
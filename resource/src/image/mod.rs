@@ -2,14 +2,26 @@
 
 pub mod format;
 mod usage;
+mod view;
 
-pub use self::format::Format;
+pub use self::format::{ChannelType, Format};
 pub use self::usage::*;
+pub use self::view::*;
 
-use memory::MemoryBlock;
+use std::{
+    ops::Range,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use memory::{Heaps, MemoryBlock, Tag};
 use relevant::Relevant;
 
+use device::Device;
 use escape::Escape;
+use frame::FrameIndex;
 use SharingMode;
 
 /// Image dimensionality
@@ -36,6 +48,30 @@ pub struct Extent3D {
     pub depth: u32,
 }
 
+/// Offset of the image region in texels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Offset3D {
+    /// The x offset
+    pub x: i32,
+    /// The y offset
+    pub y: i32,
+    /// The z offset
+    pub z: i32,
+}
+
+impl Extent3D {
+    /// Extent of the mip level.
+    /// Each level halves every dimension, rounding down, but not below `1`.
+    pub fn mip_level(&self, level: u32) -> Extent3D {
+        let shrink = |size: u32| size.checked_shr(level).unwrap_or(0).max(1);
+        Extent3D {
+            width: shrink(self.width),
+            height: shrink(self.height),
+            depth: shrink(self.depth),
+        }
+    }
+}
+
 bitflags! {
     /// Bitmask specifying sample counts supported for an image used for storage operations.
     /// See Vulkan docs for detailed info:
@@ -142,6 +178,58 @@ bitflags! {
     }
 }
 
+bitflags! {
+    /// Bitmask specifying which aspects of an image are included in a view or operation.
+    /// See Vulkan docs for details:
+    /// <https://www.khronos.org/registry/vulkan/specs/1.1-extensions/man/html/VkImageAspectFlagBits.html>
+    #[repr(transparent)]
+    pub struct ImageAspectFlags: u32 {
+        /// Specifies the color aspect.
+        const COLOR = 0x00000001;
+        /// Specifies the depth aspect.
+        const DEPTH = 0x00000002;
+        /// Specifies the stencil aspect.
+        const STENCIL = 0x00000004;
+    }
+}
+
+/// Range of mip levels and array layers of the image.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubresourceRange {
+    /// Aspects of the image included in the range.
+    pub aspects: ImageAspectFlags,
+
+    /// Range of mip levels.
+    pub levels: Range<u32>,
+
+    /// Range of array layers.
+    pub layers: Range<u32>,
+}
+
+/// Range of array layers of single mip level of the image.
+/// Used to specify regions for copy and blit operations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubresourceLayers {
+    /// Aspects of the image included in the range.
+    pub aspects: ImageAspectFlags,
+
+    /// Mip level.
+    pub level: u32,
+
+    /// Range of array layers.
+    pub layers: Range<u32>,
+}
+
+impl From<SubresourceLayers> for SubresourceRange {
+    fn from(layers: SubresourceLayers) -> Self {
+        SubresourceRange {
+            aspects: layers.aspects,
+            levels: layers.level..layers.level + 1,
+            layers: layers.layers,
+        }
+    }
+}
+
 /// Contains information required to create an image.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub struct CreateInfo {
@@ -176,6 +264,30 @@ pub struct CreateInfo {
     pub flags: ImageCreateFlags,
 }
 
+impl CreateInfo {
+    /// Extent of the mip level of the image.
+    pub fn mip_extent(&self, level: u32) -> Extent3D {
+        self.extent.mip_level(level)
+    }
+
+    /// Size in bytes of single array layer of the mip level when texels are tightly packed.
+    /// This is the size of the buffer region to copy the subresource to or from.
+    /// Returns `None` if the format is copied one aspect or plane at a time.
+    /// See `Format::packed_size`.
+    pub fn subresource_size(&self, level: u32) -> Option<u64> {
+        self.format.packed_size(self.mip_extent(level))
+    }
+
+    /// Range of all aspects, mip levels and array layers of the image.
+    pub fn whole_range(&self) -> SubresourceRange {
+        SubresourceRange {
+            aspects: self.format.aspects(),
+            levels: 0..self.mips,
+            layers: 0..self.array,
+        }
+    }
+}
+
 /// Generic image object wrapper.
 ///
 /// # Parameters
@@ -188,9 +300,55 @@ pub struct Image<M, I> {
     pub(super) info: CreateInfo,
 }
 
+impl<M, I> Image<M, I> {
+    /// Set debug name of the image.
+    /// The name is forwarded to the device, shown in `Debug` output
+    /// and tags the memory block so that `Heaps::leaks` reports it.
+    /// Block tagged with anything but previous name keeps its tag.
+    pub fn set_name<D>(&mut self, device: &D, heaps: &mut Heaps<M>, name: &str)
+    where
+        M: 'static,
+        D: Device<Memory = M, Image = I>,
+    {
+        device.set_image_name(&self.inner.raw, name);
+        let name: Arc<str> = name.into();
+        if self.inner.block.tag() == self.inner.name.clone().map(Tag::from) {
+            heaps.set_tag(&mut self.inner.block, Some(name.clone().into()));
+        }
+        self.inner.name = Some(name);
+    }
+
+    /// Get debug name of the image.
+    pub fn name(&self) -> Option<&str> {
+        self.inner.name.as_ref().map(|name| &**name)
+    }
+
+    /// Mark the image as used by commands submitted in the `frame`.
+    /// Dropped image is destroyed only after the last frame that used it is complete.
+    pub fn mark_used(&self, frame: FrameIndex) {
+        self.inner
+            .last_use
+            .fetch_max(frame.value(), Ordering::Relaxed);
+    }
+
+    /// Get index of the last frame that used the image.
+    pub fn last_use(&self) -> Option<FrameIndex> {
+        match self.inner.last_use.load(Ordering::Relaxed) {
+            0 => None,
+            index => Some(unsafe { FrameIndex::new(index) }),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub(super) struct Inner<M, I> {
     pub(super) block: MemoryBlock<M>,
     pub(super) raw: I,
+    /// Cloned by views of the image.
+    pub(super) views: Arc<()>,
+    /// Index of the last frame that used the image. `0` if never used.
+    pub(super) last_use: AtomicU64,
+    /// Debug name of the image.
+    pub(super) name: Option<Arc<str>>,
     pub(super) relevant: Relevant,
 }
@@ -0,0 +1,140 @@
+use std::sync::Arc;
+
+use relevant::Relevant;
+
+use escape::Escape;
+
+use super::{Format, SubresourceRange};
+
+/// Image view dimensionality.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ViewKind {
+    /// View of one-dimensional image.
+    D1,
+
+    /// View of array of one-dimensional images.
+    D1Array,
+
+    /// View of two-dimensional image.
+    D2,
+
+    /// View of array of two-dimensional images.
+    D2Array,
+
+    /// View of three-dimensional image.
+    D3,
+
+    /// View of six layers of two-dimensional image as cube map.
+    /// Image must be created with `IMAGE_CREATE_CUBE_COMPATIBLE` flag.
+    Cube,
+
+    /// View of array of cube maps.
+    CubeArray,
+}
+
+/// Source of the view component.
+/// See Vulkan docs for details:
+/// <https://www.khronos.org/registry/vulkan/specs/1.1-extensions/man/html/VkComponentSwizzle.html>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Component {
+    /// Component is taken from the same component of the image.
+    Identity,
+
+    /// Component is set to zero.
+    Zero,
+
+    /// Component is set to one.
+    One,
+
+    /// Component is taken from the red component of the image.
+    R,
+
+    /// Component is taken from the green component of the image.
+    G,
+
+    /// Component is taken from the blue component of the image.
+    B,
+
+    /// Component is taken from the alpha component of the image.
+    A,
+}
+
+/// Sources of red, green, blue and alpha components of the view.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Swizzle {
+    /// Source of the red component.
+    pub r: Component,
+
+    /// Source of the green component.
+    pub g: Component,
+
+    /// Source of the blue component.
+    pub b: Component,
+
+    /// Source of the alpha component.
+    pub a: Component,
+}
+
+impl Swizzle {
+    /// Swizzle that maps each component to itself.
+    pub const IDENTITY: Self = Swizzle {
+        r: Component::Identity,
+        g: Component::Identity,
+        b: Component::Identity,
+        a: Component::Identity,
+    };
+}
+
+impl Default for Swizzle {
+    fn default() -> Self {
+        Swizzle::IDENTITY
+    }
+}
+
+/// Contains information required to create an image view.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ViewCreateInfo {
+    /// View dimensionality.
+    pub kind: ViewKind,
+
+    /// Format of the view.
+    /// Must be the same as image format unless image is created with `IMAGE_CREATE_MUTABLE_FORMAT` flag.
+    pub format: Format,
+
+    /// Component mapping.
+    pub swizzle: Swizzle,
+
+    /// Mip levels and array layers accessible through the view.
+    pub range: SubresourceRange,
+}
+
+/// Generic image view object wrapper.
+/// Image is not destroyed while its views are alive.
+///
+/// # Parameters
+///
+/// `V` - raw image view type.
+#[derive(Debug)]
+pub struct ImageView<V> {
+    pub(crate) inner: Escape<ViewInner<V>>,
+    pub(crate) info: ViewCreateInfo,
+}
+
+impl<V> ImageView<V> {
+    /// Get raw image view.
+    pub fn raw(&self) -> &V {
+        &self.inner.raw
+    }
+
+    /// Get info the view was created with.
+    pub fn info(&self) -> &ViewCreateInfo {
+        &self.info
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ViewInner<V> {
+    pub(crate) raw: V,
+    pub(crate) image: Arc<()>,
+    pub(crate) relevant: Relevant,
+}
@@ -1,8 +1,14 @@
-use std::ptr;
+use std::{
+    ffi::CString,
+    fmt, mem,
+    ops::Range,
+    os::raw::{c_char, c_void},
+    ptr::{self, NonNull},
+};
 
 use ash::{
     self,
-    version::{DeviceV1_0, FunctionPointers},
+    version::{DeviceV1_0, FunctionPointers, InstanceV1_0},
     vk,
 };
 
@@ -10,7 +16,9 @@ use buffer;
 use device::Device;
 use error;
 use image;
-use memory;
+use memory::{self, Device as MemoryDevice};
+use sampler;
+use validation::{DeviceLimits, FormatFeatureFlags, FormatProperties};
 use MemoryRequirements;
 use SharingMode;
 
@@ -403,6 +411,381 @@ impl From<image::Format> for vk::Format {
     }
 }
 
+impl From<image::ImageAspectFlags> for vk::ImageAspectFlags {
+    fn from(aspects: image::ImageAspectFlags) -> Self {
+        vk::ImageAspectFlags::from_flags(aspects.bits()).unwrap()
+    }
+}
+
+impl From<image::SubresourceRange> for vk::ImageSubresourceRange {
+    fn from(range: image::SubresourceRange) -> Self {
+        vk::ImageSubresourceRange {
+            aspect_mask: range.aspects.into(),
+            base_mip_level: range.levels.start,
+            level_count: range.levels.end - range.levels.start,
+            base_array_layer: range.layers.start,
+            layer_count: range.layers.end - range.layers.start,
+        }
+    }
+}
+
+impl From<image::SubresourceLayers> for vk::ImageSubresourceLayers {
+    fn from(layers: image::SubresourceLayers) -> Self {
+        vk::ImageSubresourceLayers {
+            aspect_mask: layers.aspects.into(),
+            mip_level: layers.level,
+            base_array_layer: layers.layers.start,
+            layer_count: layers.layers.end - layers.layers.start,
+        }
+    }
+}
+
+/// Convert sharing mode.
+/// Returned family indices must outlive create-info that points to them.
+fn sharing_mode(sharing: SharingMode) -> (vk::SharingMode, Vec<u32>) {
+    match sharing {
+        SharingMode::Exclusive => (vk::SharingMode::Exclusive, Vec::new()),
+        SharingMode::Concurrent(families) => {
+            assert!(
+                families.len() > 1,
+                "Concurrent sharing mode requires at least two families"
+            );
+            (vk::SharingMode::Concurrent, families.iter().collect())
+        }
+    }
+}
+
+/// Convert out of memory error.
+fn out_of_memory(error: vk::Result) -> memory::OutOfMemoryError {
+    match error {
+        vk::Result::ErrorOutOfHostMemory => memory::OutOfMemoryError::OutOfHostMemory,
+        vk::Result::ErrorOutOfDeviceMemory => memory::OutOfMemoryError::OutOfDeviceMemory,
+        e => panic!("Unexpected error: {:?}", e),
+    }
+}
+
+fn component(component: image::Component) -> vk::ComponentSwizzle {
+    match component {
+        image::Component::Identity => vk::ComponentSwizzle::Identity,
+        image::Component::Zero => vk::ComponentSwizzle::Zero,
+        image::Component::One => vk::ComponentSwizzle::One,
+        image::Component::R => vk::ComponentSwizzle::R,
+        image::Component::G => vk::ComponentSwizzle::G,
+        image::Component::B => vk::ComponentSwizzle::B,
+        image::Component::A => vk::ComponentSwizzle::A,
+    }
+}
+
+fn filter(filter: sampler::Filter) -> vk::Filter {
+    match filter {
+        sampler::Filter::Nearest => vk::Filter::Nearest,
+        sampler::Filter::Linear => vk::Filter::Linear,
+    }
+}
+
+fn address_mode(mode: sampler::AddressMode) -> vk::SamplerAddressMode {
+    match mode {
+        sampler::AddressMode::Repeat => vk::SamplerAddressMode::Repeat,
+        sampler::AddressMode::MirroredRepeat => vk::SamplerAddressMode::MirroredRepeat,
+        sampler::AddressMode::ClampToEdge => vk::SamplerAddressMode::ClampToEdge,
+        sampler::AddressMode::ClampToBorder => vk::SamplerAddressMode::ClampToBorder,
+        sampler::AddressMode::MirrorClampToEdge => vk::SamplerAddressMode::MirrorClampToEdge,
+    }
+}
+
+/// `VK_STRUCTURE_TYPE_MEMORY_DEDICATED_REQUIREMENTS`.
+const STRUCTURE_TYPE_MEMORY_DEDICATED_REQUIREMENTS: i32 = 1000127000;
+
+/// `VK_STRUCTURE_TYPE_BUFFER_MEMORY_REQUIREMENTS_INFO_2`.
+const STRUCTURE_TYPE_BUFFER_MEMORY_REQUIREMENTS_INFO_2: i32 = 1000146000;
+
+/// `VK_STRUCTURE_TYPE_IMAGE_MEMORY_REQUIREMENTS_INFO_2`.
+const STRUCTURE_TYPE_IMAGE_MEMORY_REQUIREMENTS_INFO_2: i32 = 1000146001;
+
+/// `VK_STRUCTURE_TYPE_MEMORY_REQUIREMENTS_2`.
+const STRUCTURE_TYPE_MEMORY_REQUIREMENTS_2: i32 = 1000146003;
+
+/// `VK_STRUCTURE_TYPE_DEBUG_UTILS_OBJECT_NAME_INFO_EXT`.
+const STRUCTURE_TYPE_DEBUG_UTILS_OBJECT_NAME_INFO_EXT: i32 = 1000128000;
+
+/// `VK_OBJECT_TYPE_BUFFER`.
+const OBJECT_TYPE_BUFFER: i32 = 9;
+
+/// `VK_OBJECT_TYPE_IMAGE`.
+const OBJECT_TYPE_IMAGE: i32 = 10;
+
+/// `VkBufferMemoryRequirementsInfo2` from Vulkan 1.1 (`VK_KHR_get_memory_requirements2`).
+#[repr(C)]
+struct BufferMemoryRequirementsInfo2 {
+    s_type: i32,
+    p_next: *const c_void,
+    buffer: vk::Buffer,
+}
+
+/// `VkImageMemoryRequirementsInfo2` from Vulkan 1.1 (`VK_KHR_get_memory_requirements2`).
+#[repr(C)]
+struct ImageMemoryRequirementsInfo2 {
+    s_type: i32,
+    p_next: *const c_void,
+    image: vk::Image,
+}
+
+/// `VkMemoryRequirements2` from Vulkan 1.1 (`VK_KHR_get_memory_requirements2`).
+#[repr(C)]
+struct MemoryRequirements2 {
+    s_type: i32,
+    p_next: *mut c_void,
+    memory_requirements: vk::MemoryRequirements,
+}
+
+/// `VkMemoryDedicatedRequirements` from Vulkan 1.1 (`VK_KHR_dedicated_allocation`).
+#[repr(C)]
+struct MemoryDedicatedRequirements {
+    s_type: i32,
+    p_next: *mut c_void,
+    prefers_dedicated_allocation: vk::Bool32,
+    requires_dedicated_allocation: vk::Bool32,
+}
+
+/// `VkDebugUtilsObjectNameInfoEXT` from `VK_EXT_debug_utils`.
+#[repr(C)]
+struct DebugUtilsObjectNameInfo {
+    s_type: i32,
+    p_next: *const c_void,
+    object_type: i32,
+    object_handle: u64,
+    p_object_name: *const c_char,
+}
+
+/// `vkGetBufferMemoryRequirements2`.
+type GetBufferMemoryRequirements2 = unsafe extern "system" fn(
+    vk::Device,
+    *const BufferMemoryRequirementsInfo2,
+    *mut MemoryRequirements2,
+);
+
+/// `vkGetImageMemoryRequirements2`.
+type GetImageMemoryRequirements2 = unsafe extern "system" fn(
+    vk::Device,
+    *const ImageMemoryRequirementsInfo2,
+    *mut MemoryRequirements2,
+);
+
+/// `vkSetDebugUtilsObjectNameEXT`.
+type SetDebugUtilsObjectName =
+    unsafe extern "system" fn(vk::Device, *const DebugUtilsObjectNameInfo) -> i32;
+
+/// Load device-level function by the first of `names` the device provides.
+/// Names must be nul-terminated.
+unsafe fn device_proc_addr<V>(
+    instance: &ash::Instance<V>,
+    device: vk::Device,
+    names: &[&[u8]],
+) -> Option<unsafe extern "system" fn()>
+where
+    V: FunctionPointers,
+    ash::Instance<V>: InstanceV1_0,
+{
+    names
+        .iter()
+        .filter_map(|name| {
+            debug_assert_eq!(name.last(), Some(&0));
+            let proc_addr = instance
+                .fp_v1_0()
+                .get_device_proc_addr(device, name.as_ptr() as *const c_char);
+            mem::transmute::<_, Option<unsafe extern "system" fn()>>(proc_addr)
+        })
+        .next()
+}
+
+/// Convert memory requirements.
+/// Dedicated allocation flags are taken from `dedicated`.
+fn memory_requirements(
+    req: vk::MemoryRequirements,
+    dedicated: &MemoryDedicatedRequirements,
+) -> MemoryRequirements {
+    MemoryRequirements {
+        size: req.size,
+        align: req.alignment,
+        mask: req.memory_type_bits,
+        prefers_dedicated: dedicated.prefers_dedicated_allocation != 0,
+        requires_dedicated: dedicated.requires_dedicated_allocation != 0,
+    }
+}
+
+/// Dedicated requirements to be filled by the driver.
+fn dedicated_requirements() -> MemoryDedicatedRequirements {
+    MemoryDedicatedRequirements {
+        s_type: STRUCTURE_TYPE_MEMORY_DEDICATED_REQUIREMENTS,
+        p_next: ptr::null_mut(),
+        prefers_dedicated_allocation: 0,
+        requires_dedicated_allocation: 0,
+    }
+}
+
+/// Get raw value of the non-dispatchable handle.
+/// Non-dispatchable handles are 64-bit on all platforms.
+fn raw_handle<T: Copy>(handle: T) -> u64 {
+    assert_eq!(mem::size_of::<T>(), mem::size_of::<u64>());
+    unsafe { mem::transmute_copy(&handle) }
+}
+
+/// Vulkan device with functions `Device` needs beyond Vulkan 1.0.
+/// Implements `Device` for `ash` backend.
+///
+/// Dedicated allocation preferences are reported if device supports Vulkan 1.1
+/// or `VK_KHR_get_memory_requirements2` and `VK_KHR_dedicated_allocation` are enabled.
+/// Device limits and format properties are queried from the physical device.
+/// Buffers and images are named if `VK_EXT_debug_utils` is enabled on the instance.
+///
+/// `Device` implemented for `ash::Device` itself reports none of the above.
+pub struct AshDevice<V: FunctionPointers> {
+    device: ash::Device<V>,
+    instance: ash::Instance<V>,
+    physical: vk::PhysicalDevice,
+    get_buffer_memory_requirements2: Option<GetBufferMemoryRequirements2>,
+    get_image_memory_requirements2: Option<GetImageMemoryRequirements2>,
+    set_debug_utils_object_name: Option<SetDebugUtilsObjectName>,
+}
+
+impl<V> AshDevice<V>
+where
+    V: FunctionPointers,
+    ash::Device<V>: DeviceV1_0,
+{
+    /// Wrap the device and load optional functions.
+    ///
+    /// # Safety
+    ///
+    /// `device` must be created from `physical` device of the `instance`.
+    pub unsafe fn new(
+        instance: &ash::Instance<V>,
+        physical: vk::PhysicalDevice,
+        device: ash::Device<V>,
+    ) -> Self
+    where
+        ash::Instance<V>: InstanceV1_0,
+    {
+        let handle = device.handle();
+        let load = |names: &[&[u8]]| device_proc_addr(instance, handle, names);
+
+        AshDevice {
+            get_buffer_memory_requirements2: load(&[
+                b"vkGetBufferMemoryRequirements2\0",
+                b"vkGetBufferMemoryRequirements2KHR\0",
+            ])
+            .map(|f| mem::transmute(f)),
+            get_image_memory_requirements2: load(&[
+                b"vkGetImageMemoryRequirements2\0",
+                b"vkGetImageMemoryRequirements2KHR\0",
+            ])
+            .map(|f| mem::transmute(f)),
+            set_debug_utils_object_name: load(&[b"vkSetDebugUtilsObjectNameEXT\0"])
+                .map(|f| mem::transmute(f)),
+            device,
+            instance: instance.clone(),
+            physical,
+        }
+    }
+
+    /// Get wrapped device.
+    pub fn raw(&self) -> &ash::Device<V> {
+        &self.device
+    }
+
+    /// Name the object if `VK_EXT_debug_utils` is available.
+    /// Names with interior nul characters are ignored.
+    fn set_object_name(&self, object_type: i32, object_handle: u64, name: &str) {
+        let set_debug_utils_object_name = match self.set_debug_utils_object_name {
+            Some(set_debug_utils_object_name) => set_debug_utils_object_name,
+            None => return,
+        };
+        let name = match CString::new(name) {
+            Ok(name) => name,
+            Err(_) => return,
+        };
+        let info = DebugUtilsObjectNameInfo {
+            s_type: STRUCTURE_TYPE_DEBUG_UTILS_OBJECT_NAME_INFO_EXT,
+            p_next: ptr::null(),
+            object_type,
+            object_handle,
+            p_object_name: name.as_ptr(),
+        };
+        // Naming is best-effort. Failure only means the name won't show up in tools.
+        unsafe {
+            set_debug_utils_object_name(self.device.handle(), &info);
+        }
+    }
+}
+
+impl<V> fmt::Debug for AshDevice<V>
+where
+    V: FunctionPointers,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("AshDevice")
+            .field("device", &self.device.handle())
+            .field("instance", &self.instance.handle())
+            .field("physical", &self.physical)
+            .finish()
+    }
+}
+
+impl<V> MemoryDevice for AshDevice<V>
+where
+    V: FunctionPointers,
+    ash::Device<V>: DeviceV1_0,
+{
+    type Memory = vk::DeviceMemory;
+
+    unsafe fn allocate(
+        &self,
+        index: u32,
+        size: u64,
+    ) -> Result<vk::DeviceMemory, memory::AllocationError> {
+        self.device.allocate(index, size)
+    }
+
+    unsafe fn allocate_dedicated(
+        &self,
+        index: u32,
+        size: u64,
+        dedicated: memory::Dedicated,
+    ) -> Result<vk::DeviceMemory, memory::AllocationError> {
+        self.device.allocate_dedicated(index, size, dedicated)
+    }
+
+    unsafe fn free(&self, memory: vk::DeviceMemory) {
+        MemoryDevice::free(&self.device, memory)
+    }
+
+    unsafe fn map(
+        &self,
+        memory: &vk::DeviceMemory,
+        range: Range<u64>,
+    ) -> Result<NonNull<u8>, memory::MappingError> {
+        self.device.map(memory, range)
+    }
+
+    unsafe fn unmap(&self, memory: &vk::DeviceMemory) {
+        self.device.unmap(memory)
+    }
+
+    unsafe fn invalidate<'a>(
+        &self,
+        regions: impl IntoIterator<Item = (&'a vk::DeviceMemory, Range<u64>)>,
+    ) -> Result<(), memory::OutOfMemoryError> {
+        self.device.invalidate(regions)
+    }
+
+    unsafe fn flush<'a>(
+        &self,
+        regions: impl IntoIterator<Item = (&'a vk::DeviceMemory, Range<u64>)>,
+    ) -> Result<(), memory::OutOfMemoryError> {
+        self.device.flush(regions)
+    }
+}
+
 impl<V> Device for ash::Device<V>
 where
     V: FunctionPointers,
@@ -419,38 +802,26 @@ where
     fn create_buffer(
         &self,
         info: buffer::CreateInfo,
-    ) -> Result<Self::UnboundBuffer, memory::OutOfMemoryError> {
+    ) -> Result<Self::UnboundBuffer, error::BufferCreationError> {
+        let (sharing_mode, families) = sharing_mode(info.sharing);
         let info = vk::BufferCreateInfo {
             s_type: vk::StructureType::BufferCreateInfo,
             p_next: ptr::null(),
             flags: vk::BufferCreateFlags::empty(),
             size: info.size,
             usage: vk::BufferUsageFlags::from_flags(info.usage.bits()).unwrap(),
-            sharing_mode: match info.sharing {
-                SharingMode::Exclusive => vk::SharingMode::Exclusive,
-            },
-            queue_family_index_count: 0,
-            p_queue_family_indices: ptr::null(),
+            sharing_mode,
+            queue_family_index_count: families.len() as u32,
+            p_queue_family_indices: families.as_ptr(),
         };
 
-        unsafe { DeviceV1_0::create_buffer(self, &info, None) }.map_err(|e| {
-            use self::vk::types::Result;
-            match e {
-                Result::ErrorOutOfHostMemory => memory::OutOfMemoryError::OutOfHostMemory,
-                Result::ErrorOutOfDeviceMemory => memory::OutOfMemoryError::OutOfDeviceMemory,
-                e => panic!("Unexpected error: {:?}", e),
-            }
-        })
+        unsafe { DeviceV1_0::create_buffer(self, &info, None) }
+            .map_err(|e| error::BufferCreationError::OutOfMemoryError(out_of_memory(e)))
     }
 
     fn buffer_requirements(&self, buffer: &Self::UnboundBuffer) -> MemoryRequirements {
         let req = DeviceV1_0::get_buffer_memory_requirements(self, *buffer);
-
-        MemoryRequirements {
-            size: req.size,
-            align: req.alignment,
-            mask: req.memory_type_bits,
-        }
+        memory_requirements(req, &dedicated_requirements())
     }
 
     unsafe fn bind_buffer(
@@ -459,15 +830,10 @@ where
         memory: &Self::Memory,
         offset: u64,
     ) -> Result<Self::Buffer, error::BindError> {
-        DeviceV1_0::bind_buffer_memory(self, buffer, *memory, offset).map_err(|e| match e {
-            vk::Result::ErrorOutOfHostMemory => {
-                error::BindError::OutOfMemoryError(memory::OutOfMemoryError::OutOfHostMemory)
-            }
-            vk::Result::ErrorOutOfDeviceMemory => {
-                error::BindError::OutOfMemoryError(memory::OutOfMemoryError::OutOfDeviceMemory)
-            }
-            _ => unreachable!(),
-        })?;
+        if let Err(e) = DeviceV1_0::bind_buffer_memory(self, buffer, *memory, offset) {
+            DeviceV1_0::destroy_buffer(self, buffer, None);
+            return Err(error::BindError::OutOfMemoryError(out_of_memory(e)));
+        }
         Ok(buffer)
     }
 
@@ -475,10 +841,38 @@ where
         DeviceV1_0::destroy_buffer(self, buffer, None);
     }
 
+    unsafe fn destroy_unbound_buffer(&self, buffer: Self::UnboundBuffer) {
+        DeviceV1_0::destroy_buffer(self, buffer, None);
+    }
+
+    fn create_buffer_view(
+        &self,
+        buffer: &Self::Buffer,
+        info: buffer::ViewCreateInfo,
+    ) -> Result<Self::BufferView, error::ViewCreationError> {
+        let info = vk::BufferViewCreateInfo {
+            s_type: vk::StructureType::BufferViewCreateInfo,
+            p_next: ptr::null(),
+            flags: vk::BufferViewCreateFlags::empty(),
+            buffer: *buffer,
+            format: info.format.into(),
+            offset: info.range.start,
+            range: info.range.end - info.range.start,
+        };
+
+        unsafe { DeviceV1_0::create_buffer_view(self, &info, None) }
+            .map_err(|e| error::ViewCreationError::OutOfMemoryError(out_of_memory(e)))
+    }
+
+    unsafe fn destroy_buffer_view(&self, view: Self::BufferView) {
+        DeviceV1_0::destroy_buffer_view(self, view, None);
+    }
+
     fn create_image(
         &self,
         info: image::CreateInfo,
     ) -> Result<Self::UnboundImage, error::ImageCreationError> {
+        let (sharing_mode, families) = sharing_mode(info.sharing);
         let info = vk::ImageCreateInfo {
             s_type: vk::StructureType::ImageCreateInfo,
             p_next: ptr::null(),
@@ -502,11 +896,9 @@ where
                 image::ImageTiling::Linear => vk::ImageTiling::Linear,
             },
             usage: vk::ImageUsageFlags::from_flags(info.usage.bits()).unwrap(),
-            sharing_mode: match info.sharing {
-                SharingMode::Exclusive => vk::SharingMode::Exclusive,
-            },
-            queue_family_index_count: 0,
-            p_queue_family_indices: ptr::null(),
+            sharing_mode,
+            queue_family_index_count: families.len() as u32,
+            p_queue_family_indices: families.as_ptr(),
             initial_layout: vk::ImageLayout::Undefined,
         };
 
@@ -525,12 +917,7 @@ where
 
     fn image_requirements(&self, image: &Self::UnboundImage) -> MemoryRequirements {
         let req = DeviceV1_0::get_image_memory_requirements(self, *image);
-
-        MemoryRequirements {
-            size: req.size,
-            align: req.alignment,
-            mask: req.memory_type_bits,
-        }
+        memory_requirements(req, &dedicated_requirements())
     }
 
     unsafe fn bind_image(
@@ -539,15 +926,10 @@ where
         memory: &Self::Memory,
         offset: u64,
     ) -> Result<Self::Image, error::BindError> {
-        DeviceV1_0::bind_image_memory(self, image, *memory, offset).map_err(|e| match e {
-            vk::Result::ErrorOutOfHostMemory => {
-                error::BindError::OutOfMemoryError(memory::OutOfMemoryError::OutOfHostMemory)
-            }
-            vk::Result::ErrorOutOfDeviceMemory => {
-                error::BindError::OutOfMemoryError(memory::OutOfMemoryError::OutOfDeviceMemory)
-            }
-            _ => unreachable!(),
-        })?;
+        if let Err(e) = DeviceV1_0::bind_image_memory(self, image, *memory, offset) {
+            DeviceV1_0::destroy_image(self, image, None);
+            return Err(error::BindError::OutOfMemoryError(out_of_memory(e)));
+        }
 
         Ok(image)
     }
@@ -555,4 +937,291 @@ where
     unsafe fn destroy_image(&self, image: Self::Image) {
         DeviceV1_0::destroy_image(self, image, None);
     }
+
+    unsafe fn destroy_unbound_image(&self, image: Self::UnboundImage) {
+        DeviceV1_0::destroy_image(self, image, None);
+    }
+
+    fn create_image_view(
+        &self,
+        image: &Self::Image,
+        info: image::ViewCreateInfo,
+    ) -> Result<Self::ImageView, error::ViewCreationError> {
+        let info = vk::ImageViewCreateInfo {
+            s_type: vk::StructureType::ImageViewCreateInfo,
+            p_next: ptr::null(),
+            flags: vk::ImageViewCreateFlags::empty(),
+            image: *image,
+            view_type: match info.kind {
+                image::ViewKind::D1 => vk::ImageViewType::Type1d,
+                image::ViewKind::D1Array => vk::ImageViewType::Type1dArray,
+                image::ViewKind::D2 => vk::ImageViewType::Type2d,
+                image::ViewKind::D2Array => vk::ImageViewType::Type2dArray,
+                image::ViewKind::D3 => vk::ImageViewType::Type3d,
+                image::ViewKind::Cube => vk::ImageViewType::Cube,
+                image::ViewKind::CubeArray => vk::ImageViewType::CubeArray,
+            },
+            format: info.format.into(),
+            components: vk::ComponentMapping {
+                r: component(info.swizzle.r),
+                g: component(info.swizzle.g),
+                b: component(info.swizzle.b),
+                a: component(info.swizzle.a),
+            },
+            subresource_range: info.range.clone().into(),
+        };
+
+        unsafe { DeviceV1_0::create_image_view(self, &info, None) }
+            .map_err(|e| error::ViewCreationError::OutOfMemoryError(out_of_memory(e)))
+    }
+
+    unsafe fn destroy_image_view(&self, view: Self::ImageView) {
+        DeviceV1_0::destroy_image_view(self, view, None);
+    }
+
+    fn create_sampler(
+        &self,
+        info: sampler::CreateInfo,
+    ) -> Result<Self::Sampler, error::SamplerCreationError> {
+        let info = vk::SamplerCreateInfo {
+            s_type: vk::StructureType::SamplerCreateInfo,
+            p_next: ptr::null(),
+            flags: vk::SamplerCreateFlags::empty(),
+            mag_filter: filter(info.mag_filter),
+            min_filter: filter(info.min_filter),
+            mipmap_mode: match info.mipmap_mode {
+                sampler::MipmapMode::Nearest => vk::SamplerMipmapMode::Nearest,
+                sampler::MipmapMode::Linear => vk::SamplerMipmapMode::Linear,
+            },
+            address_mode_u: address_mode(info.address[0]),
+            address_mode_v: address_mode(info.address[1]),
+            address_mode_w: address_mode(info.address[2]),
+            mip_lod_bias: info.lod_bias,
+            anisotropy_enable: info.anisotropy.is_some() as vk::Bool32,
+            max_anisotropy: info.anisotropy.unwrap_or(1.0),
+            compare_enable: info.compare.is_some() as vk::Bool32,
+            compare_op: match info.compare {
+                None | Some(sampler::CompareOp::Never) => vk::CompareOp::Never,
+                Some(sampler::CompareOp::Less) => vk::CompareOp::Less,
+                Some(sampler::CompareOp::Equal) => vk::CompareOp::Equal,
+                Some(sampler::CompareOp::LessOrEqual) => vk::CompareOp::LessOrEqual,
+                Some(sampler::CompareOp::Greater) => vk::CompareOp::Greater,
+                Some(sampler::CompareOp::NotEqual) => vk::CompareOp::NotEqual,
+                Some(sampler::CompareOp::GreaterOrEqual) => vk::CompareOp::GreaterOrEqual,
+                Some(sampler::CompareOp::Always) => vk::CompareOp::Always,
+            },
+            min_lod: info.lod_range.0,
+            max_lod: info.lod_range.1,
+            border_color: match info.border {
+                sampler::BorderColor::FloatTransparentBlack => {
+                    vk::BorderColor::FloatTransparentBlack
+                }
+                sampler::BorderColor::IntTransparentBlack => vk::BorderColor::IntTransparentBlack,
+                sampler::BorderColor::FloatOpaqueBlack => vk::BorderColor::FloatOpaqueBlack,
+                sampler::BorderColor::IntOpaqueBlack => vk::BorderColor::IntOpaqueBlack,
+                sampler::BorderColor::FloatOpaqueWhite => vk::BorderColor::FloatOpaqueWhite,
+                sampler::BorderColor::IntOpaqueWhite => vk::BorderColor::IntOpaqueWhite,
+            },
+            unnormalized_coordinates: info.unnormalized as vk::Bool32,
+        };
+
+        unsafe { DeviceV1_0::create_sampler(self, &info, None) }
+            .map_err(|e| error::SamplerCreationError::OutOfMemoryError(out_of_memory(e)))
+    }
+
+    unsafe fn destroy_sampler(&self, sampler: Self::Sampler) {
+        DeviceV1_0::destroy_sampler(self, sampler, None);
+    }
+}
+
+impl<V> Device for AshDevice<V>
+where
+    V: FunctionPointers,
+    ash::Device<V>: DeviceV1_0,
+    ash::Instance<V>: InstanceV1_0,
+{
+    type Sampler = vk::Sampler;
+    type Buffer = vk::Buffer;
+    type UnboundBuffer = vk::Buffer;
+    type BufferView = vk::BufferView;
+    type Image = vk::Image;
+    type UnboundImage = vk::Image;
+    type ImageView = vk::ImageView;
+
+    fn create_buffer(
+        &self,
+        info: buffer::CreateInfo,
+    ) -> Result<Self::UnboundBuffer, error::BufferCreationError> {
+        Device::create_buffer(&self.device, info)
+    }
+
+    fn buffer_requirements(&self, buffer: &Self::UnboundBuffer) -> MemoryRequirements {
+        let mut dedicated = dedicated_requirements();
+        let req = match self.get_buffer_memory_requirements2 {
+            Some(get_buffer_memory_requirements2) => unsafe {
+                let info = BufferMemoryRequirementsInfo2 {
+                    s_type: STRUCTURE_TYPE_BUFFER_MEMORY_REQUIREMENTS_INFO_2,
+                    p_next: ptr::null(),
+                    buffer: *buffer,
+                };
+                let mut req = MemoryRequirements2 {
+                    s_type: STRUCTURE_TYPE_MEMORY_REQUIREMENTS_2,
+                    p_next: &mut dedicated as *mut _ as *mut c_void,
+                    memory_requirements: mem::zeroed(),
+                };
+                get_buffer_memory_requirements2(self.device.handle(), &info, &mut req);
+                req.memory_requirements
+            },
+            None => DeviceV1_0::get_buffer_memory_requirements(&self.device, *buffer),
+        };
+
+        memory_requirements(req, &dedicated)
+    }
+
+    fn buffer_handle(&self, buffer: &Self::UnboundBuffer) -> Option<u64> {
+        Some(raw_handle(*buffer))
+    }
+
+    unsafe fn bind_buffer(
+        &self,
+        buffer: Self::UnboundBuffer,
+        memory: &Self::Memory,
+        offset: u64,
+    ) -> Result<Self::Buffer, error::BindError> {
+        Device::bind_buffer(&self.device, buffer, memory, offset)
+    }
+
+    unsafe fn destroy_buffer(&self, buffer: Self::Buffer) {
+        Device::destroy_buffer(&self.device, buffer)
+    }
+
+    unsafe fn destroy_unbound_buffer(&self, buffer: Self::UnboundBuffer) {
+        Device::destroy_unbound_buffer(&self.device, buffer)
+    }
+
+    fn create_buffer_view(
+        &self,
+        buffer: &Self::Buffer,
+        info: buffer::ViewCreateInfo,
+    ) -> Result<Self::BufferView, error::ViewCreationError> {
+        Device::create_buffer_view(&self.device, buffer, info)
+    }
+
+    unsafe fn destroy_buffer_view(&self, view: Self::BufferView) {
+        Device::destroy_buffer_view(&self.device, view)
+    }
+
+    fn create_image(
+        &self,
+        info: image::CreateInfo,
+    ) -> Result<Self::UnboundImage, error::ImageCreationError> {
+        Device::create_image(&self.device, info)
+    }
+
+    fn image_requirements(&self, image: &Self::UnboundImage) -> MemoryRequirements {
+        let mut dedicated = dedicated_requirements();
+        let req = match self.get_image_memory_requirements2 {
+            Some(get_image_memory_requirements2) => unsafe {
+                let info = ImageMemoryRequirementsInfo2 {
+                    s_type: STRUCTURE_TYPE_IMAGE_MEMORY_REQUIREMENTS_INFO_2,
+                    p_next: ptr::null(),
+                    image: *image,
+                };
+                let mut req = MemoryRequirements2 {
+                    s_type: STRUCTURE_TYPE_MEMORY_REQUIREMENTS_2,
+                    p_next: &mut dedicated as *mut _ as *mut c_void,
+                    memory_requirements: mem::zeroed(),
+                };
+                get_image_memory_requirements2(self.device.handle(), &info, &mut req);
+                req.memory_requirements
+            },
+            None => DeviceV1_0::get_image_memory_requirements(&self.device, *image),
+        };
+
+        memory_requirements(req, &dedicated)
+    }
+
+    fn image_handle(&self, image: &Self::UnboundImage) -> Option<u64> {
+        Some(raw_handle(*image))
+    }
+
+    unsafe fn bind_image(
+        &self,
+        image: Self::UnboundImage,
+        memory: &Self::Memory,
+        offset: u64,
+    ) -> Result<Self::Image, error::BindError> {
+        Device::bind_image(&self.device, image, memory, offset)
+    }
+
+    unsafe fn destroy_image(&self, image: Self::Image) {
+        Device::destroy_image(&self.device, image)
+    }
+
+    unsafe fn destroy_unbound_image(&self, image: Self::UnboundImage) {
+        Device::destroy_unbound_image(&self.device, image)
+    }
+
+    fn create_image_view(
+        &self,
+        image: &Self::Image,
+        info: image::ViewCreateInfo,
+    ) -> Result<Self::ImageView, error::ViewCreationError> {
+        Device::create_image_view(&self.device, image, info)
+    }
+
+    unsafe fn destroy_image_view(&self, view: Self::ImageView) {
+        Device::destroy_image_view(&self.device, view)
+    }
+
+    fn create_sampler(
+        &self,
+        info: sampler::CreateInfo,
+    ) -> Result<Self::Sampler, error::SamplerCreationError> {
+        Device::create_sampler(&self.device, info)
+    }
+
+    unsafe fn destroy_sampler(&self, sampler: Self::Sampler) {
+        Device::destroy_sampler(&self.device, sampler)
+    }
+
+    fn set_buffer_name(&self, buffer: &Self::Buffer, name: &str) {
+        self.set_object_name(OBJECT_TYPE_BUFFER, raw_handle(*buffer), name);
+    }
+
+    fn set_image_name(&self, image: &Self::Image, name: &str) {
+        self.set_object_name(OBJECT_TYPE_IMAGE, raw_handle(*image), name);
+    }
+
+    fn limits(&self) -> Option<DeviceLimits> {
+        let limits = self
+            .instance
+            .get_physical_device_properties(self.physical)
+            .limits;
+        let sample_counts = limits.framebuffer_color_sample_counts.flags()
+            & limits.framebuffer_depth_sample_counts.flags();
+        Some(DeviceLimits {
+            max_image_dimension_1d: limits.max_image_dimension1d,
+            max_image_dimension_2d: limits.max_image_dimension2d,
+            max_image_dimension_3d: limits.max_image_dimension3d,
+            max_image_dimension_cube: limits.max_image_dimension_cube,
+            max_image_array_layers: limits.max_image_array_layers,
+            max_texel_buffer_elements: limits.max_texel_buffer_elements,
+            sample_counts: image::SampleCountFlags::from_bits_truncate(sample_counts),
+            buffer_image_granularity: limits.buffer_image_granularity,
+        })
+    }
+
+    fn format_properties(&self, format: image::Format) -> Option<FormatProperties> {
+        let properties = self
+            .instance
+            .get_physical_device_format_properties(self.physical, format.into());
+        let features =
+            |flags: vk::FormatFeatureFlags| FormatFeatureFlags::from_bits_truncate(flags.flags());
+        Some(FormatProperties {
+            linear_tiling_features: features(properties.linear_tiling_features),
+            optimal_tiling_features: features(properties.optimal_tiling_features),
+            buffer_features: features(properties.buffer_features),
+        })
+    }
 }
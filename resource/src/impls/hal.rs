@@ -1,15 +1,21 @@
 use std::borrow::Borrow;
+use std::fmt;
 use std::marker::PhantomData;
+use std::ops::Range;
+use std::ptr::NonNull;
 
 use hal;
-use hal::Device as HalDevice;
+use hal::{Device as RawDevice, PhysicalDevice as RawPhysicalDevice};
 
 use buffer;
 use device::Device;
 use error;
 use image;
-use memory;
+use memory::{self, Device as MemoryDevice};
+use sampler;
+use validation::{DeviceLimits, FormatFeatureFlags, FormatProperties};
 use MemoryRequirements;
+use SharingMode;
 
 impl From<hal::device::BindError> for error::BindError {
     fn from(e: hal::device::BindError) -> Self {
@@ -20,6 +26,24 @@ impl From<hal::device::BindError> for error::BindError {
     }
 }
 
+impl From<hal::buffer::CreationError> for error::BufferCreationError {
+    fn from(e: hal::buffer::CreationError) -> Self {
+        use error::BufferCreationError;
+        use hal::buffer::CreationError as HalCreationError;
+        match e {
+            HalCreationError::OutOfHostMemory => {
+                BufferCreationError::OutOfMemoryError(memory::OutOfMemoryError::OutOfHostMemory)
+            }
+            HalCreationError::OutOfDeviceMemory => {
+                BufferCreationError::OutOfMemoryError(memory::OutOfMemoryError::OutOfDeviceMemory)
+            }
+            HalCreationError::UnsupportedUsage { usage } => BufferCreationError::UnsupportedUsage(
+                buffer::UsageFlags::from_bits_truncate(usage.bits()),
+            ),
+        }
+    }
+}
+
 impl From<hal::image::CreationError> for error::ImageCreationError {
     fn from(e: hal::image::CreationError) -> Self {
         use error::ImageCreationError;
@@ -39,6 +63,33 @@ impl From<hal::image::CreationError> for error::ImageCreationError {
     }
 }
 
+impl From<hal::image::ViewError> for error::ViewCreationError {
+    fn from(e: hal::image::ViewError) -> Self {
+        use error::ViewCreationError;
+        use hal::image::ViewError;
+        match e {
+            ViewError::Usage(_) => ViewCreationError::BadUsage,
+            ViewError::Level(_) | ViewError::Layer(_) => ViewCreationError::OutOfBounds,
+            ViewError::BadFormat | ViewError::Unsupported => ViewCreationError::Unsupported,
+        }
+    }
+}
+
+impl From<hal::buffer::ViewCreationError> for error::ViewCreationError {
+    fn from(e: hal::buffer::ViewCreationError) -> Self {
+        use error::ViewCreationError;
+        use hal::buffer::ViewCreationError as HalViewCreationError;
+        match e {
+            HalViewCreationError::UnsupportedFormat {
+                format: Some(format),
+            } => ViewCreationError::UnsupportedFormat(format.into()),
+            HalViewCreationError::UnsupportedFormat { format: None } => {
+                ViewCreationError::Unsupported
+            }
+        }
+    }
+}
+
 impl From<hal::format::Format> for image::Format {
     fn from(f: hal::format::Format) -> Self {
         use hal::format::Format::*;
@@ -428,6 +479,168 @@ impl From<image::Format> for hal::format::Format {
     }
 }
 
+impl From<image::ImageAspectFlags> for hal::format::Aspects {
+    fn from(aspects: image::ImageAspectFlags) -> Self {
+        hal::format::Aspects::from_bits(aspects.bits() as _).unwrap()
+    }
+}
+
+impl From<image::SubresourceRange> for hal::image::SubresourceRange {
+    fn from(range: image::SubresourceRange) -> Self {
+        hal::image::SubresourceRange {
+            aspects: range.aspects.into(),
+            levels: range.levels.start as _..range.levels.end as _,
+            layers: range.layers.start as _..range.layers.end as _,
+        }
+    }
+}
+
+impl From<image::SubresourceLayers> for hal::image::SubresourceLayers {
+    fn from(layers: image::SubresourceLayers) -> Self {
+        hal::image::SubresourceLayers {
+            aspects: layers.aspects.into(),
+            level: layers.level as _,
+            layers: layers.layers.start as _..layers.layers.end as _,
+        }
+    }
+}
+
+/// `gfx-hal` creates all resources with exclusive sharing mode.
+fn exclusive(sharing: SharingMode) -> bool {
+    match sharing {
+        SharingMode::Exclusive => true,
+        SharingMode::Concurrent(_) => false,
+    }
+}
+
+fn component(
+    component: image::Component,
+    identity: hal::format::Component,
+) -> hal::format::Component {
+    match component {
+        image::Component::Identity => identity,
+        image::Component::Zero => hal::format::Component::Zero,
+        image::Component::One => hal::format::Component::One,
+        image::Component::R => hal::format::Component::R,
+        image::Component::G => hal::format::Component::G,
+        image::Component::B => hal::format::Component::B,
+        image::Component::A => hal::format::Component::A,
+    }
+}
+
+fn filter(filter: sampler::Filter) -> hal::image::Filter {
+    match filter {
+        sampler::Filter::Nearest => hal::image::Filter::Nearest,
+        sampler::Filter::Linear => hal::image::Filter::Linear,
+    }
+}
+
+/// Convert address mode.
+/// `gfx-hal` doesn't support mirror-clamp-to-edge addressing.
+fn wrap_mode(
+    mode: sampler::AddressMode,
+) -> Result<hal::image::WrapMode, error::SamplerCreationError> {
+    match mode {
+        sampler::AddressMode::Repeat => Ok(hal::image::WrapMode::Tile),
+        sampler::AddressMode::MirroredRepeat => Ok(hal::image::WrapMode::Mirror),
+        sampler::AddressMode::ClampToEdge => Ok(hal::image::WrapMode::Clamp),
+        sampler::AddressMode::ClampToBorder => Ok(hal::image::WrapMode::Border),
+        sampler::AddressMode::MirrorClampToEdge => Err(error::SamplerCreationError::Unsupported),
+    }
+}
+
+/// `gfx-hal` device with the physical device it was created from.
+/// Implements `Device` for `gfx-hal` backends.
+/// Device limits and format properties are queried from the physical device.
+///
+/// `Device` implemented for `(D, PhantomData<B>)` reports neither.
+pub struct HalDevice<B: hal::Backend, D, P> {
+    device: (D, PhantomData<B>),
+    physical: P,
+}
+
+impl<B, D, P> HalDevice<B, D, P>
+where
+    B: hal::Backend,
+    D: Borrow<B::Device>,
+    P: Borrow<B::PhysicalDevice>,
+{
+    /// Wrap the device.
+    /// `device` must be created from `physical` device.
+    pub fn new(device: D, physical: P) -> Self {
+        HalDevice {
+            device: (device, PhantomData),
+            physical,
+        }
+    }
+
+    /// Get wrapped device.
+    pub fn raw(&self) -> &B::Device {
+        self.device.0.borrow()
+    }
+}
+
+impl<B, D, P> fmt::Debug for HalDevice<B, D, P>
+where
+    B: hal::Backend,
+    D: fmt::Debug,
+    P: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("HalDevice")
+            .field("device", &self.device.0)
+            .field("physical", &self.physical)
+            .finish()
+    }
+}
+
+impl<B, D, P> MemoryDevice for HalDevice<B, D, P>
+where
+    B: hal::Backend,
+    D: Borrow<B::Device>,
+    P: Borrow<B::PhysicalDevice>,
+{
+    type Memory = B::Memory;
+
+    unsafe fn allocate(&self, index: u32, size: u64) -> Result<B::Memory, memory::AllocationError> {
+        MemoryDevice::allocate(&self.device, index, size)
+    }
+
+    unsafe fn free(&self, memory: B::Memory) {
+        MemoryDevice::free(&self.device, memory)
+    }
+
+    unsafe fn map(
+        &self,
+        memory: &B::Memory,
+        range: Range<u64>,
+    ) -> Result<NonNull<u8>, memory::MappingError> {
+        MemoryDevice::map(&self.device, memory, range)
+    }
+
+    unsafe fn unmap(&self, memory: &B::Memory) {
+        MemoryDevice::unmap(&self.device, memory)
+    }
+
+    unsafe fn invalidate<'a>(
+        &self,
+        regions: impl IntoIterator<Item = (&'a B::Memory, Range<u64>)>,
+    ) -> Result<(), memory::OutOfMemoryError> {
+        MemoryDevice::invalidate(&self.device, regions)
+    }
+
+    unsafe fn flush<'a>(
+        &self,
+        regions: impl IntoIterator<Item = (&'a B::Memory, Range<u64>)>,
+    ) -> Result<(), memory::OutOfMemoryError> {
+        MemoryDevice::flush(&self.device, regions)
+    }
+}
+
+/// `gfx-hal` doesn't report dedicated allocation preferences.
+/// Memory requirements never prefer nor require dedicated allocation
+/// and resource handles aren't provided.
+/// `gfx-hal` doesn't support naming objects, so debug names are kept in rendy wrappers only.
 impl<D, B> Device for (D, PhantomData<B>)
 where
     B: hal::Backend,
@@ -444,21 +657,12 @@ where
     fn create_buffer(
         &self,
         info: buffer::CreateInfo,
-    ) -> Result<Self::UnboundBuffer, memory::OutOfMemoryError> {
+    ) -> Result<Self::UnboundBuffer, error::BufferCreationError> {
+        if !exclusive(info.sharing) {
+            return Err(error::BufferCreationError::UnsupportedSharing);
+        }
         let usage = hal::buffer::Usage::from_bits(info.usage.bits()).unwrap();
-        self.0
-            .borrow()
-            .create_buffer(info.size, usage)
-            .map_err(|e| {
-                use hal::buffer::CreationError;
-                match e {
-                    CreationError::OutOfHostMemory => memory::OutOfMemoryError::OutOfHostMemory,
-                    CreationError::OutOfDeviceMemory => memory::OutOfMemoryError::OutOfDeviceMemory,
-                    CreationError::UnsupportedUsage { .. } => {
-                        panic!("Backend doesn't support this usage")
-                    }
-                }
-            })
+        Ok(self.0.borrow().create_buffer(info.size, usage)?)
     }
 
     fn buffer_requirements(&self, buffer: &Self::UnboundBuffer) -> MemoryRequirements {
@@ -467,6 +671,8 @@ where
             size: req.size,
             align: req.alignment,
             mask: req.type_mask as u32,
+            prefers_dedicated: false,
+            requires_dedicated: false,
         }
     }
 
@@ -483,10 +689,35 @@ where
         self.0.borrow().destroy_buffer(buffer);
     }
 
+    /// `gfx-hal` can't destroy buffers that are not bound to memory.
+    /// Buffer object is leaked.
+    unsafe fn destroy_unbound_buffer(&self, buffer: Self::UnboundBuffer) {
+        drop(buffer);
+    }
+
+    fn create_buffer_view(
+        &self,
+        buffer: &Self::Buffer,
+        info: buffer::ViewCreateInfo,
+    ) -> Result<Self::BufferView, error::ViewCreationError> {
+        Ok(self.0.borrow().create_buffer_view(
+            buffer,
+            Some(info.format.into()),
+            info.range.clone(),
+        )?)
+    }
+
+    unsafe fn destroy_buffer_view(&self, view: Self::BufferView) {
+        self.0.borrow().destroy_buffer_view(view);
+    }
+
     fn create_image(
         &self,
         info: image::CreateInfo,
     ) -> Result<Self::UnboundImage, error::ImageCreationError> {
+        if !exclusive(info.sharing) {
+            return Err(error::ImageCreationError::UnsupportedSharing);
+        }
         let kind = match info.kind {
             image::Kind::D1 => hal::image::Kind::D1(info.extent.width, info.array as u16),
             image::Kind::D2 => hal::image::Kind::D2(
@@ -519,6 +750,8 @@ where
             size: req.size,
             align: req.alignment,
             mask: req.type_mask as u32,
+            prefers_dedicated: false,
+            requires_dedicated: false,
         }
     }
 
@@ -534,4 +767,244 @@ where
     unsafe fn destroy_image(&self, image: Self::Image) {
         self.0.borrow().destroy_image(image);
     }
+
+    /// `gfx-hal` can't destroy images that are not bound to memory.
+    /// Image object is leaked.
+    unsafe fn destroy_unbound_image(&self, image: Self::UnboundImage) {
+        drop(image);
+    }
+
+    fn create_image_view(
+        &self,
+        image: &Self::Image,
+        info: image::ViewCreateInfo,
+    ) -> Result<Self::ImageView, error::ViewCreationError> {
+        let kind = match info.kind {
+            image::ViewKind::D1 => hal::image::ViewKind::D1,
+            image::ViewKind::D1Array => hal::image::ViewKind::D1Array,
+            image::ViewKind::D2 => hal::image::ViewKind::D2,
+            image::ViewKind::D2Array => hal::image::ViewKind::D2Array,
+            image::ViewKind::D3 => hal::image::ViewKind::D3,
+            image::ViewKind::Cube => hal::image::ViewKind::Cube,
+            image::ViewKind::CubeArray => hal::image::ViewKind::CubeArray,
+        };
+        let swizzle = hal::format::Swizzle(
+            component(info.swizzle.r, hal::format::Component::R),
+            component(info.swizzle.g, hal::format::Component::G),
+            component(info.swizzle.b, hal::format::Component::B),
+            component(info.swizzle.a, hal::format::Component::A),
+        );
+
+        Ok(self.0.borrow().create_image_view(
+            image,
+            kind,
+            info.format.into(),
+            swizzle,
+            info.range.into(),
+        )?)
+    }
+
+    unsafe fn destroy_image_view(&self, view: Self::ImageView) {
+        self.0.borrow().destroy_image_view(view);
+    }
+
+    fn create_sampler(
+        &self,
+        info: sampler::CreateInfo,
+    ) -> Result<Self::Sampler, error::SamplerCreationError> {
+        // `gfx-hal` doesn't support unnormalized coordinates.
+        if info.unnormalized {
+            return Err(error::SamplerCreationError::Unsupported);
+        }
+        let border = match info.border {
+            sampler::BorderColor::FloatTransparentBlack
+            | sampler::BorderColor::IntTransparentBlack => [0.0, 0.0, 0.0, 0.0],
+            sampler::BorderColor::FloatOpaqueBlack | sampler::BorderColor::IntOpaqueBlack => {
+                [0.0, 0.0, 0.0, 1.0]
+            }
+            sampler::BorderColor::FloatOpaqueWhite | sampler::BorderColor::IntOpaqueWhite => {
+                [1.0, 1.0, 1.0, 1.0]
+            }
+        };
+        let info = hal::image::SamplerInfo {
+            min_filter: filter(info.min_filter),
+            mag_filter: filter(info.mag_filter),
+            mip_filter: match info.mipmap_mode {
+                sampler::MipmapMode::Nearest => hal::image::Filter::Nearest,
+                sampler::MipmapMode::Linear => hal::image::Filter::Linear,
+            },
+            wrap_mode: (
+                wrap_mode(info.address[0])?,
+                wrap_mode(info.address[1])?,
+                wrap_mode(info.address[2])?,
+            ),
+            lod_bias: info.lod_bias.into(),
+            lod_range: info.lod_range.0.into()..info.lod_range.1.into(),
+            comparison: info.compare.map(|op| match op {
+                sampler::CompareOp::Never => hal::pso::Comparison::Never,
+                sampler::CompareOp::Less => hal::pso::Comparison::Less,
+                sampler::CompareOp::Equal => hal::pso::Comparison::Equal,
+                sampler::CompareOp::LessOrEqual => hal::pso::Comparison::LessEqual,
+                sampler::CompareOp::Greater => hal::pso::Comparison::Greater,
+                sampler::CompareOp::NotEqual => hal::pso::Comparison::NotEqual,
+                sampler::CompareOp::GreaterOrEqual => hal::pso::Comparison::GreaterEqual,
+                sampler::CompareOp::Always => hal::pso::Comparison::Always,
+            }),
+            border: border.into(),
+            anisotropic: match info.anisotropy {
+                None => hal::image::Anisotropic::Off,
+                Some(max) => hal::image::Anisotropic::On(max as u8),
+            },
+        };
+
+        Ok(self.0.borrow().create_sampler(info))
+    }
+
+    unsafe fn destroy_sampler(&self, sampler: Self::Sampler) {
+        self.0.borrow().destroy_sampler(sampler);
+    }
+}
+
+/// Memory requirements, resource handles and names are the same as for `(D, PhantomData<B>)`.
+impl<B, D, P> Device for HalDevice<B, D, P>
+where
+    B: hal::Backend,
+    D: Borrow<B::Device>,
+    P: Borrow<B::PhysicalDevice>,
+{
+    type Sampler = B::Sampler;
+    type Buffer = B::Buffer;
+    type UnboundBuffer = B::UnboundBuffer;
+    type BufferView = B::BufferView;
+    type Image = B::Image;
+    type UnboundImage = B::UnboundImage;
+    type ImageView = B::ImageView;
+
+    fn create_buffer(
+        &self,
+        info: buffer::CreateInfo,
+    ) -> Result<Self::UnboundBuffer, error::BufferCreationError> {
+        Device::create_buffer(&self.device, info)
+    }
+
+    fn buffer_requirements(&self, buffer: &Self::UnboundBuffer) -> MemoryRequirements {
+        Device::buffer_requirements(&self.device, buffer)
+    }
+
+    unsafe fn bind_buffer(
+        &self,
+        buffer: Self::UnboundBuffer,
+        memory: &Self::Memory,
+        offset: u64,
+    ) -> Result<Self::Buffer, error::BindError> {
+        Device::bind_buffer(&self.device, buffer, memory, offset)
+    }
+
+    unsafe fn destroy_buffer(&self, buffer: Self::Buffer) {
+        Device::destroy_buffer(&self.device, buffer)
+    }
+
+    unsafe fn destroy_unbound_buffer(&self, buffer: Self::UnboundBuffer) {
+        Device::destroy_unbound_buffer(&self.device, buffer)
+    }
+
+    fn create_buffer_view(
+        &self,
+        buffer: &Self::Buffer,
+        info: buffer::ViewCreateInfo,
+    ) -> Result<Self::BufferView, error::ViewCreationError> {
+        Device::create_buffer_view(&self.device, buffer, info)
+    }
+
+    unsafe fn destroy_buffer_view(&self, view: Self::BufferView) {
+        Device::destroy_buffer_view(&self.device, view)
+    }
+
+    fn create_image(
+        &self,
+        info: image::CreateInfo,
+    ) -> Result<Self::UnboundImage, error::ImageCreationError> {
+        Device::create_image(&self.device, info)
+    }
+
+    fn image_requirements(&self, image: &Self::UnboundImage) -> MemoryRequirements {
+        Device::image_requirements(&self.device, image)
+    }
+
+    unsafe fn bind_image(
+        &self,
+        image: Self::UnboundImage,
+        memory: &Self::Memory,
+        offset: u64,
+    ) -> Result<Self::Image, error::BindError> {
+        Device::bind_image(&self.device, image, memory, offset)
+    }
+
+    unsafe fn destroy_image(&self, image: Self::Image) {
+        Device::destroy_image(&self.device, image)
+    }
+
+    unsafe fn destroy_unbound_image(&self, image: Self::UnboundImage) {
+        Device::destroy_unbound_image(&self.device, image)
+    }
+
+    fn create_image_view(
+        &self,
+        image: &Self::Image,
+        info: image::ViewCreateInfo,
+    ) -> Result<Self::ImageView, error::ViewCreationError> {
+        Device::create_image_view(&self.device, image, info)
+    }
+
+    unsafe fn destroy_image_view(&self, view: Self::ImageView) {
+        Device::destroy_image_view(&self.device, view)
+    }
+
+    fn create_sampler(
+        &self,
+        info: sampler::CreateInfo,
+    ) -> Result<Self::Sampler, error::SamplerCreationError> {
+        Device::create_sampler(&self.device, info)
+    }
+
+    unsafe fn destroy_sampler(&self, sampler: Self::Sampler) {
+        Device::destroy_sampler(&self.device, sampler)
+    }
+
+    /// `gfx-hal` reports single size limit for images of all kinds
+    /// and addresses array layers with 16-bit indices.
+    fn limits(&self) -> Option<DeviceLimits> {
+        let limits = self.physical.borrow().limits();
+        let size = limits.max_texture_size as u32;
+        let sample_counts =
+            limits.framebuffer_color_samples_count & limits.framebuffer_depth_samples_count;
+        Some(DeviceLimits {
+            max_image_dimension_1d: size,
+            max_image_dimension_2d: size,
+            max_image_dimension_3d: size,
+            max_image_dimension_cube: size,
+            max_image_array_layers: u16::max_value() as u32,
+            max_texel_buffer_elements: limits.max_texel_elements as u32,
+            sample_counts: image::SampleCountFlags::from_bits_truncate(sample_counts.into()),
+            buffer_image_granularity: limits.buffer_image_granularity,
+        })
+    }
+
+    fn format_properties(&self, format: image::Format) -> Option<FormatProperties> {
+        let properties = self
+            .physical
+            .borrow()
+            .format_properties(Some(format.into()));
+        Some(FormatProperties {
+            linear_tiling_features: FormatFeatureFlags::from_bits_truncate(
+                properties.linear_tiling.bits(),
+            ),
+            optimal_tiling_features: FormatFeatureFlags::from_bits_truncate(
+                properties.optimal_tiling.bits(),
+            ),
+            buffer_features: FormatFeatureFlags::from_bits_truncate(
+                properties.buffer_features.bits(),
+            ),
+        })
+    }
 }
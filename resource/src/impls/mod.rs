@@ -3,3 +3,9 @@ mod hal;
 
 #[cfg(feature = "ash")]
 mod ash;
+
+#[cfg(feature = "hal")]
+pub use self::hal::HalDevice;
+
+#[cfg(feature = "ash")]
+pub use self::ash::AshDevice;
@@ -34,19 +34,38 @@ extern crate gfx_hal as hal;
 #[cfg(feature = "ash")]
 extern crate ash;
 
+#[cfg(test)]
+mod test;
+
+mod aliasing;
 mod device;
 mod error;
 mod escape;
+mod frame;
 mod impls;
 mod resources;
 
 pub mod buffer;
 pub mod image;
+pub mod sampler;
+pub mod sparse;
+pub mod validation;
 
+pub use aliasing::{Aliased, TransientInfo, TransientRequest, TransientResource};
 pub use device::Device;
-pub use error::{ImageCreationError, ResourceError};
+pub use error::{
+    BufferCreationError, ImageCreationError, ResourceError, SamplerCreationError, ValidationError,
+    ViewCreationError,
+};
+pub use frame::FrameIndex;
 pub use resources::Resources;
 
+#[cfg(feature = "hal")]
+pub use impls::HalDevice;
+
+#[cfg(feature = "ash")]
+pub use impls::AshDevice;
+
 /// Sharing mode.
 /// Resources created with sharing mode `Exclusive`
 /// can be accessed only from queues of single family that owns resource.
@@ -57,11 +76,78 @@ pub enum SharingMode {
     /// Sharing mode that denies sharing.
     /// Resource created with this sharing mode can be accessed
     /// only by queues of single family.
-    /// This generally results in faster access than concurrent sharing mode which is not implemented yet.
-    /// Ownership transfer is required in order to access resource by the queue from different family.Resources
+    /// This generally results in faster access than concurrent sharing mode.
+    /// Ownership transfer is required in order to access resource by the queue from different family.
     /// See Vulkan docs for more detail:
     /// <https://www.khronos.org/registry/vulkan/specs/1.1-extensions/html/vkspec.html#synchronization-queue-transfers>
     Exclusive,
+
+    /// Sharing mode that allows access from queues of all specified families
+    /// without ownership transfers.
+    /// At least two distinct families must be specified.
+    Concurrent(Families),
+}
+
+impl SharingMode {
+    /// Check if ownership transfer is required
+    /// to access resource by queue of family `to` after queue of family `from`.
+    pub fn transfer_required(&self, from: u32, to: u32) -> bool {
+        match *self {
+            SharingMode::Exclusive => from != to,
+            SharingMode::Concurrent(families) => {
+                from != to && !(families.contains(from) && families.contains(to))
+            }
+        }
+    }
+}
+
+/// Set of queue family indices.
+/// Family indices must be less than 32.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Families(u32);
+
+impl Families {
+    /// Empty set.
+    pub fn empty() -> Self {
+        Families(0)
+    }
+
+    /// Add family to the set.
+    pub fn with(self, family: u32) -> Self {
+        assert!(family < 32, "Family index {} is too large", family);
+        Families(self.0 | 1 << family)
+    }
+
+    /// Check if set contains the family.
+    pub fn contains(&self, family: u32) -> bool {
+        family < 32 && self.0 & 1 << family != 0
+    }
+
+    /// Number of families in the set.
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Check if set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// Iterate over family indices in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = u32> {
+        let bits = self.0;
+        (0..32).filter(move |&family| bits & 1 << family != 0)
+    }
+}
+
+impl std::iter::FromIterator<u32> for Families {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = u32>,
+    {
+        iter.into_iter()
+            .fold(Families::empty(), |families, family| families.with(family))
+    }
 }
 
 /// Memory requirements for the resource.
@@ -73,4 +159,15 @@ pub struct MemoryRequirements {
     pub align: u64,
     /// Memory type mask with bits set for memory types that support the resource.
     pub mask: u32,
+    /// Driver prefers the resource to be bound to memory object of its own.
+    pub prefers_dedicated: bool,
+    /// Driver requires the resource to be bound to memory object of its own.
+    pub requires_dedicated: bool,
+}
+
+impl MemoryRequirements {
+    /// Check if resource should get memory object of its own.
+    pub fn dedicated(&self) -> bool {
+        self.prefers_dedicated || self.requires_dedicated
+    }
 }
@@ -1,24 +1,75 @@
-use std::cmp::max;
+use std::{
+    cmp::max,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
-use memory::{Block, Heaps, MemoryError, Usage as MemoryUsage};
+use memory::{
+    usage::UsageValue as MemoryUsageValue, AliasingPlan, AliasingRequest, Block, Dedicated, Heaps,
+    MemoryBlock, Usage as MemoryUsage,
+};
 use relevant::Relevant;
 
+use aliasing::{Aliased, TransientInfo, TransientRequest, TransientResource};
 use buffer;
 use device::Device;
 use error::ResourceError;
 use escape::{Escape, Terminal};
+use frame::FrameIndex;
 use image;
+use sampler;
+use validation;
+use MemoryRequirements;
+use SharingMode;
 
 /// Resource manager.
-/// It can be used to create and destroy resources such as buffers and images.
+/// It can be used to create and destroy resources such as buffers and images
+/// as well as their views and samplers.
+/// Buffers and images are destroyed only after all their views are destroyed
+/// and the last frame that used them is complete.
 #[derive(Debug)]
-pub struct Resources<M, B, I> {
+pub struct Resources<M, B, I, BV, IV, S> {
     buffers: Terminal<buffer::Inner<M, B>>,
     images: Terminal<image::Inner<M, I>>,
+    buffer_views: Terminal<buffer::ViewInner<BV>>,
+    image_views: Terminal<image::ViewInner<IV>>,
+    samplers: Terminal<sampler::Inner<S>>,
+
+    /// Dropped buffers and images that still have views or are used by incomplete frames.
+    pending_buffers: Vec<buffer::Inner<M, B>>,
+    pending_images: Vec<image::Inner<M, I>>,
+
+    /// Index of the last complete frame. `0` if no frames are complete.
+    complete: u64,
 }
 
-impl<M: 'static, B: 'static, I: 'static> Resources<M, B, I> {
+impl<M, B, I, BV, IV, S> Resources<M, B, I, BV, IV, S>
+where
+    M: 'static,
+    B: 'static,
+    I: 'static,
+    BV: 'static,
+    IV: 'static,
+    S: 'static,
+{
+    /// Create new resource manager.
+    pub fn new() -> Self {
+        Resources {
+            buffers: Terminal::new(),
+            images: Terminal::new(),
+            buffer_views: Terminal::new(),
+            image_views: Terminal::new(),
+            samplers: Terminal::new(),
+            pending_buffers: Vec::new(),
+            pending_images: Vec::new(),
+            complete: 0,
+        }
+    }
+
     /// Create a buffer and bind to the memory that support intended usage.
+    /// Buffer gets memory object of its own if driver prefers or requires it.
     pub fn create_buffer<D, U>(
         &mut self,
         device: &D,
@@ -26,44 +77,97 @@ impl<M: 'static, B: 'static, I: 'static> Resources<M, B, I> {
         info: buffer::CreateInfo,
         align: u64,
         memory_usage: U,
-    ) -> Result<buffer::Buffer<M, B>, MemoryError>
+    ) -> Result<buffer::Buffer<M, B>, ResourceError>
     where
         D: Device<Memory = M, Buffer = B>,
         U: MemoryUsage,
     {
+        let memory_usage = memory_usage.value();
         let ubuf = device.create_buffer(info)?;
         let reqs = device.buffer_requirements(&ubuf);
-        let block = heaps.allocate(
-            device,
-            reqs.mask,
-            memory_usage,
-            reqs.size,
-            max(reqs.align, align),
-        )?;
-
-        let buf = unsafe {
-            device
-                .bind_buffer(ubuf, block.memory(), block.range().start)
-                .unwrap()
+        let dedicated = device.buffer_handle(&ubuf).map(Dedicated::Buffer);
+        let block = match self.allocate(heaps, device, reqs, align, memory_usage, dedicated) {
+            Ok(block) => block,
+            Err(error) => {
+                unsafe {
+                    device.destroy_unbound_buffer(ubuf);
+                }
+                return Err(error);
+            }
+        };
+
+        // Buffer that failed to bind is destroyed by the device.
+        let buf = match unsafe { device.bind_buffer(ubuf, block.memory(), block.range().start) } {
+            Ok(buf) => buf,
+            Err(error) => {
+                heaps.free(device, block);
+                return Err(error.into());
+            }
         };
 
         Ok(buffer::Buffer {
             inner: self.buffers.escape(buffer::Inner {
                 raw: buf,
                 block,
+                views: Arc::new(()),
+                last_use: AtomicU64::new(0),
+                name: None,
                 relevant: Relevant,
             }),
             info,
+            usage: (info.usage, memory_usage),
+        })
+    }
+
+    /// Create a buffer with usage specified by type.
+    /// Usage flags and memory usage are taken from `usage`.
+    pub fn create_typed_buffer<D, U>(
+        &mut self,
+        device: &D,
+        heaps: &mut Heaps<M>,
+        size: u64,
+        sharing: SharingMode,
+        align: u64,
+        usage: U,
+    ) -> Result<buffer::Buffer<M, B, U>, ResourceError>
+    where
+        D: Device<Memory = M, Buffer = B>,
+        U: buffer::Usage,
+    {
+        let info = buffer::CreateInfo {
+            size,
+            usage: usage.flags(),
+            sharing,
+        };
+        let buffer = self.create_buffer(device, heaps, info, align, usage.memory())?;
+
+        Ok(buffer::Buffer {
+            inner: buffer.inner,
+            info: buffer.info,
+            usage,
         })
     }
 
     /// Destroy buffer.
     /// Buffer can be dropped but this method reduces overhead.
-    pub unsafe fn destroy_buffer<D>(buffer: buffer::Buffer<M, B>, device: &D, heaps: &mut Heaps<M>)
-    where
+    ///
+    /// # Panics
+    ///
+    /// Panics if views of the buffer are alive.
+    pub unsafe fn destroy_buffer<D, U>(
+        buffer: buffer::Buffer<M, B, U>,
+        device: &D,
+        heaps: &mut Heaps<M>,
+    ) where
         D: Device<Memory = M, Buffer = B>,
     {
-        Self::destroy_buffer_inner(Escape::into_inner(buffer.inner), device, heaps)
+        let inner = Escape::into_inner(buffer.inner);
+        assert_eq!(
+            Arc::strong_count(&inner.views),
+            1,
+            "Buffer can't be destroyed while its views are alive"
+        );
+        Self::destroy_buffer_inner(inner, device, heaps)
     }
 
     unsafe fn destroy_buffer_inner<D>(inner: buffer::Inner<M, B>, device: &D, heaps: &mut Heaps<M>)
@@ -72,9 +176,12 @@ impl<M: 'static, B: 'static, I: 'static> Resources<M, B, I> {
     {
         device.destroy_buffer(inner.raw);
         heaps.free(device, inner.block);
+        inner.relevant.dispose();
     }
 
     /// Create an image and bind to the memory that support intended usage.
+    /// Images with `TRANSIENT_ATTACHMENT` usage get `Transient` memory regardless of `memory_usage`.
+    /// Image gets memory object of its own if driver prefers or requires it.
     pub fn create_image<D, U>(
         &mut self,
         device: &D,
@@ -87,22 +194,46 @@ impl<M: 'static, B: 'static, I: 'static> Resources<M, B, I> {
         D: Device<Memory = M, Image = I>,
         U: MemoryUsage,
     {
+        if let (Some(limits), Some(properties)) =
+            (device.limits(), device.format_properties(info.format))
+        {
+            validation::validate_image(&info, &limits, &properties)?;
+        }
+
         let uimg = device.create_image(info)?;
         let reqs = device.image_requirements(&uimg);
-        let block = heaps.allocate(
-            device,
-            reqs.mask,
-            memory_usage,
-            reqs.size,
-            max(reqs.align, align),
-        )?;
+        let memory_usage = if info.usage.contains(image::UsageFlags::TRANSIENT_ATTACHMENT) {
+            MemoryUsageValue::Transient
+        } else {
+            memory_usage.value()
+        };
+        let dedicated = device.image_handle(&uimg).map(Dedicated::Image);
+        let block = match self.allocate(heaps, device, reqs, align, memory_usage, dedicated) {
+            Ok(block) => block,
+            Err(error) => {
+                unsafe {
+                    device.destroy_unbound_image(uimg);
+                }
+                return Err(error);
+            }
+        };
 
-        let img = unsafe { device.bind_image(uimg, block.memory(), block.range().start)? };
+        // Image that failed to bind is destroyed by the device.
+        let img = match unsafe { device.bind_image(uimg, block.memory(), block.range().start) } {
+            Ok(img) => img,
+            Err(error) => {
+                heaps.free(device, block);
+                return Err(error.into());
+            }
+        };
 
         Ok(image::Image {
             inner: self.images.escape(image::Inner {
                 raw: img,
                 block,
+                views: Arc::new(()),
+                last_use: AtomicU64::new(0),
+                name: None,
                 relevant: Relevant,
             }),
             info,
@@ -111,11 +242,21 @@ impl<M: 'static, B: 'static, I: 'static> Resources<M, B, I> {
 
     /// Destroy image.
     /// Buffer can be dropped but this method reduces overhead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if views of the image are alive.
     pub unsafe fn destroy_image<D>(image: image::Image<M, I>, device: &D, heaps: &mut Heaps<M>)
     where
         D: Device<Memory = M, Image = I>,
     {
-        Self::destroy_image_inner(Escape::into_inner(image.inner), device, heaps)
+        let inner = Escape::into_inner(image.inner);
+        assert_eq!(
+            Arc::strong_count(&inner.views),
+            1,
+            "Image can't be destroyed while its views are alive"
+        );
+        Self::destroy_image_inner(inner, device, heaps)
     }
 
     unsafe fn destroy_image_inner<D>(inner: image::Inner<M, I>, device: &D, heaps: &mut Heaps<M>)
@@ -124,21 +265,404 @@ impl<M: 'static, B: 'static, I: 'static> Resources<M, B, I> {
     {
         device.destroy_image(inner.raw);
         heaps.free(device, inner.block);
+        inner.relevant.dispose();
+    }
+
+    /// Create a view to the buffer.
+    /// Buffer is kept alive until the view is destroyed.
+    pub fn create_buffer_view<D, U>(
+        &mut self,
+        device: &D,
+        buffer: &buffer::Buffer<M, B, U>,
+        info: buffer::ViewCreateInfo,
+    ) -> Result<buffer::BufferView<BV>, ResourceError>
+    where
+        D: Device<Memory = M, Buffer = B, BufferView = BV>,
+    {
+        if let (Some(limits), Some(properties)) =
+            (device.limits(), device.format_properties(info.format))
+        {
+            validation::validate_buffer_view(&buffer.info, &info, &limits, &properties)?;
+        }
+
+        let view = device.create_buffer_view(&buffer.inner.raw, info.clone())?;
+
+        Ok(buffer::BufferView {
+            inner: self.buffer_views.escape(buffer::ViewInner {
+                raw: view,
+                buffer: Arc::clone(&buffer.inner.views),
+                relevant: Relevant,
+            }),
+            info,
+        })
+    }
+
+    /// Destroy buffer view.
+    /// View can be dropped but this method reduces overhead.
+    pub unsafe fn destroy_buffer_view<D>(view: buffer::BufferView<BV>, device: &D)
+    where
+        D: Device<BufferView = BV>,
+    {
+        let inner = Escape::into_inner(view.inner);
+        device.destroy_buffer_view(inner.raw);
+        drop(inner.buffer);
+        inner.relevant.dispose();
+    }
+
+    /// Create a view to the image.
+    /// Image is kept alive until the view is destroyed.
+    pub fn create_image_view<D>(
+        &mut self,
+        device: &D,
+        image: &image::Image<M, I>,
+        info: image::ViewCreateInfo,
+    ) -> Result<image::ImageView<IV>, ResourceError>
+    where
+        D: Device<Memory = M, Image = I, ImageView = IV>,
+    {
+        if let Some(properties) = device.format_properties(info.format) {
+            validation::validate_image_view(&image.info, &info, &properties)?;
+        }
+
+        let view = device.create_image_view(&image.inner.raw, info.clone())?;
+
+        Ok(image::ImageView {
+            inner: self.image_views.escape(image::ViewInner {
+                raw: view,
+                image: Arc::clone(&image.inner.views),
+                relevant: Relevant,
+            }),
+            info,
+        })
+    }
+
+    /// Destroy image view.
+    /// View can be dropped but this method reduces overhead.
+    pub unsafe fn destroy_image_view<D>(view: image::ImageView<IV>, device: &D)
+    where
+        D: Device<ImageView = IV>,
+    {
+        let inner = Escape::into_inner(view.inner);
+        device.destroy_image_view(inner.raw);
+        drop(inner.image);
+        inner.relevant.dispose();
+    }
+
+    /// Create a sampler.
+    pub fn create_sampler<D>(
+        &mut self,
+        device: &D,
+        info: sampler::CreateInfo,
+    ) -> Result<sampler::Sampler<S>, ResourceError>
+    where
+        D: Device<Sampler = S>,
+    {
+        let raw = device.create_sampler(info)?;
+
+        Ok(sampler::Sampler {
+            inner: self.samplers.escape(sampler::Inner {
+                raw,
+                relevant: Relevant,
+            }),
+            info,
+        })
+    }
+
+    /// Destroy sampler.
+    /// Sampler can be dropped but this method reduces overhead.
+    pub unsafe fn destroy_sampler<D>(sampler: sampler::Sampler<S>, device: &D)
+    where
+        D: Device<Sampler = S>,
+    {
+        let inner = Escape::into_inner(sampler.inner);
+        device.destroy_sampler(inner.raw);
+        inner.relevant.dispose();
+    }
+
+    /// Create transient buffers and images and bind them to shared memory blocks.
+    /// Resources with disjoint lifetimes may be bound to overlapping memory ranges.
+    /// Resources that require dedicated allocation get memory objects of their own.
+    pub fn create_aliased<D, U>(
+        &mut self,
+        device: &D,
+        heaps: &mut Heaps<M>,
+        requests: &[TransientRequest],
+        memory_usage: U,
+    ) -> Result<Aliased<M, B, I>, ResourceError>
+    where
+        D: Device<Memory = M, Buffer = B, Image = I>,
+        U: MemoryUsage,
+    {
+        let memory_usage = memory_usage.value();
+        let granularity = device
+            .limits()
+            .map_or(1, |limits| limits.buffer_image_granularity);
+
+        let mut unbound = Vec::with_capacity(requests.len());
+        let created = requests
+            .iter()
+            .try_for_each(|request| -> Result<(), ResourceError> {
+                let (resource, reqs) = match request.info {
+                    TransientInfo::Buffer(info) => {
+                        let ubuf = device.create_buffer(info)?;
+                        let reqs = device.buffer_requirements(&ubuf);
+                        (TransientResource::Buffer(ubuf), reqs)
+                    }
+                    TransientInfo::Image(info) => {
+                        if let (Some(limits), Some(properties)) =
+                            (device.limits(), device.format_properties(info.format))
+                        {
+                            validation::validate_image(&info, &limits, &properties)?;
+                        }
+
+                        let uimg = device.create_image(info)?;
+                        let reqs = device.image_requirements(&uimg);
+                        (TransientResource::Image(uimg), reqs)
+                    }
+                };
+                unbound.push((resource, reqs));
+                Ok(())
+            });
+
+        if let Err(error) = created {
+            unsafe {
+                destroy_unbound(device, unbound.into_iter().map(|(resource, _)| resource));
+            }
+            return Err(error);
+        }
+
+        // Resources that require dedicated allocation can't share memory.
+        let aliased = unbound
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, reqs))| !reqs.requires_dedicated)
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        let plan = AliasingPlan::new(
+            &aliased
+                .iter()
+                .map(|&index| {
+                    let reqs = unbound[index].1;
+                    AliasingRequest {
+                        lifetime: requests[index].lifetime.clone(),
+                        size: reqs.size,
+                        align: reqs.align,
+                        mask: reqs.mask,
+                        linear: match requests[index].info {
+                            TransientInfo::Buffer(_) => true,
+                            TransientInfo::Image(info) => info.tiling == image::ImageTiling::Linear,
+                        },
+                    }
+                }).collect::<Vec<_>>(),
+            granularity,
+        );
+
+        // Block index and offset of each resource.
+        let mut bindings = vec![(0, 0); unbound.len()];
+        let mut blocks = Vec::with_capacity(plan.groups.len());
+        let result: Result<(), ResourceError> = plan
+            .groups
+            .iter()
+            .try_for_each(|group| {
+                let block =
+                    heaps.allocate(device, group.mask, memory_usage, group.size, group.align)?;
+                blocks.push(block);
+                Ok(())
+            }).and_then(|()| {
+                for (&index, placement) in aliased.iter().zip(&plan.placements) {
+                    bindings[index] = (placement.group, placement.offset);
+                }
+                for (index, &(ref resource, reqs)) in unbound.iter().enumerate() {
+                    if reqs.requires_dedicated {
+                        let dedicated = match *resource {
+                            TransientResource::Buffer(ref ubuf) => {
+                                device.buffer_handle(ubuf).map(Dedicated::Buffer)
+                            }
+                            TransientResource::Image(ref uimg) => {
+                                device.image_handle(uimg).map(Dedicated::Image)
+                            }
+                        };
+                        let block =
+                            self.allocate(heaps, device, reqs, 1, memory_usage, dedicated)?;
+                        bindings[index] = (blocks.len(), 0);
+                        blocks.push(block);
+                    }
+                }
+                Ok(())
+            });
+
+        if let Err(error) = result {
+            unsafe {
+                destroy_unbound(device, unbound.into_iter().map(|(resource, _)| resource));
+                let aliased = Aliased {
+                    resources: Vec::new(),
+                    blocks,
+                    relevant: Relevant,
+                };
+                aliased.dispose(device, heaps);
+            }
+            return Err(error);
+        }
+
+        let mut resources = Vec::with_capacity(unbound.len());
+        let mut unbound = unbound
+            .into_iter()
+            .map(|(resource, _)| resource)
+            .zip(bindings);
+        let mut bound = Ok(());
+        for (resource, (block, offset)) in unbound.by_ref() {
+            let ref block = blocks[block];
+            let offset = block.range().start + offset;
+            let resource = match resource {
+                TransientResource::Buffer(ubuf) => unsafe {
+                    device
+                        .bind_buffer(ubuf, block.memory(), offset)
+                        .map(TransientResource::Buffer)
+                },
+                TransientResource::Image(uimg) => unsafe {
+                    device
+                        .bind_image(uimg, block.memory(), offset)
+                        .map(TransientResource::Image)
+                },
+            };
+            match resource {
+                Ok(resource) => resources.push(resource),
+                Err(error) => {
+                    bound = Err(error);
+                    break;
+                }
+            }
+        }
+
+        let aliased = Aliased {
+            resources,
+            blocks,
+            relevant: Relevant,
+        };
+
+        match bound {
+            Ok(()) => Ok(aliased),
+            Err(error) => {
+                // Resource that failed to bind is destroyed by the device.
+                unsafe {
+                    destroy_unbound(device, unbound.map(|(resource, _)| resource));
+                    aliased.dispose(device, heaps);
+                }
+                Err(ResourceError::BindError(error))
+            }
+        }
+    }
+
+    /// Allocate memory block that satisfies `reqs`.
+    /// Block is dedicated to the resource if driver prefers or requires it.
+    /// Fails if dedicated allocation is required but resource handle is unknown.
+    fn allocate<D, U>(
+        &mut self,
+        heaps: &mut Heaps<M>,
+        device: &D,
+        reqs: MemoryRequirements,
+        align: u64,
+        memory_usage: U,
+        dedicated: Option<Dedicated>,
+    ) -> Result<MemoryBlock<M>, ResourceError>
+    where
+        D: Device<Memory = M>,
+        U: MemoryUsage,
+    {
+        let align = max(reqs.align, align);
+        let block = match dedicated {
+            Some(dedicated) if reqs.dedicated() => heaps.allocate_dedicated(
+                device,
+                reqs.mask,
+                memory_usage,
+                reqs.size,
+                align,
+                dedicated,
+            )?,
+            _ if reqs.requires_dedicated => return Err(ResourceError::UnknownDedicatedResource),
+            _ => heaps.allocate(device, reqs.mask, memory_usage, reqs.size, align)?,
+        };
+        Ok(block)
+    }
+
+    /// Report that all frames up to `frame` are complete.
+    /// Usually called with index of the `CompleteFrame`.
+    /// Dropped buffers and images last used by those frames are recycled by the next `cleanup`.
+    ///
+    /// # Safety
+    ///
+    /// All commands submitted in frames up to `frame` must be complete.
+    pub unsafe fn complete(&mut self, frame: FrameIndex) {
+        self.complete = max(self.complete, frame.value());
+    }
+
+    /// Get index of the last complete frame.
+    pub fn last_complete(&self) -> Option<FrameIndex> {
+        match self.complete {
+            0 => None,
+            index => Some(unsafe { FrameIndex::new(index) }),
+        }
     }
 
     /// Recycle dropped resources.
+    /// Dropped buffers and images with views still alive are recycled after their views.
+    /// Dropped buffers and images used by incomplete frames are recycled after those frames complete.
     pub unsafe fn cleanup<D>(&mut self, device: &D, heaps: &mut Heaps<M>)
     where
-        D: Device<Memory = M, Buffer = B, Image = I>,
+        D: Device<Memory = M, Buffer = B, Image = I, BufferView = BV, ImageView = IV, Sampler = S>,
     {
-        for buffer in self.buffers.drain() {
-            device.destroy_buffer(buffer.raw);
-            heaps.free(device, buffer.block);
+        for view in self.buffer_views.drain() {
+            device.destroy_buffer_view(view.raw);
+            drop(view.buffer);
+            view.relevant.dispose();
+        }
+
+        for view in self.image_views.drain() {
+            device.destroy_image_view(view.raw);
+            drop(view.image);
+            view.relevant.dispose();
         }
 
-        for image in self.images.drain() {
-            device.destroy_image(image.raw);
-            heaps.free(device, image.block);
+        for sampler in self.samplers.drain() {
+            device.destroy_sampler(sampler.raw);
+            sampler.relevant.dispose();
+        }
+
+        let mut buffers = self.buffers.drain().collect::<Vec<_>>();
+        buffers.extend(self.pending_buffers.drain(..));
+        for buffer in buffers {
+            let used = buffer.last_use.load(Ordering::Relaxed) > self.complete;
+            if used || Arc::strong_count(&buffer.views) > 1 {
+                self.pending_buffers.push(buffer);
+            } else {
+                Self::destroy_buffer_inner(buffer, device, heaps);
+            }
+        }
+
+        let mut images = self.images.drain().collect::<Vec<_>>();
+        images.extend(self.pending_images.drain(..));
+        for image in images {
+            let used = image.last_use.load(Ordering::Relaxed) > self.complete;
+            if used || Arc::strong_count(&image.views) > 1 {
+                self.pending_images.push(image);
+            } else {
+                Self::destroy_image_inner(image, device, heaps);
+            }
+        }
+    }
+}
+
+/// Destroy transient resources that were never bound to memory.
+unsafe fn destroy_unbound<D>(
+    device: &D,
+    unbound: impl IntoIterator<Item = TransientResource<D::UnboundBuffer, D::UnboundImage>>,
+) where
+    D: Device,
+{
+    for resource in unbound {
+        match resource {
+            TransientResource::Buffer(ubuf) => device.destroy_unbound_buffer(ubuf),
+            TransientResource::Image(uimg) => device.destroy_unbound_image(uimg),
         }
     }
 }
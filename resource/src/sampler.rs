@@ -0,0 +1,180 @@
+//! Sampler filtering, addressing, creation-info and wrapper.
+
+use relevant::Relevant;
+
+use escape::Escape;
+
+/// Texel filtering mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Filter {
+    /// Nearest texel is used.
+    Nearest,
+
+    /// Weighted average of neighbouring texels is used.
+    Linear,
+}
+
+/// Filtering mode between mip levels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MipmapMode {
+    /// Nearest mip level is used.
+    Nearest,
+
+    /// Weighted average of two nearest mip levels is used.
+    Linear,
+}
+
+/// Handling of texel coordinates outside of the image.
+/// See Vulkan docs for details:
+/// <https://www.khronos.org/registry/vulkan/specs/1.1-extensions/man/html/VkSamplerAddressMode.html>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressMode {
+    /// Image is tiled.
+    Repeat,
+
+    /// Image is tiled with every other tile mirrored.
+    MirroredRepeat,
+
+    /// Coordinates are clamped to the edge of the image.
+    ClampToEdge,
+
+    /// Border color is used outside of the image.
+    ClampToBorder,
+
+    /// Image is mirrored once and then clamped to the edge.
+    MirrorClampToEdge,
+}
+
+/// Color used with `AddressMode::ClampToBorder`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BorderColor {
+    /// `(0.0, 0.0, 0.0, 0.0)` for floating-point formats.
+    FloatTransparentBlack,
+
+    /// `(0, 0, 0, 0)` for integer formats.
+    IntTransparentBlack,
+
+    /// `(0.0, 0.0, 0.0, 1.0)` for floating-point formats.
+    FloatOpaqueBlack,
+
+    /// `(0, 0, 0, 1)` for integer formats.
+    IntOpaqueBlack,
+
+    /// `(1.0, 1.0, 1.0, 1.0)` for floating-point formats.
+    FloatOpaqueWhite,
+
+    /// `(1, 1, 1, 1)` for integer formats.
+    IntOpaqueWhite,
+}
+
+/// Comparison operator applied to fetched texels for depth comparison.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CompareOp {
+    /// Comparison always fails.
+    Never,
+
+    /// Passes if reference is less than texel.
+    Less,
+
+    /// Passes if reference is equal to texel.
+    Equal,
+
+    /// Passes if reference is less than or equal to texel.
+    LessOrEqual,
+
+    /// Passes if reference is greater than texel.
+    Greater,
+
+    /// Passes if reference is not equal to texel.
+    NotEqual,
+
+    /// Passes if reference is greater than or equal to texel.
+    GreaterOrEqual,
+
+    /// Comparison always passes.
+    Always,
+}
+
+/// Contains information required to create a sampler.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CreateInfo {
+    /// Filter used for magnification.
+    pub mag_filter: Filter,
+
+    /// Filter used for minification.
+    pub min_filter: Filter,
+
+    /// Filter used between mip levels.
+    pub mipmap_mode: MipmapMode,
+
+    /// Addressing mode for `u`, `v` and `w` coordinates.
+    pub address: [AddressMode; 3],
+
+    /// Bias added to calculated mip level.
+    pub lod_bias: f32,
+
+    /// Calculated mip level is clamped to this range.
+    pub lod_range: (f32, f32),
+
+    /// Maximum anisotropy. `None` disables anisotropic filtering.
+    pub anisotropy: Option<f32>,
+
+    /// Comparison operator. `None` disables comparison.
+    pub compare: Option<CompareOp>,
+
+    /// Border color.
+    pub border: BorderColor,
+
+    /// Use unnormalized texel coordinates.
+    pub unnormalized: bool,
+}
+
+impl CreateInfo {
+    /// Info for sampler with specified filter and addressing mode for all coordinates.
+    pub fn new(filter: Filter, address: AddressMode) -> Self {
+        CreateInfo {
+            mag_filter: filter,
+            min_filter: filter,
+            mipmap_mode: match filter {
+                Filter::Nearest => MipmapMode::Nearest,
+                Filter::Linear => MipmapMode::Linear,
+            },
+            address: [address; 3],
+            lod_bias: 0.0,
+            lod_range: (0.0, 1000.0),
+            anisotropy: None,
+            compare: None,
+            border: BorderColor::FloatTransparentBlack,
+            unnormalized: false,
+        }
+    }
+}
+
+/// Generic sampler object wrapper.
+///
+/// # Parameters
+///
+/// `S` - raw sampler type.
+#[derive(Debug)]
+pub struct Sampler<S> {
+    pub(crate) inner: Escape<Inner<S>>,
+    pub(crate) info: CreateInfo,
+}
+
+impl<S> Sampler<S> {
+    /// Get raw sampler.
+    pub fn raw(&self) -> &S {
+        &self.inner.raw
+    }
+
+    /// Get info the sampler was created with.
+    pub fn info(&self) -> &CreateInfo {
+        &self.info
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Inner<S> {
+    pub(crate) raw: S,
+    pub(crate) relevant: Relevant,
+}
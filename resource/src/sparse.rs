@@ -0,0 +1,459 @@
+//! Bookkeeping for partially resident sparse images and buffers.
+//! Memory tiles are allocated from `Heaps` and bound to the resource by sparse-binding queue.
+//! Residency trackers produce lists of bind and unbind operations to be submitted to that queue.
+
+use std::{cmp::min, collections::BTreeMap};
+
+use memory::{Block, Device, Heaps, MemoryBlock, MemoryError, Usage as MemoryUsage};
+
+use image::{Extent3D, Offset3D};
+
+/// Tile of sparse image.
+/// Coordinates are counted in tiles of the mip level.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tile {
+    /// Array layer of the tile.
+    pub layer: u32,
+
+    /// Mip level of the tile.
+    pub mip: u32,
+
+    /// Z coordinate of the tile.
+    pub z: u32,
+
+    /// Y coordinate of the tile.
+    pub y: u32,
+
+    /// X coordinate of the tile.
+    pub x: u32,
+}
+
+/// Operation that binds memory to the image region or unbinds it.
+#[derive(Debug)]
+pub struct SparseImageBind<'a, M: 'a> {
+    /// Array layer of the region.
+    pub layer: u32,
+
+    /// Mip level of the region.
+    pub mip: u32,
+
+    /// Offset of the region in texels.
+    pub offset: Offset3D,
+
+    /// Size of the region in texels.
+    /// Tiles on the image edge are clipped.
+    pub extent: Extent3D,
+
+    /// Memory object and offset bound to the region.
+    /// `None` for unbind operation.
+    pub memory: Option<(&'a M, u64)>,
+}
+
+/// Operation that binds memory to the buffer range or unbinds it.
+#[derive(Debug)]
+pub struct SparseBufferBind<'a, M: 'a> {
+    /// Offset of the range in the buffer.
+    pub offset: u64,
+
+    /// Size of the range.
+    pub size: u64,
+
+    /// Memory object and offset bound to the range.
+    /// `None` for unbind operation.
+    pub memory: Option<(&'a M, u64)>,
+}
+
+/// Residency of tiles of sparse image.
+///
+/// Tiles are made resident or evicted in batches.
+/// Operations of the batch are fetched with `operations`
+/// and the batch is closed with `submitted` after operations are submitted to the device.
+#[derive(Debug)]
+pub struct SparseImage<M> {
+    extent: Extent3D,
+    mips: u32,
+    layers: u32,
+    tile_extent: Extent3D,
+    residency: Residency<Tile, M>,
+}
+
+impl<M: 'static> SparseImage<M> {
+    /// Create residency tracker for image with `extent`, `mips` levels and `layers`.
+    /// Image has no resident tiles initially.
+    /// `tile_extent` and `tile_size` are image granularity and memory alignment
+    /// reported by the device for sparse image.
+    pub fn new(
+        extent: Extent3D,
+        mips: u32,
+        layers: u32,
+        tile_extent: Extent3D,
+        tile_size: u64,
+    ) -> Self {
+        assert!(extent.width > 0 && extent.height > 0 && extent.depth > 0);
+        assert!(tile_extent.width > 0 && tile_extent.height > 0 && tile_extent.depth > 0);
+        assert!(mips > 0 && layers > 0);
+        SparseImage {
+            extent,
+            mips,
+            layers,
+            tile_extent,
+            residency: Residency::new(tile_size),
+        }
+    }
+
+    /// Size of the memory tile.
+    pub fn tile_size(&self) -> u64 {
+        self.residency.tile_size
+    }
+
+    /// Size of the tile in texels.
+    pub fn tile_extent(&self) -> Extent3D {
+        self.tile_extent
+    }
+
+    /// Size of the `mip` level in texels.
+    pub fn mip_extent(&self, mip: u32) -> Extent3D {
+        assert!(mip < self.mips, "Mip level {} is out of bounds", mip);
+        self.extent.mip_level(mip)
+    }
+
+    /// Number of tiles of the `mip` level in each dimension.
+    pub fn tiles(&self, mip: u32) -> Extent3D {
+        let extent = self.mip_extent(mip);
+        Extent3D {
+            width: (extent.width + self.tile_extent.width - 1) / self.tile_extent.width,
+            height: (extent.height + self.tile_extent.height - 1) / self.tile_extent.height,
+            depth: (extent.depth + self.tile_extent.depth - 1) / self.tile_extent.depth,
+        }
+    }
+
+    /// Check if `tile` is within the image.
+    pub fn contains(&self, tile: Tile) -> bool {
+        if tile.layer >= self.layers || tile.mip >= self.mips {
+            return false;
+        }
+        let tiles = self.tiles(tile.mip);
+        tile.x < tiles.width && tile.y < tiles.height && tile.z < tiles.depth
+    }
+
+    /// Check if `tile` has memory bound to it.
+    pub fn is_resident(&self, tile: Tile) -> bool {
+        self.residency.is_resident(&tile)
+    }
+
+    /// Iterate over resident tiles.
+    pub fn resident(&self) -> impl Iterator<Item = Tile> + '_ {
+        self.residency.resident.keys().cloned()
+    }
+
+    /// Number of resident tiles.
+    pub fn resident_count(&self) -> usize {
+        self.residency.resident.len()
+    }
+
+    /// Allocate memory for `tiles` that are not resident yet
+    /// from one of memory types specified by `mask` for intended `usage`.
+    /// Either all tiles become resident or none.
+    pub fn make_resident<D, U, I>(
+        &mut self,
+        device: &D,
+        heaps: &mut Heaps<M>,
+        mask: u32,
+        usage: U,
+        tiles: I,
+    ) -> Result<(), MemoryError>
+    where
+        D: Device<Memory = M>,
+        U: MemoryUsage,
+        I: IntoIterator<Item = Tile>,
+    {
+        let tiles = tiles
+            .into_iter()
+            .inspect(|&tile| {
+                assert!(self.contains(tile), "Tile {:?} is out of bounds", tile);
+            }).collect::<Vec<_>>();
+        self.residency
+            .make_resident(device, heaps, mask, usage, tiles)
+    }
+
+    /// Evict `tiles`.
+    /// Memory of the tiles is returned by `submitted`.
+    pub fn evict<I>(&mut self, tiles: I)
+    where
+        I: IntoIterator<Item = Tile>,
+    {
+        self.residency.evict(tiles)
+    }
+
+    /// Get operations of the current batch.
+    /// Unbind operations go first.
+    pub fn operations(&self) -> Vec<SparseImageBind<'_, M>> {
+        self.residency
+            .operations()
+            .map(|(tile, memory)| {
+                let extent = self.mip_extent(tile.mip);
+                let x = tile.x * self.tile_extent.width;
+                let y = tile.y * self.tile_extent.height;
+                let z = tile.z * self.tile_extent.depth;
+                SparseImageBind {
+                    layer: tile.layer,
+                    mip: tile.mip,
+                    offset: Offset3D {
+                        x: x as i32,
+                        y: y as i32,
+                        z: z as i32,
+                    },
+                    extent: Extent3D {
+                        width: min(self.tile_extent.width, extent.width - x),
+                        height: min(self.tile_extent.height, extent.height - y),
+                        depth: min(self.tile_extent.depth, extent.depth - z),
+                    },
+                    memory,
+                }
+            }).collect()
+    }
+
+    /// Close current batch after its operations are submitted.
+    /// Returns memory blocks of evicted tiles.
+    /// They must be freed after submitted operations are complete.
+    pub fn submitted(&mut self) -> Vec<MemoryBlock<M>> {
+        self.residency.submitted()
+    }
+
+    /// Free memory of all tiles.
+    /// Image must be destroyed or all its tiles unbound beforehand.
+    pub fn dispose<D>(self, device: &D, heaps: &mut Heaps<M>)
+    where
+        D: Device<Memory = M>,
+    {
+        self.residency.dispose(device, heaps)
+    }
+}
+
+/// Residency of tiles of sparse buffer.
+/// Tiles are ranges of `tile_size` bytes indexed from the buffer start.
+///
+/// Tiles are made resident or evicted in batches.
+/// Operations of the batch are fetched with `operations`
+/// and the batch is closed with `submitted` after operations are submitted to the device.
+#[derive(Debug)]
+pub struct SparseBuffer<M> {
+    size: u64,
+    residency: Residency<u64, M>,
+}
+
+impl<M: 'static> SparseBuffer<M> {
+    /// Create residency tracker for buffer of `size` bytes.
+    /// Buffer has no resident tiles initially.
+    /// `tile_size` is the memory alignment reported by the device for sparse buffer.
+    pub fn new(size: u64, tile_size: u64) -> Self {
+        assert_ne!(size, 0);
+        SparseBuffer {
+            size,
+            residency: Residency::new(tile_size),
+        }
+    }
+
+    /// Size of the memory tile.
+    pub fn tile_size(&self) -> u64 {
+        self.residency.tile_size
+    }
+
+    /// Number of tiles of the buffer.
+    pub fn tiles(&self) -> u64 {
+        (self.size + self.tile_size() - 1) / self.tile_size()
+    }
+
+    /// Check if `tile` has memory bound to it.
+    pub fn is_resident(&self, tile: u64) -> bool {
+        self.residency.is_resident(&tile)
+    }
+
+    /// Iterate over resident tiles.
+    pub fn resident(&self) -> impl Iterator<Item = u64> + '_ {
+        self.residency.resident.keys().cloned()
+    }
+
+    /// Number of resident tiles.
+    pub fn resident_count(&self) -> usize {
+        self.residency.resident.len()
+    }
+
+    /// Allocate memory for `tiles` that are not resident yet
+    /// from one of memory types specified by `mask` for intended `usage`.
+    /// Either all tiles become resident or none.
+    pub fn make_resident<D, U, I>(
+        &mut self,
+        device: &D,
+        heaps: &mut Heaps<M>,
+        mask: u32,
+        usage: U,
+        tiles: I,
+    ) -> Result<(), MemoryError>
+    where
+        D: Device<Memory = M>,
+        U: MemoryUsage,
+        I: IntoIterator<Item = u64>,
+    {
+        let count = self.tiles();
+        let tiles = tiles
+            .into_iter()
+            .inspect(|&tile| {
+                assert!(tile < count, "Tile {} is out of bounds", tile);
+            }).collect::<Vec<_>>();
+        self.residency
+            .make_resident(device, heaps, mask, usage, tiles)
+    }
+
+    /// Evict `tiles`.
+    /// Memory of the tiles is returned by `submitted`.
+    pub fn evict<I>(&mut self, tiles: I)
+    where
+        I: IntoIterator<Item = u64>,
+    {
+        self.residency.evict(tiles)
+    }
+
+    /// Get operations of the current batch.
+    /// Unbind operations go first.
+    pub fn operations(&self) -> Vec<SparseBufferBind<'_, M>> {
+        let tile_size = self.tile_size();
+        self.residency
+            .operations()
+            .map(|(tile, memory)| {
+                let offset = tile * tile_size;
+                SparseBufferBind {
+                    offset,
+                    size: min(tile_size, self.size - offset),
+                    memory,
+                }
+            }).collect()
+    }
+
+    /// Close current batch after its operations are submitted.
+    /// Returns memory blocks of evicted tiles.
+    /// They must be freed after submitted operations are complete.
+    pub fn submitted(&mut self) -> Vec<MemoryBlock<M>> {
+        self.residency.submitted()
+    }
+
+    /// Free memory of all tiles.
+    /// Buffer must be destroyed or all its tiles unbound beforehand.
+    pub fn dispose<D>(self, device: &D, heaps: &mut Heaps<M>)
+    where
+        D: Device<Memory = M>,
+    {
+        self.residency.dispose(device, heaps)
+    }
+}
+
+/// Residency of tiles identified by keys of type `K`.
+#[derive(Debug)]
+struct Residency<K, M> {
+    tile_size: u64,
+
+    /// Memory blocks bound to resident tiles.
+    resident: BTreeMap<K, MemoryBlock<M>>,
+
+    /// Tiles bound in current batch.
+    bound: Vec<K>,
+
+    /// Tiles unbound in current batch.
+    unbound: Vec<K>,
+
+    /// Memory blocks of evicted tiles.
+    released: Vec<MemoryBlock<M>>,
+}
+
+impl<K, M> Residency<K, M>
+where
+    K: Copy + Ord,
+    M: 'static,
+{
+    fn new(tile_size: u64) -> Self {
+        assert_eq!(tile_size.count_ones(), 1, "Tile size must be power of two");
+        Residency {
+            tile_size,
+            resident: BTreeMap::new(),
+            bound: Vec::new(),
+            unbound: Vec::new(),
+            released: Vec::new(),
+        }
+    }
+
+    fn is_resident(&self, tile: &K) -> bool {
+        self.resident.contains_key(tile)
+    }
+
+    fn make_resident<D, U>(
+        &mut self,
+        device: &D,
+        heaps: &mut Heaps<M>,
+        mask: u32,
+        usage: U,
+        mut tiles: Vec<K>,
+    ) -> Result<(), MemoryError>
+    where
+        D: Device<Memory = M>,
+        U: MemoryUsage,
+    {
+        tiles.sort();
+        tiles.dedup();
+        tiles.retain(|tile| !self.resident.contains_key(tile));
+        if tiles.is_empty() {
+            return Ok(());
+        }
+
+        let blocks = heaps.allocate_tiles(device, mask, usage, self.tile_size, tiles.len())?;
+        for (tile, block) in tiles.into_iter().zip(blocks) {
+            self.resident.insert(tile, block);
+            self.bound.push(tile);
+        }
+        Ok(())
+    }
+
+    fn evict<I>(&mut self, tiles: I)
+    where
+        I: IntoIterator<Item = K>,
+    {
+        for tile in tiles {
+            if let Some(block) = self.resident.remove(&tile) {
+                self.released.push(block);
+                match self.bound.iter().position(|&bound| bound == tile) {
+                    // Tile wasn't bound yet. Just forget about it.
+                    Some(index) => {
+                        self.bound.remove(index);
+                    }
+                    None => self.unbound.push(tile),
+                }
+            }
+        }
+    }
+
+    fn operations(&self) -> impl Iterator<Item = (K, Option<(&M, u64)>)> {
+        let unbinds = self.unbound.iter().map(|&tile| (tile, None));
+        let binds = self.bound.iter().map(move |tile| {
+            let block = &self.resident[tile];
+            (*tile, Some((block.memory(), block.range().start)))
+        });
+        unbinds.chain(binds)
+    }
+
+    fn submitted(&mut self) -> Vec<MemoryBlock<M>> {
+        self.bound.clear();
+        self.unbound.clear();
+        ::std::mem::replace(&mut self.released, Vec::new())
+    }
+
+    fn dispose<D>(self, device: &D, heaps: &mut Heaps<M>)
+    where
+        D: Device<Memory = M>,
+    {
+        for block in self.released {
+            heaps.free(device, block);
+        }
+        for (_, block) in self.resident {
+            heaps.free(device, block);
+        }
+    }
+}
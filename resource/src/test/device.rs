@@ -0,0 +1,347 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    ops::Range,
+    ptr::NonNull,
+};
+
+use memory::{
+    mock::{MockDevice, MockMemory},
+    AllocationError, Dedicated, Device as MemoryDevice, MappingError, OutOfMemoryError,
+};
+
+use buffer;
+use device::Device;
+use error::{
+    BindError, BufferCreationError, ImageCreationError, SamplerCreationError, ViewCreationError,
+};
+use image;
+use sampler;
+use validation::{DeviceLimits, FormatFeatureFlags, FormatProperties};
+use MemoryRequirements;
+
+/// Kind of the object created by `TestDevice`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Object {
+    Buffer,
+    BufferView,
+    Image,
+    ImageView,
+    Sampler,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next: u64,
+    live: HashSet<(Object, u64)>,
+    destroyed: Vec<(Object, u64)>,
+    names: HashMap<(Object, u64), String>,
+    prefers_dedicated: bool,
+    requires_dedicated: bool,
+    hide_handles: bool,
+}
+
+/// Device that creates resources backed by `MockDevice` memory.
+/// Raw objects are unique ids.
+/// Destroying objects that are not alive results in panic.
+#[derive(Debug, Default)]
+pub struct TestDevice {
+    memory: MockDevice,
+    state: RefCell<State>,
+    limits: Option<DeviceLimits>,
+    formats: Vec<(image::Format, FormatProperties)>,
+}
+
+impl TestDevice {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Device that reports limits and format properties for validation.
+    /// Formats not listed have no features.
+    pub fn with_validation(
+        limits: DeviceLimits,
+        formats: Vec<(image::Format, FormatProperties)>,
+    ) -> Self {
+        TestDevice {
+            limits: Some(limits),
+            formats,
+            ..Self::default()
+        }
+    }
+
+    /// Memory device.
+    pub fn memory(&self) -> &MockDevice {
+        &self.memory
+    }
+
+    /// Number of live objects of specified kind.
+    pub fn live(&self, object: Object) -> usize {
+        self.state
+            .borrow()
+            .live
+            .iter()
+            .filter(|&&(kind, _)| kind == object)
+            .count()
+    }
+
+    /// Objects in order of destruction.
+    pub fn destroyed(&self) -> Vec<(Object, u64)> {
+        self.state.borrow().destroyed.clone()
+    }
+
+    /// Make requirements of resources created afterwards prefer or require dedicated allocation.
+    pub fn set_dedicated(&self, prefers: bool, requires: bool) {
+        let mut state = self.state.borrow_mut();
+        state.prefers_dedicated = prefers;
+        state.requires_dedicated = requires;
+    }
+
+    /// Make `buffer_handle` and `image_handle` return `None` like backends that don't know handles.
+    pub fn hide_handles(&self, hide: bool) {
+        self.state.borrow_mut().hide_handles = hide;
+    }
+
+    /// Debug name set for the object.
+    pub fn name(&self, object: Object, id: u64) -> Option<String> {
+        self.state.borrow().names.get(&(object, id)).cloned()
+    }
+
+    fn set_name(&self, object: Object, id: u64, name: &str) {
+        let mut state = self.state.borrow_mut();
+        assert!(
+            state.live.contains(&(object, id)),
+            "{:?} {} is not alive",
+            object,
+            id
+        );
+        state.names.insert((object, id), name.to_owned());
+    }
+
+    fn requirements(&self, size: u64) -> MemoryRequirements {
+        let state = self.state.borrow();
+        MemoryRequirements {
+            size,
+            align: 256,
+            mask: !0,
+            prefers_dedicated: state.prefers_dedicated,
+            requires_dedicated: state.requires_dedicated,
+        }
+    }
+
+    fn create(&self, object: Object) -> u64 {
+        let mut state = self.state.borrow_mut();
+        let id = state.next;
+        state.next += 1;
+        state.live.insert((object, id));
+        id
+    }
+
+    fn destroy(&self, object: Object, id: u64) {
+        let mut state = self.state.borrow_mut();
+        assert!(
+            state.live.remove(&(object, id)),
+            "{:?} {} is not alive",
+            object,
+            id
+        );
+        state.destroyed.push((object, id));
+    }
+}
+
+impl MemoryDevice for TestDevice {
+    type Memory = MockMemory;
+
+    unsafe fn allocate(&self, index: u32, size: u64) -> Result<MockMemory, AllocationError> {
+        self.memory.allocate(index, size)
+    }
+
+    unsafe fn allocate_dedicated(
+        &self,
+        index: u32,
+        size: u64,
+        dedicated: Dedicated,
+    ) -> Result<MockMemory, AllocationError> {
+        self.memory.allocate_dedicated(index, size, dedicated)
+    }
+
+    unsafe fn free(&self, memory: MockMemory) {
+        self.memory.free(memory)
+    }
+
+    unsafe fn map(
+        &self,
+        memory: &MockMemory,
+        range: Range<u64>,
+    ) -> Result<NonNull<u8>, MappingError> {
+        self.memory.map(memory, range)
+    }
+
+    unsafe fn unmap(&self, memory: &MockMemory) {
+        self.memory.unmap(memory)
+    }
+
+    unsafe fn invalidate<'a>(
+        &self,
+        regions: impl IntoIterator<Item = (&'a MockMemory, Range<u64>)>,
+    ) -> Result<(), OutOfMemoryError> {
+        self.memory.invalidate(regions)
+    }
+
+    unsafe fn flush<'a>(
+        &self,
+        regions: impl IntoIterator<Item = (&'a MockMemory, Range<u64>)>,
+    ) -> Result<(), OutOfMemoryError> {
+        self.memory.flush(regions)
+    }
+}
+
+impl Device for TestDevice {
+    type Sampler = u64;
+    type Buffer = u64;
+    type UnboundBuffer = (u64, u64);
+    type BufferView = u64;
+    type Image = u64;
+    type UnboundImage = (u64, u64);
+    type ImageView = u64;
+
+    fn create_buffer(&self, info: buffer::CreateInfo) -> Result<(u64, u64), BufferCreationError> {
+        Ok((self.create(Object::Buffer), info.size))
+    }
+
+    fn buffer_requirements(&self, buffer: &(u64, u64)) -> MemoryRequirements {
+        self.requirements(buffer.1)
+    }
+
+    fn buffer_handle(&self, buffer: &(u64, u64)) -> Option<u64> {
+        if self.state.borrow().hide_handles {
+            None
+        } else {
+            Some(buffer.0)
+        }
+    }
+
+    unsafe fn bind_buffer(
+        &self,
+        buffer: (u64, u64),
+        _memory: &MockMemory,
+        offset: u64,
+    ) -> Result<u64, BindError> {
+        assert_eq!(offset % 256, 0);
+        Ok(buffer.0)
+    }
+
+    unsafe fn destroy_buffer(&self, buffer: u64) {
+        self.destroy(Object::Buffer, buffer)
+    }
+
+    unsafe fn destroy_unbound_buffer(&self, buffer: (u64, u64)) {
+        self.destroy(Object::Buffer, buffer.0)
+    }
+
+    fn create_buffer_view(
+        &self,
+        buffer: &u64,
+        _info: buffer::ViewCreateInfo,
+    ) -> Result<u64, ViewCreationError> {
+        let live = self
+            .state
+            .borrow()
+            .live
+            .contains(&(Object::Buffer, *buffer));
+        assert!(live, "Buffer is not alive");
+        Ok(self.create(Object::BufferView))
+    }
+
+    unsafe fn destroy_buffer_view(&self, view: u64) {
+        self.destroy(Object::BufferView, view)
+    }
+
+    fn create_image(&self, info: image::CreateInfo) -> Result<(u64, u64), ImageCreationError> {
+        let texels =
+            info.extent.width as u64 * info.extent.height as u64 * info.extent.depth as u64;
+        Ok((self.create(Object::Image), texels * info.array as u64 * 4))
+    }
+
+    fn image_requirements(&self, image: &(u64, u64)) -> MemoryRequirements {
+        self.requirements(image.1)
+    }
+
+    fn image_handle(&self, image: &(u64, u64)) -> Option<u64> {
+        if self.state.borrow().hide_handles {
+            None
+        } else {
+            Some(image.0)
+        }
+    }
+
+    unsafe fn bind_image(
+        &self,
+        image: (u64, u64),
+        _memory: &MockMemory,
+        offset: u64,
+    ) -> Result<u64, BindError> {
+        assert_eq!(offset % 256, 0);
+        Ok(image.0)
+    }
+
+    unsafe fn destroy_image(&self, image: u64) {
+        self.destroy(Object::Image, image)
+    }
+
+    unsafe fn destroy_unbound_image(&self, image: (u64, u64)) {
+        self.destroy(Object::Image, image.0)
+    }
+
+    fn create_image_view(
+        &self,
+        image: &u64,
+        _info: image::ViewCreateInfo,
+    ) -> Result<u64, ViewCreationError> {
+        let live = self.state.borrow().live.contains(&(Object::Image, *image));
+        assert!(live, "Image is not alive");
+        Ok(self.create(Object::ImageView))
+    }
+
+    unsafe fn destroy_image_view(&self, view: u64) {
+        self.destroy(Object::ImageView, view)
+    }
+
+    /// Unnormalized coordinates are not supported, like in `gfx-hal` backends.
+    fn create_sampler(&self, info: sampler::CreateInfo) -> Result<u64, SamplerCreationError> {
+        if info.unnormalized {
+            return Err(SamplerCreationError::Unsupported);
+        }
+        Ok(self.create(Object::Sampler))
+    }
+
+    unsafe fn destroy_sampler(&self, sampler: u64) {
+        self.destroy(Object::Sampler, sampler)
+    }
+
+    fn set_buffer_name(&self, buffer: &u64, name: &str) {
+        self.set_name(Object::Buffer, *buffer, name)
+    }
+
+    fn set_image_name(&self, image: &u64, name: &str) {
+        self.set_name(Object::Image, *image, name)
+    }
+
+    fn limits(&self) -> Option<DeviceLimits> {
+        self.limits
+    }
+
+    fn format_properties(&self, format: image::Format) -> Option<FormatProperties> {
+        self.limits?;
+        let properties = self
+            .formats
+            .iter()
+            .find(|&&(f, _)| f == format)
+            .map(|&(_, properties)| properties);
+        Some(properties.unwrap_or(FormatProperties {
+            linear_tiling_features: FormatFeatureFlags::empty(),
+            optimal_tiling_features: FormatFeatureFlags::empty(),
+            buffer_features: FormatFeatureFlags::empty(),
+        }))
+    }
+}
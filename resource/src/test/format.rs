@@ -0,0 +1,318 @@
+use image::{format::*, ChannelType, ChannelType::*, ImageAspectFlags};
+
+type Row = (
+    Format,
+    u32,
+    (u32, u32),
+    ImageAspectFlags,
+    bool,
+    Option<ChannelType>,
+);
+
+/// Texel block size, block extent, aspects, compression and channel type of every format.
+/// Taken from the Vulkan spec format compatibility table:
+/// <https://www.khronos.org/registry/vulkan/specs/1.1-extensions/html/vkspec.html#formats-compatibility>
+#[cfg_attr(rustfmt, rustfmt_skip)]
+fn table() -> Vec<Row> {
+    let none = ImageAspectFlags::empty();
+    let color = ImageAspectFlags::COLOR;
+    let depth = ImageAspectFlags::DEPTH;
+    let stencil = ImageAspectFlags::STENCIL;
+    let depth_stencil = depth | stencil;
+
+    vec![
+        (UNDEFINED, 0, (1, 1), none, false, None),
+        (R4G4_UNORM_PACK8, 1, (1, 1), color, false, Some(Unorm)),
+        (R4G4B4A4_UNORM_PACK16, 2, (1, 1), color, false, Some(Unorm)),
+        (B4G4R4A4_UNORM_PACK16, 2, (1, 1), color, false, Some(Unorm)),
+        (R5G6B5_UNORM_PACK16, 2, (1, 1), color, false, Some(Unorm)),
+        (B5G6R5_UNORM_PACK16, 2, (1, 1), color, false, Some(Unorm)),
+        (R5G5B5A1_UNORM_PACK16, 2, (1, 1), color, false, Some(Unorm)),
+        (B5G5R5A1_UNORM_PACK16, 2, (1, 1), color, false, Some(Unorm)),
+        (A1R5G5B5_UNORM_PACK16, 2, (1, 1), color, false, Some(Unorm)),
+        (R8_UNORM, 1, (1, 1), color, false, Some(Unorm)),
+        (R8_SNORM, 1, (1, 1), color, false, Some(Snorm)),
+        (R8_USCALED, 1, (1, 1), color, false, Some(Uscaled)),
+        (R8_SSCALED, 1, (1, 1), color, false, Some(Sscaled)),
+        (R8_UINT, 1, (1, 1), color, false, Some(Uint)),
+        (R8_SINT, 1, (1, 1), color, false, Some(Sint)),
+        (R8_SRGB, 1, (1, 1), color, false, Some(Srgb)),
+        (R8G8_UNORM, 2, (1, 1), color, false, Some(Unorm)),
+        (R8G8_SNORM, 2, (1, 1), color, false, Some(Snorm)),
+        (R8G8_USCALED, 2, (1, 1), color, false, Some(Uscaled)),
+        (R8G8_SSCALED, 2, (1, 1), color, false, Some(Sscaled)),
+        (R8G8_UINT, 2, (1, 1), color, false, Some(Uint)),
+        (R8G8_SINT, 2, (1, 1), color, false, Some(Sint)),
+        (R8G8_SRGB, 2, (1, 1), color, false, Some(Srgb)),
+        (R8G8B8_UNORM, 3, (1, 1), color, false, Some(Unorm)),
+        (R8G8B8_SNORM, 3, (1, 1), color, false, Some(Snorm)),
+        (R8G8B8_USCALED, 3, (1, 1), color, false, Some(Uscaled)),
+        (R8G8B8_SSCALED, 3, (1, 1), color, false, Some(Sscaled)),
+        (R8G8B8_UINT, 3, (1, 1), color, false, Some(Uint)),
+        (R8G8B8_SINT, 3, (1, 1), color, false, Some(Sint)),
+        (R8G8B8_SRGB, 3, (1, 1), color, false, Some(Srgb)),
+        (B8G8R8_UNORM, 3, (1, 1), color, false, Some(Unorm)),
+        (B8G8R8_SNORM, 3, (1, 1), color, false, Some(Snorm)),
+        (B8G8R8_USCALED, 3, (1, 1), color, false, Some(Uscaled)),
+        (B8G8R8_SSCALED, 3, (1, 1), color, false, Some(Sscaled)),
+        (B8G8R8_UINT, 3, (1, 1), color, false, Some(Uint)),
+        (B8G8R8_SINT, 3, (1, 1), color, false, Some(Sint)),
+        (B8G8R8_SRGB, 3, (1, 1), color, false, Some(Srgb)),
+        (R8G8B8A8_UNORM, 4, (1, 1), color, false, Some(Unorm)),
+        (R8G8B8A8_SNORM, 4, (1, 1), color, false, Some(Snorm)),
+        (R8G8B8A8_USCALED, 4, (1, 1), color, false, Some(Uscaled)),
+        (R8G8B8A8_SSCALED, 4, (1, 1), color, false, Some(Sscaled)),
+        (R8G8B8A8_UINT, 4, (1, 1), color, false, Some(Uint)),
+        (R8G8B8A8_SINT, 4, (1, 1), color, false, Some(Sint)),
+        (R8G8B8A8_SRGB, 4, (1, 1), color, false, Some(Srgb)),
+        (B8G8R8A8_UNORM, 4, (1, 1), color, false, Some(Unorm)),
+        (B8G8R8A8_SNORM, 4, (1, 1), color, false, Some(Snorm)),
+        (B8G8R8A8_USCALED, 4, (1, 1), color, false, Some(Uscaled)),
+        (B8G8R8A8_SSCALED, 4, (1, 1), color, false, Some(Sscaled)),
+        (B8G8R8A8_UINT, 4, (1, 1), color, false, Some(Uint)),
+        (B8G8R8A8_SINT, 4, (1, 1), color, false, Some(Sint)),
+        (B8G8R8A8_SRGB, 4, (1, 1), color, false, Some(Srgb)),
+        (A8B8G8R8_UNORM_PACK32, 4, (1, 1), color, false, Some(Unorm)),
+        (A8B8G8R8_SNORM_PACK32, 4, (1, 1), color, false, Some(Snorm)),
+        (A8B8G8R8_USCALED_PACK32, 4, (1, 1), color, false, Some(Uscaled)),
+        (A8B8G8R8_SSCALED_PACK32, 4, (1, 1), color, false, Some(Sscaled)),
+        (A8B8G8R8_UINT_PACK32, 4, (1, 1), color, false, Some(Uint)),
+        (A8B8G8R8_SINT_PACK32, 4, (1, 1), color, false, Some(Sint)),
+        (A8B8G8R8_SRGB_PACK32, 4, (1, 1), color, false, Some(Srgb)),
+        (A2R10G10B10_UNORM_PACK32, 4, (1, 1), color, false, Some(Unorm)),
+        (A2R10G10B10_SNORM_PACK32, 4, (1, 1), color, false, Some(Snorm)),
+        (A2R10G10B10_USCALED_PACK32, 4, (1, 1), color, false, Some(Uscaled)),
+        (A2R10G10B10_SSCALED_PACK32, 4, (1, 1), color, false, Some(Sscaled)),
+        (A2R10G10B10_UINT_PACK32, 4, (1, 1), color, false, Some(Uint)),
+        (A2R10G10B10_SINT_PACK32, 4, (1, 1), color, false, Some(Sint)),
+        (A2B10G10R10_UNORM_PACK32, 4, (1, 1), color, false, Some(Unorm)),
+        (A2B10G10R10_SNORM_PACK32, 4, (1, 1), color, false, Some(Snorm)),
+        (A2B10G10R10_USCALED_PACK32, 4, (1, 1), color, false, Some(Uscaled)),
+        (A2B10G10R10_SSCALED_PACK32, 4, (1, 1), color, false, Some(Sscaled)),
+        (A2B10G10R10_UINT_PACK32, 4, (1, 1), color, false, Some(Uint)),
+        (A2B10G10R10_SINT_PACK32, 4, (1, 1), color, false, Some(Sint)),
+        (R16_UNORM, 2, (1, 1), color, false, Some(Unorm)),
+        (R16_SNORM, 2, (1, 1), color, false, Some(Snorm)),
+        (R16_USCALED, 2, (1, 1), color, false, Some(Uscaled)),
+        (R16_SSCALED, 2, (1, 1), color, false, Some(Sscaled)),
+        (R16_UINT, 2, (1, 1), color, false, Some(Uint)),
+        (R16_SINT, 2, (1, 1), color, false, Some(Sint)),
+        (R16_SFLOAT, 2, (1, 1), color, false, Some(Sfloat)),
+        (R16G16_UNORM, 4, (1, 1), color, false, Some(Unorm)),
+        (R16G16_SNORM, 4, (1, 1), color, false, Some(Snorm)),
+        (R16G16_USCALED, 4, (1, 1), color, false, Some(Uscaled)),
+        (R16G16_SSCALED, 4, (1, 1), color, false, Some(Sscaled)),
+        (R16G16_UINT, 4, (1, 1), color, false, Some(Uint)),
+        (R16G16_SINT, 4, (1, 1), color, false, Some(Sint)),
+        (R16G16_SFLOAT, 4, (1, 1), color, false, Some(Sfloat)),
+        (R16G16B16_UNORM, 6, (1, 1), color, false, Some(Unorm)),
+        (R16G16B16_SNORM, 6, (1, 1), color, false, Some(Snorm)),
+        (R16G16B16_USCALED, 6, (1, 1), color, false, Some(Uscaled)),
+        (R16G16B16_SSCALED, 6, (1, 1), color, false, Some(Sscaled)),
+        (R16G16B16_UINT, 6, (1, 1), color, false, Some(Uint)),
+        (R16G16B16_SINT, 6, (1, 1), color, false, Some(Sint)),
+        (R16G16B16_SFLOAT, 6, (1, 1), color, false, Some(Sfloat)),
+        (R16G16B16A16_UNORM, 8, (1, 1), color, false, Some(Unorm)),
+        (R16G16B16A16_SNORM, 8, (1, 1), color, false, Some(Snorm)),
+        (R16G16B16A16_USCALED, 8, (1, 1), color, false, Some(Uscaled)),
+        (R16G16B16A16_SSCALED, 8, (1, 1), color, false, Some(Sscaled)),
+        (R16G16B16A16_UINT, 8, (1, 1), color, false, Some(Uint)),
+        (R16G16B16A16_SINT, 8, (1, 1), color, false, Some(Sint)),
+        (R16G16B16A16_SFLOAT, 8, (1, 1), color, false, Some(Sfloat)),
+        (R32_UINT, 4, (1, 1), color, false, Some(Uint)),
+        (R32_SINT, 4, (1, 1), color, false, Some(Sint)),
+        (R32_SFLOAT, 4, (1, 1), color, false, Some(Sfloat)),
+        (R32G32_UINT, 8, (1, 1), color, false, Some(Uint)),
+        (R32G32_SINT, 8, (1, 1), color, false, Some(Sint)),
+        (R32G32_SFLOAT, 8, (1, 1), color, false, Some(Sfloat)),
+        (R32G32B32_UINT, 12, (1, 1), color, false, Some(Uint)),
+        (R32G32B32_SINT, 12, (1, 1), color, false, Some(Sint)),
+        (R32G32B32_SFLOAT, 12, (1, 1), color, false, Some(Sfloat)),
+        (R32G32B32A32_UINT, 16, (1, 1), color, false, Some(Uint)),
+        (R32G32B32A32_SINT, 16, (1, 1), color, false, Some(Sint)),
+        (R32G32B32A32_SFLOAT, 16, (1, 1), color, false, Some(Sfloat)),
+        (R64_UINT, 8, (1, 1), color, false, Some(Uint)),
+        (R64_SINT, 8, (1, 1), color, false, Some(Sint)),
+        (R64_SFLOAT, 8, (1, 1), color, false, Some(Sfloat)),
+        (R64G64_UINT, 16, (1, 1), color, false, Some(Uint)),
+        (R64G64_SINT, 16, (1, 1), color, false, Some(Sint)),
+        (R64G64_SFLOAT, 16, (1, 1), color, false, Some(Sfloat)),
+        (R64G64B64_UINT, 24, (1, 1), color, false, Some(Uint)),
+        (R64G64B64_SINT, 24, (1, 1), color, false, Some(Sint)),
+        (R64G64B64_SFLOAT, 24, (1, 1), color, false, Some(Sfloat)),
+        (R64G64B64A64_UINT, 32, (1, 1), color, false, Some(Uint)),
+        (R64G64B64A64_SINT, 32, (1, 1), color, false, Some(Sint)),
+        (R64G64B64A64_SFLOAT, 32, (1, 1), color, false, Some(Sfloat)),
+        (B10G11R11_UFLOAT_PACK32, 4, (1, 1), color, false, Some(Ufloat)),
+        (E5B9G9R9_UFLOAT_PACK32, 4, (1, 1), color, false, Some(Ufloat)),
+        (D16_UNORM, 2, (1, 1), depth, false, Some(Unorm)),
+        (X8_D24_UNORM_PACK32, 4, (1, 1), depth, false, Some(Unorm)),
+        (D32_SFLOAT, 4, (1, 1), depth, false, Some(Sfloat)),
+        (S8_UINT, 1, (1, 1), stencil, false, Some(Uint)),
+        (D16_UNORM_S8_UINT, 3, (1, 1), depth_stencil, false, Some(Unorm)),
+        (D24_UNORM_S8_UINT, 4, (1, 1), depth_stencil, false, Some(Unorm)),
+        (D32_SFLOAT_S8_UINT, 5, (1, 1), depth_stencil, false, Some(Sfloat)),
+        (BC1_RGB_UNORM_BLOCK, 8, (4, 4), color, true, Some(Unorm)),
+        (BC1_RGB_SRGB_BLOCK, 8, (4, 4), color, true, Some(Srgb)),
+        (BC1_RGBA_UNORM_BLOCK, 8, (4, 4), color, true, Some(Unorm)),
+        (BC1_RGBA_SRGB_BLOCK, 8, (4, 4), color, true, Some(Srgb)),
+        (BC2_UNORM_BLOCK, 16, (4, 4), color, true, Some(Unorm)),
+        (BC2_SRGB_BLOCK, 16, (4, 4), color, true, Some(Srgb)),
+        (BC3_UNORM_BLOCK, 16, (4, 4), color, true, Some(Unorm)),
+        (BC3_SRGB_BLOCK, 16, (4, 4), color, true, Some(Srgb)),
+        (BC4_UNORM_BLOCK, 8, (4, 4), color, true, Some(Unorm)),
+        (BC4_SNORM_BLOCK, 8, (4, 4), color, true, Some(Snorm)),
+        (BC5_UNORM_BLOCK, 16, (4, 4), color, true, Some(Unorm)),
+        (BC5_SNORM_BLOCK, 16, (4, 4), color, true, Some(Snorm)),
+        (BC6H_UFLOAT_BLOCK, 16, (4, 4), color, true, Some(Ufloat)),
+        (BC6H_SFLOAT_BLOCK, 16, (4, 4), color, true, Some(Sfloat)),
+        (BC7_UNORM_BLOCK, 16, (4, 4), color, true, Some(Unorm)),
+        (BC7_SRGB_BLOCK, 16, (4, 4), color, true, Some(Srgb)),
+        (ETC2_R8G8B8_UNORM_BLOCK, 8, (4, 4), color, true, Some(Unorm)),
+        (ETC2_R8G8B8_SRGB_BLOCK, 8, (4, 4), color, true, Some(Srgb)),
+        (ETC2_R8G8B8A1_UNORM_BLOCK, 8, (4, 4), color, true, Some(Unorm)),
+        (ETC2_R8G8B8A1_SRGB_BLOCK, 8, (4, 4), color, true, Some(Srgb)),
+        (ETC2_R8G8B8A8_UNORM_BLOCK, 16, (4, 4), color, true, Some(Unorm)),
+        (ETC2_R8G8B8A8_SRGB_BLOCK, 16, (4, 4), color, true, Some(Srgb)),
+        (EAC_R11_UNORM_BLOCK, 8, (4, 4), color, true, Some(Unorm)),
+        (EAC_R11_SNORM_BLOCK, 8, (4, 4), color, true, Some(Snorm)),
+        (EAC_R11G11_UNORM_BLOCK, 16, (4, 4), color, true, Some(Unorm)),
+        (EAC_R11G11_SNORM_BLOCK, 16, (4, 4), color, true, Some(Snorm)),
+        (ASTC_4x4_UNORM_BLOCK, 16, (4, 4), color, true, Some(Unorm)),
+        (ASTC_4x4_SRGB_BLOCK, 16, (4, 4), color, true, Some(Srgb)),
+        (ASTC_5x4_UNORM_BLOCK, 16, (5, 4), color, true, Some(Unorm)),
+        (ASTC_5x4_SRGB_BLOCK, 16, (5, 4), color, true, Some(Srgb)),
+        (ASTC_5x5_UNORM_BLOCK, 16, (5, 5), color, true, Some(Unorm)),
+        (ASTC_5x5_SRGB_BLOCK, 16, (5, 5), color, true, Some(Srgb)),
+        (ASTC_6x5_UNORM_BLOCK, 16, (6, 5), color, true, Some(Unorm)),
+        (ASTC_6x5_SRGB_BLOCK, 16, (6, 5), color, true, Some(Srgb)),
+        (ASTC_6x6_UNORM_BLOCK, 16, (6, 6), color, true, Some(Unorm)),
+        (ASTC_6x6_SRGB_BLOCK, 16, (6, 6), color, true, Some(Srgb)),
+        (ASTC_8x5_UNORM_BLOCK, 16, (8, 5), color, true, Some(Unorm)),
+        (ASTC_8x5_SRGB_BLOCK, 16, (8, 5), color, true, Some(Srgb)),
+        (ASTC_8x6_UNORM_BLOCK, 16, (8, 6), color, true, Some(Unorm)),
+        (ASTC_8x6_SRGB_BLOCK, 16, (8, 6), color, true, Some(Srgb)),
+        (ASTC_8x8_UNORM_BLOCK, 16, (8, 8), color, true, Some(Unorm)),
+        (ASTC_8x8_SRGB_BLOCK, 16, (8, 8), color, true, Some(Srgb)),
+        (ASTC_10x5_UNORM_BLOCK, 16, (10, 5), color, true, Some(Unorm)),
+        (ASTC_10x5_SRGB_BLOCK, 16, (10, 5), color, true, Some(Srgb)),
+        (ASTC_10x6_UNORM_BLOCK, 16, (10, 6), color, true, Some(Unorm)),
+        (ASTC_10x6_SRGB_BLOCK, 16, (10, 6), color, true, Some(Srgb)),
+        (ASTC_10x8_UNORM_BLOCK, 16, (10, 8), color, true, Some(Unorm)),
+        (ASTC_10x8_SRGB_BLOCK, 16, (10, 8), color, true, Some(Srgb)),
+        (ASTC_10x10_UNORM_BLOCK, 16, (10, 10), color, true, Some(Unorm)),
+        (ASTC_10x10_SRGB_BLOCK, 16, (10, 10), color, true, Some(Srgb)),
+        (ASTC_12x10_UNORM_BLOCK, 16, (12, 10), color, true, Some(Unorm)),
+        (ASTC_12x10_SRGB_BLOCK, 16, (12, 10), color, true, Some(Srgb)),
+        (ASTC_12x12_UNORM_BLOCK, 16, (12, 12), color, true, Some(Unorm)),
+        (ASTC_12x12_SRGB_BLOCK, 16, (12, 12), color, true, Some(Srgb)),
+        (G8B8G8R8_422_UNORM, 4, (2, 1), color, false, Some(Unorm)),
+        (B8G8R8G8_422_UNORM, 4, (2, 1), color, false, Some(Unorm)),
+        (G8_B8_R8_3PLANE_420_UNORM, 3, (1, 1), color, false, Some(Unorm)),
+        (G8_B8R8_2PLANE_420_UNORM, 3, (1, 1), color, false, Some(Unorm)),
+        (G8_B8_R8_3PLANE_422_UNORM, 3, (1, 1), color, false, Some(Unorm)),
+        (G8_B8R8_2PLANE_422_UNORM, 3, (1, 1), color, false, Some(Unorm)),
+        (G8_B8_R8_3PLANE_444_UNORM, 3, (1, 1), color, false, Some(Unorm)),
+        (R10X6_UNORM_PACK16, 2, (1, 1), color, false, Some(Unorm)),
+        (R10X6G10X6_UNORM_2PACK16, 4, (1, 1), color, false, Some(Unorm)),
+        (R10X6G10X6B10X6A10X6_UNORM_4PACK16, 8, (1, 1), color, false, Some(Unorm)),
+        (G10X6B10X6G10X6R10X6_422_UNORM_4PACK16, 8, (2, 1), color, false, Some(Unorm)),
+        (B10X6G10X6R10X6G10X6_422_UNORM_4PACK16, 8, (2, 1), color, false, Some(Unorm)),
+        (G10X6_B10X6_R10X6_3PLANE_420_UNORM_3PACK16, 6, (1, 1), color, false, Some(Unorm)),
+        (G10X6_B10X6R10X6_2PLANE_420_UNORM_3PACK16, 6, (1, 1), color, false, Some(Unorm)),
+        (G10X6_B10X6_R10X6_3PLANE_422_UNORM_3PACK16, 6, (1, 1), color, false, Some(Unorm)),
+        (G10X6_B10X6R10X6_2PLANE_422_UNORM_3PACK16, 6, (1, 1), color, false, Some(Unorm)),
+        (G10X6_B10X6_R10X6_3PLANE_444_UNORM_3PACK16, 6, (1, 1), color, false, Some(Unorm)),
+        (R12X4_UNORM_PACK16, 2, (1, 1), color, false, Some(Unorm)),
+        (R12X4G12X4_UNORM_2PACK16, 4, (1, 1), color, false, Some(Unorm)),
+        (R12X4G12X4B12X4A12X4_UNORM_4PACK16, 8, (1, 1), color, false, Some(Unorm)),
+        (G12X4B12X4G12X4R12X4_422_UNORM_4PACK16, 8, (2, 1), color, false, Some(Unorm)),
+        (B12X4G12X4R12X4G12X4_422_UNORM_4PACK16, 8, (2, 1), color, false, Some(Unorm)),
+        (G12X4_B12X4_R12X4_3PLANE_420_UNORM_3PACK16, 6, (1, 1), color, false, Some(Unorm)),
+        (G12X4_B12X4R12X4_2PLANE_420_UNORM_3PACK16, 6, (1, 1), color, false, Some(Unorm)),
+        (G12X4_B12X4_R12X4_3PLANE_422_UNORM_3PACK16, 6, (1, 1), color, false, Some(Unorm)),
+        (G12X4_B12X4R12X4_2PLANE_422_UNORM_3PACK16, 6, (1, 1), color, false, Some(Unorm)),
+        (G12X4_B12X4_R12X4_3PLANE_444_UNORM_3PACK16, 6, (1, 1), color, false, Some(Unorm)),
+        (G16B16G16R16_422_UNORM, 8, (2, 1), color, false, Some(Unorm)),
+        (B16G16R16G16_422_UNORM, 8, (2, 1), color, false, Some(Unorm)),
+        (G16_B16_R16_3PLANE_420_UNORM, 6, (1, 1), color, false, Some(Unorm)),
+        (G16_B16R16_2PLANE_420_UNORM, 6, (1, 1), color, false, Some(Unorm)),
+        (G16_B16_R16_3PLANE_422_UNORM, 6, (1, 1), color, false, Some(Unorm)),
+        (G16_B16R16_2PLANE_422_UNORM, 6, (1, 1), color, false, Some(Unorm)),
+        (G16_B16_R16_3PLANE_444_UNORM, 6, (1, 1), color, false, Some(Unorm)),
+        (PVRTC1_2BPP_UNORM_BLOCK_IMG, 8, (8, 4), color, true, Some(Unorm)),
+        (PVRTC1_4BPP_UNORM_BLOCK_IMG, 8, (4, 4), color, true, Some(Unorm)),
+        (PVRTC2_2BPP_UNORM_BLOCK_IMG, 8, (8, 4), color, true, Some(Unorm)),
+        (PVRTC2_4BPP_UNORM_BLOCK_IMG, 8, (4, 4), color, true, Some(Unorm)),
+        (PVRTC1_2BPP_SRGB_BLOCK_IMG, 8, (8, 4), color, true, Some(Srgb)),
+        (PVRTC1_4BPP_SRGB_BLOCK_IMG, 8, (4, 4), color, true, Some(Srgb)),
+        (PVRTC2_2BPP_SRGB_BLOCK_IMG, 8, (8, 4), color, true, Some(Srgb)),
+        (PVRTC2_4BPP_SRGB_BLOCK_IMG, 8, (4, 4), color, true, Some(Srgb)),
+    ]
+}
+
+/// Number of variants in `Format`.
+const FORMAT_COUNT: usize = 227;
+
+/// Number of formats with sRGB encoding.
+const SRGB_COUNT: usize = 33;
+
+#[test]
+fn format_table_test() {
+    let table = table();
+    assert_eq!(table.len(), FORMAT_COUNT);
+    for (index, row) in table.iter().enumerate() {
+        assert!(
+            table[..index].iter().all(|other| other.0 != row.0),
+            "{:?} is listed twice",
+            row.0
+        );
+    }
+
+    for &(format, size, (width, height), aspects, compressed, channel) in &table {
+        assert_eq!(format.block_size(), size, "{:?}", format);
+        let extent = format.block_extent();
+        assert_eq!(
+            (extent.width, extent.height, extent.depth),
+            (width, height, 1),
+            "{:?}",
+            format
+        );
+        assert_eq!(format.aspects(), aspects, "{:?}", format);
+        assert_eq!(format.is_compressed(), compressed, "{:?}", format);
+        assert_eq!(format.channel_type(), channel, "{:?}", format);
+        assert_eq!(
+            format.is_srgb(),
+            channel == Some(ChannelType::Srgb),
+            "{:?}",
+            format
+        );
+    }
+}
+
+#[test]
+fn srgb_counterpart_test() {
+    let mut srgb = 0;
+    for &(format, ..) in &table() {
+        if format.is_srgb() {
+            srgb += 1;
+            // Every sRGB format has linear counterpart.
+            let linear = format.srgb_counterpart().unwrap();
+            assert_eq!(linear.channel_type(), Some(ChannelType::Unorm));
+        }
+
+        if let Some(counterpart) = format.srgb_counterpart() {
+            assert_ne!(format.is_srgb(), counterpart.is_srgb(), "{:?}", format);
+            assert_eq!(counterpart.srgb_counterpart(), Some(format));
+            assert_eq!(counterpart.block_size(), format.block_size());
+            assert_eq!(counterpart.block_extent(), format.block_extent());
+        }
+    }
+    assert_eq!(srgb, SRGB_COUNT);
+
+    assert_eq!(R8G8B8A8_UNORM.srgb_counterpart(), Some(R8G8B8A8_SRGB));
+    assert_eq!(BC7_SRGB_BLOCK.srgb_counterpart(), Some(BC7_UNORM_BLOCK));
+    assert_eq!(R16G16B16A16_UNORM.srgb_counterpart(), None);
+    assert_eq!(R8G8B8A8_SNORM.srgb_counterpart(), None);
+    assert_eq!(UNDEFINED.srgb_counterpart(), None);
+}
@@ -0,0 +1,33 @@
+mod device;
+mod format;
+mod resources;
+mod sharing;
+mod sparse;
+mod subresource;
+mod validation;
+
+use memory::{allocator::BuddyConfig, mock::MockMemory, Config, Heaps, Properties};
+
+/// Create `Heaps` with single device-local memory type
+/// served by buddy allocator with `min_block_size`.
+fn init(min_block_size: u64) -> Heaps<MockMemory> {
+    unsafe {
+        Heaps::new(
+            vec![(
+                Properties::DEVICE_LOCAL,
+                0,
+                Config {
+                    arena: None,
+                    dynamic: None,
+                    buddy: Some(BuddyConfig {
+                        chunk_size: 1024 * 1024,
+                        min_block_size,
+                    }),
+                    ring: None,
+                },
+            )],
+            vec![16 * 1024 * 1024],
+            1,
+        )
+    }
+}
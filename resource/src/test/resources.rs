@@ -0,0 +1,579 @@
+use memory::{
+    allocator::BuddyConfig,
+    mock::{Fault, MockMemory, Operation},
+    usage::{Data, UsageValue},
+    Config, Dedicated, Heaps, Properties, Tag,
+};
+
+use buffer;
+use image;
+use sampler;
+use test::{
+    device::{Object, TestDevice},
+    init,
+};
+use validation::DeviceLimits;
+use FrameIndex;
+use ResourceError;
+use Resources;
+use SamplerCreationError;
+use SharingMode;
+use TransientInfo;
+use TransientRequest;
+
+type TestResources = Resources<MockMemory, u64, u64, u64, u64, u64>;
+
+fn frame(index: u64) -> FrameIndex {
+    unsafe { FrameIndex::new(index) }
+}
+
+fn buffer_info() -> buffer::CreateInfo {
+    buffer::CreateInfo {
+        size: 1024,
+        usage: buffer::UsageFlags::UNIFORM_TEXEL_BUFFER,
+        sharing: SharingMode::Exclusive,
+    }
+}
+
+fn image_info() -> image::CreateInfo {
+    image::CreateInfo {
+        kind: image::Kind::D2,
+        format: image::Format::R8G8B8A8_UNORM,
+        extent: image::Extent3D {
+            width: 16,
+            height: 16,
+            depth: 1,
+        },
+        mips: 1,
+        array: 1,
+        samples: image::SampleCountFlags::SAMPLE_COUNT_1,
+        tiling: image::ImageTiling::Optimal,
+        usage: image::UsageFlags::SAMPLED,
+        sharing: SharingMode::Exclusive,
+        flags: image::ImageCreateFlags::empty(),
+    }
+}
+
+fn image_view_info() -> image::ViewCreateInfo {
+    image::ViewCreateInfo {
+        kind: image::ViewKind::D2,
+        format: image::Format::R8G8B8A8_UNORM,
+        swizzle: image::Swizzle::IDENTITY,
+        range: image::SubresourceRange {
+            aspects: image::ImageAspectFlags::COLOR,
+            levels: 0..1,
+            layers: 0..1,
+        },
+    }
+}
+
+#[test]
+fn views_test() {
+    let mut heaps = init(256);
+    let ref device = TestDevice::new();
+    let mut resources = TestResources::new();
+
+    let buffer = resources
+        .create_buffer(device, &mut heaps, buffer_info(), 1, Data)
+        .unwrap();
+    let buffer_view = resources
+        .create_buffer_view(
+            device,
+            &buffer,
+            buffer::ViewCreateInfo {
+                format: image::Format::R32_SFLOAT,
+                range: 0..512,
+            },
+        ).unwrap();
+    let image = resources
+        .create_image(device, &mut heaps, image_info(), 1, Data)
+        .unwrap();
+    let image_view = resources
+        .create_image_view(device, &image, image_view_info())
+        .unwrap();
+    let sampler = resources
+        .create_sampler(
+            device,
+            sampler::CreateInfo::new(sampler::Filter::Linear, sampler::AddressMode::Repeat),
+        ).unwrap();
+    assert_eq!(image_view.info().range.levels, 0..1);
+
+    // Sampler the device doesn't support is reported as error.
+    let mut unnormalized =
+        sampler::CreateInfo::new(sampler::Filter::Nearest, sampler::AddressMode::ClampToEdge);
+    unnormalized.unnormalized = true;
+    match resources.create_sampler(device, unnormalized) {
+        Err(ResourceError::SamplerCreationError(SamplerCreationError::Unsupported)) => {}
+        result => panic!("Unexpected result {:?}", result.map(|_| ())),
+    }
+    assert_eq!(device.live(Object::Sampler), 1);
+
+    // Resources are kept alive while their views are alive.
+    drop(buffer);
+    drop(image);
+    unsafe {
+        resources.cleanup(device, &mut heaps);
+    }
+    assert_eq!(device.live(Object::Buffer), 1);
+    assert_eq!(device.live(Object::Image), 1);
+    assert!(device.destroyed().is_empty());
+
+    drop(image_view);
+    unsafe {
+        resources.cleanup(device, &mut heaps);
+    }
+    assert_eq!(device.live(Object::Image), 0);
+    assert_eq!(device.live(Object::Buffer), 1);
+    let destroyed = device.destroyed();
+    assert_eq!(destroyed.len(), 2);
+    assert_eq!(destroyed[0].0, Object::ImageView);
+    assert_eq!(destroyed[1].0, Object::Image);
+
+    unsafe {
+        TestResources::destroy_buffer_view(buffer_view, device);
+        TestResources::destroy_sampler(sampler, device);
+        resources.cleanup(device, &mut heaps);
+    }
+    assert_eq!(device.live(Object::Buffer), 0);
+    assert_eq!(device.live(Object::Sampler), 0);
+    assert_eq!(device.destroyed().len(), 5);
+
+    heaps.dispose(device);
+    assert_eq!(device.memory().allocated(), 0);
+}
+
+#[test]
+fn deferred_destruction_test() {
+    let mut heaps = init(256);
+    let ref device = TestDevice::new();
+    let mut resources = TestResources::new();
+
+    let unused = resources
+        .create_buffer(device, &mut heaps, buffer_info(), 1, Data)
+        .unwrap();
+    let buffer = resources
+        .create_buffer(device, &mut heaps, buffer_info(), 1, Data)
+        .unwrap();
+    let image = resources
+        .create_image(device, &mut heaps, image_info(), 1, Data)
+        .unwrap();
+
+    buffer.mark_used(frame(1));
+    buffer.mark_used(frame(3));
+    // Earlier frame doesn't override the last use.
+    buffer.mark_used(frame(2));
+    assert_eq!(buffer.last_use(), Some(frame(3)));
+    image.mark_used(frame(2));
+    assert_eq!(unused.last_use(), None);
+
+    drop(unused);
+    drop(buffer);
+    drop(image);
+
+    // Resource that was never used is destroyed right away.
+    unsafe {
+        resources.cleanup(device, &mut heaps);
+    }
+    assert_eq!(device.live(Object::Buffer), 1);
+    assert_eq!(device.live(Object::Image), 1);
+
+    unsafe {
+        resources.complete(frame(1));
+        resources.cleanup(device, &mut heaps);
+    }
+    assert_eq!(device.live(Object::Buffer), 1);
+    assert_eq!(device.live(Object::Image), 1);
+
+    unsafe {
+        resources.complete(frame(2));
+        resources.cleanup(device, &mut heaps);
+    }
+    assert_eq!(device.live(Object::Buffer), 1);
+    assert_eq!(device.live(Object::Image), 0);
+
+    // Completing earlier frame again changes nothing.
+    unsafe {
+        resources.complete(frame(1));
+        resources.cleanup(device, &mut heaps);
+    }
+    assert_eq!(resources.last_complete(), Some(frame(2)));
+    assert_eq!(device.live(Object::Buffer), 1);
+
+    unsafe {
+        resources.complete(frame(3));
+        resources.cleanup(device, &mut heaps);
+    }
+    assert_eq!(device.live(Object::Buffer), 0);
+    assert_eq!(device.destroyed().len(), 3);
+
+    heaps.dispose(device);
+    assert_eq!(device.memory().allocated(), 0);
+}
+
+#[test]
+fn transient_image_test() {
+    let buddy = Config {
+        arena: None,
+        dynamic: None,
+        buddy: Some(BuddyConfig {
+            chunk_size: 1024 * 1024,
+            min_block_size: 256,
+        }),
+        ring: None,
+    };
+    let lazy = Config {
+        arena: None,
+        dynamic: None,
+        buddy: None,
+        ring: None,
+    };
+    let mut heaps = unsafe {
+        Heaps::new(
+            vec![
+                (Properties::DEVICE_LOCAL, 0, buddy),
+                (
+                    Properties::DEVICE_LOCAL | Properties::LAZILY_ALLOCATED,
+                    0,
+                    lazy,
+                ),
+            ],
+            vec![16 * 1024 * 1024],
+            1,
+        )
+    };
+    let ref device = TestDevice::new();
+    let mut resources = TestResources::new();
+
+    let mut info = image_info();
+    info.usage = image::UsageFlags::COLOR_ATTACHMENT;
+    let image = resources
+        .create_image(device, &mut heaps, info, 1, Data)
+        .unwrap();
+    let utilization = heaps.utilization();
+    assert_eq!(utilization.types[0].blocks.buddy, 1);
+    assert_eq!(utilization.types[1].blocks.total(), 0);
+
+    // Transient attachment gets lazily allocated memory even though `Data` is requested.
+    info.usage |= image::UsageFlags::TRANSIENT_ATTACHMENT;
+    let transient = resources
+        .create_image(device, &mut heaps, info, 1, Data)
+        .unwrap();
+    let utilization = heaps.utilization();
+    assert_eq!(utilization.types[0].blocks.total(), 1);
+    assert_eq!(utilization.types[1].blocks.dedicated, 1);
+
+    drop(image);
+    drop(transient);
+    unsafe {
+        resources.cleanup(device, &mut heaps);
+    }
+    heaps.dispose(device);
+    assert_eq!(device.memory().allocated(), 0);
+}
+
+#[test]
+fn dedicated_test() {
+    let mut heaps = init(256);
+    let ref device = TestDevice::new();
+    let mut resources = TestResources::new();
+
+    let shared = resources
+        .create_buffer(device, &mut heaps, buffer_info(), 1, Data)
+        .unwrap();
+    assert_eq!(shared.inner.block.dedicated(), None);
+
+    device.set_dedicated(true, false);
+    let preferred = resources
+        .create_buffer(device, &mut heaps, buffer_info(), 1, Data)
+        .unwrap();
+
+    device.set_dedicated(false, true);
+    let required = resources
+        .create_image(device, &mut heaps, image_info(), 1, Data)
+        .unwrap();
+
+    let utilization = heaps.utilization();
+    assert_eq!(utilization.types[0].blocks.buddy, 1);
+    assert_eq!(utilization.types[0].blocks.dedicated, 2);
+
+    // Memory device receives handles of the resources.
+    let preferred_handle = Dedicated::Buffer(preferred.inner.raw);
+    let required_handle = Dedicated::Image(required.inner.raw);
+    assert_eq!(preferred.inner.block.dedicated(), Some(preferred_handle));
+    assert_eq!(required.inner.block.dedicated(), Some(required_handle));
+    let mut dedicated = device.memory().dedicated();
+    dedicated.sort_by_key(|dedicated| match *dedicated {
+        Dedicated::Buffer(raw) | Dedicated::Image(raw) => raw,
+    });
+    assert_eq!(dedicated, vec![preferred_handle, required_handle]);
+
+    drop(shared);
+    drop(preferred);
+    drop(required);
+    unsafe {
+        resources.cleanup(device, &mut heaps);
+    }
+    assert!(device.memory().dedicated().is_empty());
+    heaps.dispose(device);
+    assert_eq!(device.memory().allocated(), 0);
+}
+
+#[test]
+fn allocation_errors_test() {
+    let mut heaps = init(256);
+    let ref device = TestDevice::new();
+    let mut resources = TestResources::new();
+
+    // Resources are destroyed if memory can't be allocated.
+    device
+        .memory()
+        .fail_all(Operation::Allocate, Fault::OutOfDeviceMemory);
+    match resources.create_buffer(device, &mut heaps, buffer_info(), 1, Data) {
+        Err(ResourceError::MemoryError(_)) => {}
+        result => panic!("Unexpected result {:?}", result.map(|_| ())),
+    }
+    match resources.create_image(device, &mut heaps, image_info(), 1, Data) {
+        Err(ResourceError::MemoryError(_)) => {}
+        result => panic!("Unexpected result {:?}", result.map(|_| ())),
+    }
+    assert_eq!(device.live(Object::Buffer), 0);
+    assert_eq!(device.live(Object::Image), 0);
+    assert_eq!(device.memory().allocated(), 0);
+
+    // Dedicated allocation can't be made without resource handle.
+    let ref device = TestDevice::new();
+    device.set_dedicated(false, true);
+    device.hide_handles(true);
+    match resources.create_buffer(device, &mut heaps, buffer_info(), 1, Data) {
+        Err(ResourceError::UnknownDedicatedResource) => {}
+        result => panic!("Unexpected result {:?}", result.map(|_| ())),
+    }
+    match resources.create_image(device, &mut heaps, image_info(), 1, Data) {
+        Err(ResourceError::UnknownDedicatedResource) => {}
+        result => panic!("Unexpected result {:?}", result.map(|_| ())),
+    }
+    assert_eq!(device.live(Object::Buffer), 0);
+    assert_eq!(device.live(Object::Image), 0);
+    assert_eq!(device.memory().allocated(), 0);
+
+    // Handle isn't needed if dedicated allocation is only preferred.
+    device.set_dedicated(true, false);
+    let buffer = resources
+        .create_buffer(device, &mut heaps, buffer_info(), 1, Data)
+        .unwrap();
+    assert_eq!(buffer.inner.block.dedicated(), None);
+
+    drop(buffer);
+    unsafe {
+        resources.cleanup(device, &mut heaps);
+    }
+    heaps.dispose(device);
+    assert_eq!(device.memory().allocated(), 0);
+}
+
+#[test]
+fn aliased_errors_test() {
+    let mut heaps = init(256);
+    let mut resources = TestResources::new();
+    let requests = vec![
+        TransientRequest {
+            info: TransientInfo::Buffer(buffer_info()),
+            lifetime: 0..2,
+        },
+        TransientRequest {
+            info: TransientInfo::Image(image_info()),
+            lifetime: 1..3,
+        },
+    ];
+
+    // Image fails validation after buffer is created.
+    let limits = DeviceLimits {
+        max_image_dimension_1d: 4096,
+        max_image_dimension_2d: 4096,
+        max_image_dimension_3d: 256,
+        max_image_dimension_cube: 4096,
+        max_image_array_layers: 256,
+        max_texel_buffer_elements: 65536,
+        sample_counts: image::SampleCountFlags::SAMPLE_COUNT_1,
+        buffer_image_granularity: 1024,
+    };
+    let ref device = TestDevice::with_validation(limits, Vec::new());
+    match resources.create_aliased(device, &mut heaps, &requests, Data) {
+        Err(ResourceError::ValidationError(_)) => {}
+        result => panic!("Unexpected result {:?}", result.map(|_| ())),
+    }
+    assert_eq!(device.live(Object::Buffer), 0);
+    assert_eq!(device.memory().allocated(), 0);
+
+    // Second memory block fails to allocate.
+    let ref device = TestDevice::new();
+    device.set_dedicated(false, true);
+    device
+        .memory()
+        .fail_nth(Operation::Allocate, 2, Fault::OutOfDeviceMemory);
+    match resources.create_aliased(device, &mut heaps, &requests, Data) {
+        Err(ResourceError::MemoryError(_)) => {}
+        result => panic!("Unexpected result {:?}", result.map(|_| ())),
+    }
+    assert_eq!(device.live(Object::Buffer), 0);
+    assert_eq!(device.live(Object::Image), 0);
+    assert_eq!(device.memory().allocated(), 0);
+
+    let aliased = resources
+        .create_aliased(device, &mut heaps, &requests, Data)
+        .unwrap();
+    assert_eq!(aliased.len(), 2);
+    assert_eq!(aliased.blocks().len(), 2);
+    unsafe {
+        aliased.dispose(device, &mut heaps);
+    }
+    assert_eq!(device.live(Object::Buffer), 0);
+    assert_eq!(device.live(Object::Image), 0);
+
+    heaps.dispose(device);
+    assert_eq!(device.memory().allocated(), 0);
+}
+
+/// Accepts only vertex buffers.
+fn vertex_buffer_size<M, B>(buffer: &buffer::Buffer<M, B, buffer::VertexBuffer>) -> u64 {
+    buffer.info.size
+}
+
+#[test]
+fn typed_buffer_test() {
+    let mut heaps = init(256);
+    let ref device = TestDevice::new();
+    let mut resources = TestResources::new();
+
+    let vertices = resources
+        .create_typed_buffer(
+            device,
+            &mut heaps,
+            1024,
+            SharingMode::Exclusive,
+            1,
+            buffer::VertexBuffer,
+        ).unwrap();
+    let indirect = resources
+        .create_typed_buffer(
+            device,
+            &mut heaps,
+            512,
+            SharingMode::Exclusive,
+            1,
+            buffer::IndirectBuffer,
+        ).unwrap();
+
+    assert_eq!(vertex_buffer_size(&vertices), 1024);
+    assert_eq!(
+        vertices.info.usage,
+        buffer::UsageFlags::TRANSFER_DST | buffer::UsageFlags::VERTEX_BUFFER
+    );
+    assert_eq!(
+        indirect.info.usage,
+        buffer::UsageFlags::TRANSFER_DST | buffer::UsageFlags::INDIRECT_BUFFER
+    );
+
+    // Typed buffers can be converted to value-level usage.
+    let indirect = indirect.into_dynamic();
+    match *indirect.usage() {
+        (flags, UsageValue::Data) => assert!(flags.contains(buffer::UsageFlags::INDIRECT_BUFFER)),
+        usage => panic!("Unexpected usage {:?}", usage),
+    }
+
+    let dynamic = resources
+        .create_buffer(device, &mut heaps, buffer_info(), 1, Data)
+        .unwrap();
+    assert_eq!(dynamic.usage().0, buffer_info().usage);
+
+    unsafe {
+        TestResources::destroy_buffer(vertices, device, &mut heaps);
+        TestResources::destroy_buffer(indirect, device, &mut heaps);
+        TestResources::destroy_buffer(dynamic, device, &mut heaps);
+        resources.cleanup(device, &mut heaps);
+    }
+    assert_eq!(device.live(Object::Buffer), 0);
+
+    heaps.dispose(device);
+    assert_eq!(device.memory().allocated(), 0);
+}
+
+#[test]
+fn names_test() {
+    let mut heaps = init(256);
+    let ref device = TestDevice::new();
+    let mut resources = TestResources::new();
+
+    let mut buffer = resources
+        .create_buffer(device, &mut heaps, buffer_info(), 1, Data)
+        .unwrap();
+    let mut image = resources
+        .create_image(device, &mut heaps, image_info(), 1, Data)
+        .unwrap();
+    assert_eq!(buffer.name(), None);
+
+    buffer.set_name(device, &mut heaps, "vertices");
+    image.set_name(device, &mut heaps, "albedo");
+    assert_eq!(buffer.name(), Some("vertices"));
+    assert_eq!(image.name(), Some("albedo"));
+    assert_eq!(
+        device.name(Object::Buffer, buffer.inner.raw),
+        Some("vertices".to_owned())
+    );
+    assert_eq!(
+        device.name(Object::Image, image.inner.raw),
+        Some("albedo".to_owned())
+    );
+    assert!(format!("{:?}", buffer).contains("vertices"));
+    assert!(format!("{:?}", image).contains("albedo"));
+
+    let tags = |heaps: &Heaps<MockMemory>| {
+        heaps
+            .leaks()
+            .leaks
+            .into_iter()
+            .filter_map(|leak| leak.tag)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        tags(&heaps),
+        vec![
+            Tag::from(String::from("albedo")),
+            Tag::from(String::from("vertices")),
+        ]
+    );
+
+    // Renaming replaces the tag set by previous name but keeps other tags.
+    buffer.set_name(device, &mut heaps, "indices");
+    assert_eq!(
+        tags(&heaps),
+        vec![
+            Tag::from(String::from("albedo")),
+            Tag::from(String::from("indices")),
+        ]
+    );
+    heaps.set_tag(&mut buffer.inner.block, Some("geometry".into()));
+    buffer.set_name(device, &mut heaps, "positions");
+    assert_eq!(buffer.name(), Some("positions"));
+    assert_eq!(
+        tags(&heaps),
+        vec![Tag::from("geometry"), Tag::from(String::from("albedo"))]
+    );
+
+    // Image is still used by incomplete frame after cleanup.
+    image.mark_used(frame(1));
+    drop(image);
+    unsafe {
+        TestResources::destroy_buffer(buffer, device, &mut heaps);
+        resources.cleanup(device, &mut heaps);
+    }
+    assert_eq!(tags(&heaps), vec![Tag::from(String::from("albedo"))]);
+    assert!(heaps.leaks().to_string().contains("tagged \"albedo\""));
+
+    unsafe {
+        resources.complete(frame(1));
+        resources.cleanup(device, &mut heaps);
+    }
+    assert!(heaps.leaks().is_empty());
+    heaps.dispose(device);
+}
@@ -0,0 +1,22 @@
+use {Families, SharingMode};
+
+#[test]
+fn sharing_test() {
+    let families = vec![2, 0, 2].into_iter().collect::<Families>();
+    assert_eq!(families.len(), 2);
+    assert!(families.contains(0));
+    assert!(!families.contains(1));
+    assert!(!families.contains(40));
+    assert_eq!(families.iter().collect::<Vec<_>>(), vec![0, 2]);
+
+    assert!(!SharingMode::Exclusive.transfer_required(1, 1));
+    assert!(SharingMode::Exclusive.transfer_required(0, 2));
+
+    let concurrent = SharingMode::Concurrent(families);
+    assert!(!concurrent.transfer_required(0, 2));
+    assert!(!concurrent.transfer_required(2, 0));
+    assert!(!concurrent.transfer_required(1, 1));
+
+    // Family outside of the set still requires ownership transfer.
+    assert!(concurrent.transfer_required(0, 1));
+}
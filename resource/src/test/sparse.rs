@@ -0,0 +1,220 @@
+use memory::{
+    mock::{Fault, MockDevice, MockMemory, Operation},
+    usage::Data,
+    AllocationError, Block, MemoryError, OutOfMemoryError,
+};
+
+use image::{Extent3D, Offset3D};
+use sparse::{SparseBuffer, SparseImage, Tile};
+use test::init;
+
+const TILE_SIZE: u64 = 64 * 1024;
+
+fn tile(mip: u32, x: u32, y: u32) -> Tile {
+    Tile {
+        layer: 0,
+        mip,
+        z: 0,
+        y,
+        x,
+    }
+}
+
+fn image() -> SparseImage<MockMemory> {
+    SparseImage::new(
+        Extent3D {
+            width: 1000,
+            height: 512,
+            depth: 1,
+        },
+        3,
+        1,
+        Extent3D {
+            width: 128,
+            height: 128,
+            depth: 1,
+        },
+        TILE_SIZE,
+    )
+}
+
+#[test]
+fn sparse_image_test() {
+    let mut heaps = init(TILE_SIZE);
+    let ref device = MockDevice::new();
+    let mut image = image();
+
+    assert_eq!(
+        image.tiles(0),
+        Extent3D {
+            width: 8,
+            height: 4,
+            depth: 1,
+        }
+    );
+    assert_eq!(
+        image.tiles(2),
+        Extent3D {
+            width: 2,
+            height: 1,
+            depth: 1,
+        }
+    );
+    assert!(image.contains(tile(2, 1, 0)));
+    assert!(!image.contains(tile(2, 2, 0)));
+
+    image
+        .make_resident(
+            device,
+            &mut heaps,
+            1,
+            Data,
+            vec![tile(0, 0, 0), tile(0, 7, 0), tile(2, 1, 0), tile(0, 0, 0)],
+        ).unwrap();
+    assert_eq!(image.resident_count(), 3);
+    {
+        let operations = image.operations();
+        assert_eq!(operations.len(), 3);
+        for operation in &operations {
+            let (_, offset) = operation.memory.unwrap();
+            assert_eq!(offset % TILE_SIZE, 0);
+        }
+
+        // Tiles on the edge are clipped.
+        assert_eq!(operations[1].offset, Offset3D { x: 896, y: 0, z: 0 });
+        assert_eq!(
+            operations[1].extent,
+            Extent3D {
+                width: 104,
+                height: 128,
+                depth: 1,
+            }
+        );
+        assert_eq!(operations[2].mip, 2);
+        assert_eq!(
+            operations[2].extent,
+            Extent3D {
+                width: 122,
+                height: 128,
+                depth: 1,
+            }
+        );
+    }
+    assert!(image.submitted().is_empty());
+    assert!(image.operations().is_empty());
+
+    // Resident tiles are not bound again.
+    image
+        .make_resident(device, &mut heaps, 1, Data, Some(tile(0, 7, 0)))
+        .unwrap();
+    assert!(image.operations().is_empty());
+
+    // Evicted tiles are unbound and their memory is released after submission.
+    image.evict(vec![tile(0, 7, 0), tile(1, 0, 0)]);
+    assert!(!image.is_resident(tile(0, 7, 0)));
+    {
+        let operations = image.operations();
+        assert_eq!(operations.len(), 1);
+        assert_eq!(operations[0].mip, 0);
+        assert!(operations[0].memory.is_none());
+    }
+    let released = image.submitted();
+    assert_eq!(released.len(), 1);
+    for block in released {
+        heaps.free(device, block);
+    }
+
+    // Tile bound and evicted within single batch produces no operations.
+    image
+        .make_resident(device, &mut heaps, 1, Data, Some(tile(1, 1, 1)))
+        .unwrap();
+    image.evict(Some(tile(1, 1, 1)));
+    assert!(image.operations().is_empty());
+    for block in image.submitted() {
+        heaps.free(device, block);
+    }
+
+    assert_eq!(
+        image.resident().collect::<Vec<_>>(),
+        vec![tile(0, 0, 0), tile(2, 1, 0)]
+    );
+
+    image.dispose(device, &mut heaps);
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
+
+#[test]
+fn sparse_image_fault_test() {
+    let mut heaps = init(TILE_SIZE);
+    let ref device = MockDevice::new();
+    let mut image = image();
+
+    // Leave single tile of the first chunk free.
+    let tiles = (0..8)
+        .map(|x| tile(0, x, 0))
+        .chain((0..7).map(|x| tile(0, x, 1)));
+    image
+        .make_resident(device, &mut heaps, 1, Data, tiles)
+        .unwrap();
+    assert_eq!(device.allocated(), 1);
+    image.submitted();
+
+    device.fail_all(Operation::Allocate, Fault::OutOfDeviceMemory);
+    match image.make_resident(
+        device,
+        &mut heaps,
+        1,
+        Data,
+        vec![tile(0, 0, 2), tile(0, 1, 2)],
+    ) {
+        Err(MemoryError::AllocationError(AllocationError::OutOfMemoryError(
+            OutOfMemoryError::OutOfDeviceMemory,
+        ))) => {}
+        other => panic!("Unexpected result {:?}", other),
+    }
+    assert_eq!(image.resident_count(), 15);
+    assert!(!image.is_resident(tile(0, 0, 2)));
+    assert!(image.operations().is_empty());
+
+    device.clear_faults();
+    image.dispose(device, &mut heaps);
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
+
+#[test]
+fn sparse_buffer_test() {
+    let mut heaps = init(TILE_SIZE);
+    let ref device = MockDevice::new();
+    let mut buffer = SparseBuffer::new(TILE_SIZE * 4 + 100, TILE_SIZE);
+    assert_eq!(buffer.tiles(), 5);
+
+    buffer
+        .make_resident(device, &mut heaps, 1, Data, vec![4, 1])
+        .unwrap();
+    {
+        let operations = buffer.operations();
+        assert_eq!(operations.len(), 2);
+        assert_eq!(operations[0].offset, TILE_SIZE);
+        assert_eq!(operations[0].size, TILE_SIZE);
+        assert_eq!(operations[1].offset, TILE_SIZE * 4);
+        assert_eq!(operations[1].size, 100);
+        let (memory, _) = operations[0].memory.unwrap();
+        assert_eq!(memory.memory_type(), 0);
+    }
+    buffer.submitted();
+
+    buffer.evict(Some(1));
+    assert_eq!(buffer.operations()[0].offset, TILE_SIZE);
+    for block in buffer.submitted() {
+        let range = block.range();
+        assert_eq!(range.end - range.start, TILE_SIZE);
+        heaps.free(device, block);
+    }
+    assert_eq!(buffer.resident().collect::<Vec<_>>(), vec![4]);
+
+    buffer.dispose(device, &mut heaps);
+    heaps.dispose(device);
+    assert_eq!(device.allocated(), 0);
+}
@@ -0,0 +1,109 @@
+use image::{
+    CreateInfo, Extent3D, Format, ImageAspectFlags, ImageCreateFlags, ImageTiling, Kind,
+    SampleCountFlags, SubresourceLayers, SubresourceRange, UsageFlags,
+};
+use SharingMode;
+
+fn extent(width: u32, height: u32, depth: u32) -> Extent3D {
+    Extent3D {
+        width,
+        height,
+        depth,
+    }
+}
+
+fn image_info(format: Format, extent: Extent3D, mips: u32) -> CreateInfo {
+    CreateInfo {
+        kind: Kind::D2,
+        format,
+        extent,
+        mips,
+        array: 6,
+        samples: SampleCountFlags::SAMPLE_COUNT_1,
+        tiling: ImageTiling::Optimal,
+        usage: UsageFlags::SAMPLED,
+        sharing: SharingMode::Exclusive,
+        flags: ImageCreateFlags::empty(),
+    }
+}
+
+#[test]
+fn mip_extent_test() {
+    let base = extent(256, 100, 7);
+    assert_eq!(base.mip_level(0), base);
+    assert_eq!(base.mip_level(1), extent(128, 50, 3));
+    assert_eq!(base.mip_level(2), extent(64, 25, 1));
+    assert_eq!(base.mip_level(3), extent(32, 12, 1));
+    assert_eq!(base.mip_level(8), extent(1, 1, 1));
+    assert_eq!(base.mip_level(9), extent(1, 1, 1));
+    assert_eq!(base.mip_level(40), extent(1, 1, 1));
+}
+
+#[test]
+fn subresource_size_test() {
+    let info = image_info(Format::R8G8B8A8_UNORM, extent(256, 100, 1), 9);
+    assert_eq!(info.subresource_size(0), Some(256 * 100 * 4));
+    assert_eq!(info.subresource_size(3), Some(32 * 12 * 4));
+    assert_eq!(info.subresource_size(8), Some(4));
+
+    // Sizes are rounded up to whole blocks.
+    let info = image_info(Format::BC1_RGBA_UNORM_BLOCK, extent(256, 100, 1), 9);
+    assert_eq!(info.subresource_size(0), Some(64 * 25 * 8));
+    assert_eq!(info.subresource_size(3), Some(8 * 3 * 8));
+    assert_eq!(info.subresource_size(8), Some(8));
+
+    let info = image_info(Format::ASTC_10x8_SRGB_BLOCK, extent(100, 100, 1), 1);
+    assert_eq!(info.mip_extent(0), extent(100, 100, 1));
+    assert_eq!(info.subresource_size(0), Some(10 * 13 * 16));
+
+    assert_eq!(
+        Format::R32G32B32A32_SFLOAT.packed_size(extent(4, 4, 4)),
+        Some(4 * 4 * 4 * 16)
+    );
+
+    // Depth and stencil aspects are copied separately.
+    assert_eq!(
+        Format::D32_SFLOAT.packed_size(extent(4, 4, 1)),
+        Some(4 * 4 * 4)
+    );
+    assert_eq!(Format::D24_UNORM_S8_UINT.packed_size(extent(4, 4, 1)), None);
+
+    // Planes are copied separately.
+    assert_eq!(
+        Format::G8B8G8R8_422_UNORM.packed_size(extent(4, 4, 1)),
+        Some(2 * 4 * 4)
+    );
+    assert_eq!(
+        Format::G8_B8R8_2PLANE_420_UNORM.packed_size(extent(4, 4, 1)),
+        None
+    );
+    let info = image_info(Format::G8_B8_R8_3PLANE_444_UNORM, extent(64, 64, 1), 1);
+    assert_eq!(info.subresource_size(0), None);
+}
+
+#[test]
+fn subresource_range_test() {
+    let info = image_info(Format::D24_UNORM_S8_UINT, extent(64, 64, 1), 7);
+    assert_eq!(
+        info.whole_range(),
+        SubresourceRange {
+            aspects: ImageAspectFlags::DEPTH | ImageAspectFlags::STENCIL,
+            levels: 0..7,
+            layers: 0..6,
+        }
+    );
+
+    let layers = SubresourceLayers {
+        aspects: ImageAspectFlags::COLOR,
+        level: 3,
+        layers: 2..5,
+    };
+    assert_eq!(
+        SubresourceRange::from(layers),
+        SubresourceRange {
+            aspects: ImageAspectFlags::COLOR,
+            levels: 3..4,
+            layers: 2..5,
+        }
+    );
+}
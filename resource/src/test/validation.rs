@@ -0,0 +1,354 @@
+use memory::{mock::MockMemory, usage::Data};
+
+use buffer;
+use image::{
+    CreateInfo, Extent3D, Format, ImageAspectFlags, ImageCreateFlags, ImageTiling, Kind,
+    SampleCountFlags, SubresourceRange, Swizzle, UsageFlags, ViewCreateInfo, ViewKind,
+};
+use test::{
+    device::{Object, TestDevice},
+    init,
+};
+use validation::{
+    validate_buffer_view, validate_image, validate_image_view, DeviceLimits, FormatFeatureFlags,
+    FormatProperties,
+};
+use ResourceError;
+use Resources;
+use SharingMode;
+use ValidationError;
+
+fn limits() -> DeviceLimits {
+    DeviceLimits {
+        max_image_dimension_1d: 4096,
+        max_image_dimension_2d: 4096,
+        max_image_dimension_3d: 256,
+        max_image_dimension_cube: 1024,
+        max_image_array_layers: 256,
+        max_texel_buffer_elements: 65536,
+        sample_counts: SampleCountFlags::SAMPLE_COUNT_1 | SampleCountFlags::SAMPLE_COUNT_4,
+        buffer_image_granularity: 1024,
+    }
+}
+
+/// Properties of color format that can't be used for storage.
+fn color_properties() -> FormatProperties {
+    FormatProperties {
+        linear_tiling_features: FormatFeatureFlags::SAMPLED_IMAGE,
+        optimal_tiling_features: FormatFeatureFlags::SAMPLED_IMAGE
+            | FormatFeatureFlags::COLOR_ATTACHMENT
+            | FormatFeatureFlags::BLIT_SRC,
+        buffer_features: FormatFeatureFlags::UNIFORM_TEXEL_BUFFER,
+    }
+}
+
+fn image_info() -> CreateInfo {
+    CreateInfo {
+        kind: Kind::D2,
+        format: Format::R8G8B8A8_SRGB,
+        extent: Extent3D {
+            width: 512,
+            height: 512,
+            depth: 1,
+        },
+        mips: 10,
+        array: 1,
+        samples: SampleCountFlags::SAMPLE_COUNT_1,
+        tiling: ImageTiling::Optimal,
+        usage: UsageFlags::SAMPLED | UsageFlags::TRANSFER_DST,
+        sharing: SharingMode::Exclusive,
+        flags: ImageCreateFlags::empty(),
+    }
+}
+
+fn check(info: CreateInfo) -> Result<(), ValidationError> {
+    validate_image(&info, &limits(), &color_properties())
+}
+
+#[test]
+fn image_limits_test() {
+    assert!(check(image_info()).is_ok());
+
+    let mut info = image_info();
+    info.extent.depth = 2;
+    match check(info) {
+        Err(ValidationError::InvalidExtent(_)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    let mut info = image_info();
+    info.extent.width = 8192;
+    match check(info) {
+        Err(ValidationError::ExtentTooLarge(_)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    let mut info = image_info();
+    info.mips = 11;
+    match check(info) {
+        Err(ValidationError::TooManyMips(11)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    let mut info = image_info();
+    info.kind = Kind::D3;
+    info.extent = Extent3D {
+        width: 64,
+        height: 64,
+        depth: 4,
+    };
+    info.mips = 1;
+    info.array = 2;
+    match check(info) {
+        Err(ValidationError::TooManyLayers(2)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn image_cube_test() {
+    let mut info = image_info();
+    info.flags = ImageCreateFlags::IMAGE_CREATE_CUBE_COMPATIBLE;
+    info.array = 6;
+    assert!(check(info).is_ok());
+
+    let mut non_square = info;
+    non_square.extent.height = 256;
+    match check(non_square) {
+        Err(ValidationError::CubeIncompatible) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    let mut few_layers = info;
+    few_layers.array = 4;
+    match check(few_layers) {
+        Err(ValidationError::CubeIncompatible) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    // Cube limit is lower than 2D limit.
+    let mut large = info;
+    large.extent.width = 2048;
+    large.extent.height = 2048;
+    match check(large) {
+        Err(ValidationError::ExtentTooLarge(_)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn image_samples_test() {
+    let mut info = image_info();
+    info.mips = 1;
+    info.samples = SampleCountFlags::SAMPLE_COUNT_4;
+    assert!(check(info).is_ok());
+
+    let mut unsupported = info;
+    unsupported.samples = SampleCountFlags::SAMPLE_COUNT_8;
+    match check(unsupported) {
+        Err(ValidationError::UnsupportedSamples(SampleCountFlags::SAMPLE_COUNT_8)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    let mut volume = info;
+    volume.kind = Kind::D3;
+    volume.extent.width = 64;
+    volume.extent.height = 64;
+    volume.extent.depth = 64;
+    match check(volume) {
+        Err(ValidationError::InvalidMultisampling) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    let mut mipmapped = info;
+    mipmapped.mips = 2;
+    match check(mipmapped) {
+        Err(ValidationError::InvalidMultisampling) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn image_format_test() {
+    let mut info = image_info();
+    info.usage |= UsageFlags::STORAGE | UsageFlags::COLOR_ATTACHMENT;
+    match check(info) {
+        Err(ValidationError::UnsupportedUsage(UsageFlags::STORAGE)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    let mut info = image_info();
+    info.tiling = ImageTiling::Linear;
+    info.mips = 1;
+    info.usage |= UsageFlags::COLOR_ATTACHMENT;
+    match check(info) {
+        Err(ValidationError::UnsupportedUsage(UsageFlags::COLOR_ATTACHMENT)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    let info = image_info();
+    let unsupported = FormatProperties {
+        linear_tiling_features: FormatFeatureFlags::empty(),
+        optimal_tiling_features: FormatFeatureFlags::empty(),
+        buffer_features: FormatFeatureFlags::empty(),
+    };
+    match validate_image(&info, &limits(), &unsupported) {
+        Err(ValidationError::UnsupportedFormat(Format::R8G8B8A8_SRGB)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn buffer_view_test() {
+    let buffer = buffer::CreateInfo {
+        size: 1024 * 1024,
+        usage: buffer::UsageFlags::UNIFORM_TEXEL_BUFFER,
+        sharing: SharingMode::Exclusive,
+    };
+    let view = buffer::ViewCreateInfo {
+        format: Format::R32_SFLOAT,
+        range: 0..1024,
+    };
+    let check = |buffer: &buffer::CreateInfo, view: &buffer::ViewCreateInfo| {
+        validate_buffer_view(buffer, view, &limits(), &color_properties())
+    };
+    assert!(check(&buffer, &view).is_ok());
+
+    let mut out_of_bounds = view.clone();
+    out_of_bounds.range = 1024..2 * 1024 * 1024;
+    match check(&buffer, &out_of_bounds) {
+        Err(ValidationError::OutOfBounds) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    let mut storage = buffer;
+    storage.usage |= buffer::UsageFlags::STORAGE_TEXEL_BUFFER;
+    match check(&storage, &view) {
+        Err(ValidationError::UnsupportedBufferUsage(buffer::UsageFlags::STORAGE_TEXEL_BUFFER)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    let mut large = view.clone();
+    large.range = 0..1024 * 1024;
+    match check(&buffer, &large) {
+        Err(ValidationError::TooManyTexels(262144)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn image_view_test() {
+    let image = image_info();
+    let view = ViewCreateInfo {
+        kind: ViewKind::D2,
+        format: Format::R8G8B8A8_SRGB,
+        swizzle: Swizzle::IDENTITY,
+        range: SubresourceRange {
+            aspects: ImageAspectFlags::COLOR,
+            levels: 0..10,
+            layers: 0..1,
+        },
+    };
+    let check = |image: &CreateInfo, view: &ViewCreateInfo| {
+        validate_image_view(image, view, &color_properties())
+    };
+    assert!(check(&image, &view).is_ok());
+
+    let mut out_of_bounds = view.clone();
+    out_of_bounds.range.levels = 5..11;
+    match check(&image, &out_of_bounds) {
+        Err(ValidationError::OutOfBounds) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    let mut depth = view.clone();
+    depth.range.aspects = ImageAspectFlags::DEPTH;
+    match check(&image, &depth) {
+        Err(ValidationError::InvalidAspects(ImageAspectFlags::DEPTH)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    let mut cube_image = image;
+    cube_image.array = 6;
+    let mut cube = view.clone();
+    cube.kind = ViewKind::Cube;
+    cube.range.layers = 0..6;
+    match check(&cube_image, &cube) {
+        Err(ValidationError::IncompatibleViewKind(ViewKind::Cube)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+    cube_image.flags = ImageCreateFlags::IMAGE_CREATE_CUBE_COMPATIBLE;
+    assert!(check(&cube_image, &cube).is_ok());
+
+    // Image format can be reinterpreted only by mutable-format images.
+    let mut unorm = view.clone();
+    unorm.format = Format::R8G8B8A8_UNORM;
+    match check(&image, &unorm) {
+        Err(ValidationError::IncompatibleFormat(Format::R8G8B8A8_UNORM)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+    let mut mutable = image;
+    mutable.flags = ImageCreateFlags::IMAGE_CREATE_MUTABLE_FORMAT;
+    assert!(check(&mutable, &unorm).is_ok());
+
+    let mut storage = image;
+    storage.usage |= UsageFlags::STORAGE;
+    match check(&storage, &view) {
+        Err(ValidationError::UnsupportedUsage(UsageFlags::STORAGE)) => {}
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn resources_validation_test() {
+    let mut heaps = init(256);
+    let ref device =
+        TestDevice::with_validation(limits(), vec![(Format::R8G8B8A8_SRGB, color_properties())]);
+    let mut resources = Resources::<MockMemory, u64, u64, u64, u64, u64>::new();
+
+    let mut info = image_info();
+    info.usage |= UsageFlags::STORAGE;
+    match resources.create_image(device, &mut heaps, info, 1, Data) {
+        Err(ResourceError::ValidationError(ValidationError::UnsupportedUsage(_))) => {}
+        result => panic!("Unexpected result {:?}", result.map(|_| ())),
+    }
+
+    let mut info = image_info();
+    info.format = Format::R16G16B16A16_SFLOAT;
+    match resources.create_image(device, &mut heaps, info, 1, Data) {
+        Err(ResourceError::ValidationError(ValidationError::UnsupportedFormat(_))) => {}
+        result => panic!("Unexpected result {:?}", result.map(|_| ())),
+    }
+
+    // Invalid images never reach the device.
+    assert_eq!(device.live(Object::Image), 0);
+
+    let image = resources
+        .create_image(device, &mut heaps, image_info(), 1, Data)
+        .unwrap();
+    assert_eq!(device.live(Object::Image), 1);
+
+    let mut view = ViewCreateInfo {
+        kind: ViewKind::D2Array,
+        format: Format::R8G8B8A8_SRGB,
+        swizzle: Swizzle::IDENTITY,
+        range: SubresourceRange {
+            aspects: ImageAspectFlags::COLOR,
+            levels: 0..1,
+            layers: 0..2,
+        },
+    };
+    match resources.create_image_view(device, &image, view.clone()) {
+        Err(ResourceError::ValidationError(ValidationError::OutOfBounds)) => {}
+        result => panic!("Unexpected result {:?}", result.map(|_| ())),
+    }
+    view.range.layers = 0..1;
+    let view = resources.create_image_view(device, &image, view).unwrap();
+
+    drop(view);
+    drop(image);
+    unsafe {
+        resources.cleanup(device, &mut heaps);
+    }
+    heaps.dispose(device);
+}
@@ -0,0 +1,329 @@
+//! Validation of resource creation-info against device limits and format features.
+//! Catches invalid parameters before they reach the backend.
+
+use buffer;
+use error::ValidationError;
+use image;
+
+/// Device limits relevant for resource creation.
+/// See Vulkan docs for details:
+/// <https://www.khronos.org/registry/vulkan/specs/1.1-extensions/man/html/VkPhysicalDeviceLimits.html>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeviceLimits {
+    /// Maximum width of one-dimensional image.
+    pub max_image_dimension_1d: u32,
+
+    /// Maximum width and height of two-dimensional image.
+    pub max_image_dimension_2d: u32,
+
+    /// Maximum width, height and depth of three-dimensional image.
+    pub max_image_dimension_3d: u32,
+
+    /// Maximum width and height of cube-compatible image.
+    pub max_image_dimension_cube: u32,
+
+    /// Maximum number of image layers.
+    pub max_image_array_layers: u32,
+
+    /// Maximum number of texels addressable through buffer view.
+    pub max_texel_buffer_elements: u32,
+
+    /// Sample counts supported for images.
+    pub sample_counts: image::SampleCountFlags,
+
+    /// Granularity in bytes at which linear and non-linear resources
+    /// bound to the same memory object must be placed to not alias.
+    pub buffer_image_granularity: u64,
+}
+
+bitflags! {
+    /// Bitmask specifying features supported by a format.
+    /// See Vulkan docs for details:
+    /// <https://www.khronos.org/registry/vulkan/specs/1.1-extensions/man/html/VkFormatFeatureFlagBits.html>
+    #[repr(transparent)]
+    pub struct FormatFeatureFlags: u32 {
+        /// Specifies that image view can be sampled from.
+        const SAMPLED_IMAGE = 0x00000001;
+        /// Specifies that image view can be used as storage image.
+        const STORAGE_IMAGE = 0x00000002;
+        /// Specifies that image view can be used as storage image that supports atomic operations.
+        const STORAGE_IMAGE_ATOMIC = 0x00000004;
+        /// Specifies that buffer view can be used as uniform texel buffer.
+        const UNIFORM_TEXEL_BUFFER = 0x00000008;
+        /// Specifies that buffer view can be used as storage texel buffer.
+        const STORAGE_TEXEL_BUFFER = 0x00000010;
+        /// Specifies that buffer view can be used as storage texel buffer that supports atomic operations.
+        const STORAGE_TEXEL_BUFFER_ATOMIC = 0x00000020;
+        /// Specifies that format can be used as vertex attribute format.
+        const VERTEX_BUFFER = 0x00000040;
+        /// Specifies that image view can be used as color and input attachment.
+        const COLOR_ATTACHMENT = 0x00000080;
+        /// Specifies that image view can be used as color attachment that supports blending.
+        const COLOR_ATTACHMENT_BLEND = 0x00000100;
+        /// Specifies that image view can be used as depth-stencil and input attachment.
+        const DEPTH_STENCIL_ATTACHMENT = 0x00000200;
+        /// Specifies that image can be used as source of blit command.
+        const BLIT_SRC = 0x00000400;
+        /// Specifies that image can be used as destination of blit command.
+        const BLIT_DST = 0x00000800;
+        /// Specifies that image view can be sampled with linear filtering.
+        const SAMPLED_IMAGE_FILTER_LINEAR = 0x00001000;
+        /// Specifies that image can be used as source of transfer command.
+        const TRANSFER_SRC = 0x00004000;
+        /// Specifies that image can be used as destination of transfer command.
+        const TRANSFER_DST = 0x00008000;
+    }
+}
+
+/// Features supported by a format.
+/// See Vulkan docs for details:
+/// <https://www.khronos.org/registry/vulkan/specs/1.1-extensions/man/html/VkFormatProperties.html>
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FormatProperties {
+    /// Features supported by images with linear tiling.
+    pub linear_tiling_features: FormatFeatureFlags,
+
+    /// Features supported by images with optimal tiling.
+    pub optimal_tiling_features: FormatFeatureFlags,
+
+    /// Features supported by buffers.
+    pub buffer_features: FormatFeatureFlags,
+}
+
+/// Check image creation-info against device limits and properties of the image format.
+pub fn validate_image(
+    info: &image::CreateInfo,
+    limits: &DeviceLimits,
+    properties: &FormatProperties,
+) -> Result<(), ValidationError> {
+    let extent = info.extent;
+    let cube = info
+        .flags
+        .contains(image::ImageCreateFlags::IMAGE_CREATE_CUBE_COMPATIBLE);
+
+    let fits_kind = match info.kind {
+        image::Kind::D1 => extent.height == 1 && extent.depth == 1,
+        image::Kind::D2 => extent.depth == 1,
+        image::Kind::D3 => true,
+    };
+    if !fits_kind || extent.width == 0 || extent.height == 0 || extent.depth == 0 {
+        return Err(ValidationError::InvalidExtent(extent));
+    }
+
+    let max_dimension = match info.kind {
+        image::Kind::D1 => limits.max_image_dimension_1d,
+        image::Kind::D2 if cube => limits.max_image_dimension_cube,
+        image::Kind::D2 => limits.max_image_dimension_2d,
+        image::Kind::D3 => limits.max_image_dimension_3d,
+    };
+    if extent.width > max_dimension || extent.height > max_dimension || extent.depth > max_dimension
+    {
+        return Err(ValidationError::ExtentTooLarge(extent));
+    }
+
+    let max_layers = match info.kind {
+        image::Kind::D3 => 1,
+        _ => limits.max_image_array_layers,
+    };
+    if info.array == 0 || info.array > max_layers {
+        return Err(ValidationError::TooManyLayers(info.array));
+    }
+
+    let largest = extent.width.max(extent.height).max(extent.depth);
+    let max_mips = 32 - largest.leading_zeros();
+    if info.mips == 0 || info.mips > max_mips {
+        return Err(ValidationError::TooManyMips(info.mips));
+    }
+
+    if cube && (info.kind != image::Kind::D2 || extent.width != extent.height || info.array < 6) {
+        return Err(ValidationError::CubeIncompatible);
+    }
+
+    if info.samples.bits().count_ones() != 1 || !limits.sample_counts.contains(info.samples) {
+        return Err(ValidationError::UnsupportedSamples(info.samples));
+    }
+
+    if info.samples != image::SampleCountFlags::SAMPLE_COUNT_1
+        && (info.kind != image::Kind::D2
+            || info.tiling != image::ImageTiling::Optimal
+            || info.mips != 1
+            || cube)
+    {
+        return Err(ValidationError::InvalidMultisampling);
+    }
+
+    let features = match info.tiling {
+        image::ImageTiling::Optimal => properties.optimal_tiling_features,
+        image::ImageTiling::Linear => properties.linear_tiling_features,
+    };
+    if info.format == image::Format::UNDEFINED || features.is_empty() {
+        return Err(ValidationError::UnsupportedFormat(info.format));
+    }
+
+    // Transfer features are reported only by Vulkan 1.1 devices.
+    let usage = info.usage
+        - image::UsageFlags::TRANSFER_SRC
+        - image::UsageFlags::TRANSFER_DST
+        - image::UsageFlags::TRANSIENT_ATTACHMENT;
+    let unsupported = usage - image_usage(features);
+    if !unsupported.is_empty() {
+        return Err(ValidationError::UnsupportedUsage(unsupported));
+    }
+
+    Ok(())
+}
+
+/// Check buffer view creation-info against device limits and properties of the view format.
+/// `buffer` is creation-info of the buffer the view is created for.
+pub fn validate_buffer_view(
+    buffer: &buffer::CreateInfo,
+    info: &buffer::ViewCreateInfo,
+    limits: &DeviceLimits,
+    properties: &FormatProperties,
+) -> Result<(), ValidationError> {
+    if info.range.start > info.range.end || info.range.end > buffer.size {
+        return Err(ValidationError::OutOfBounds);
+    }
+
+    let texel_usage =
+        buffer::UsageFlags::UNIFORM_TEXEL_BUFFER | buffer::UsageFlags::STORAGE_TEXEL_BUFFER;
+    let usage = buffer.usage & texel_usage;
+    if usage.is_empty() {
+        return Err(ValidationError::UnsupportedBufferUsage(texel_usage));
+    }
+
+    if info.format.is_compressed() || info.format.block_size() == 0 {
+        return Err(ValidationError::UnsupportedFormat(info.format));
+    }
+
+    let mut supported = buffer::UsageFlags::empty();
+    if properties
+        .buffer_features
+        .contains(FormatFeatureFlags::UNIFORM_TEXEL_BUFFER)
+    {
+        supported |= buffer::UsageFlags::UNIFORM_TEXEL_BUFFER;
+    }
+    if properties
+        .buffer_features
+        .contains(FormatFeatureFlags::STORAGE_TEXEL_BUFFER)
+    {
+        supported |= buffer::UsageFlags::STORAGE_TEXEL_BUFFER;
+    }
+    if !supported.contains(usage) {
+        return Err(ValidationError::UnsupportedBufferUsage(usage - supported));
+    }
+
+    let texels = (info.range.end - info.range.start) / info.format.block_size() as u64;
+    if texels > limits.max_texel_buffer_elements as u64 {
+        return Err(ValidationError::TooManyTexels(texels));
+    }
+
+    Ok(())
+}
+
+/// Check image view creation-info against the image and properties of the view format.
+/// `image` is creation-info of the image the view is created for.
+pub fn validate_image_view(
+    image: &image::CreateInfo,
+    info: &image::ViewCreateInfo,
+    properties: &FormatProperties,
+) -> Result<(), ValidationError> {
+    let range = &info.range;
+    let flat = match (image.kind, info.kind) {
+        (image::Kind::D3, image::ViewKind::D2) | (image::Kind::D3, image::ViewKind::D2Array) => {
+            true
+        }
+        _ => false,
+    };
+
+    // Layers of two-dimensional view of three-dimensional image are depth slices.
+    let max_layers = if flat {
+        image.mip_extent(range.levels.start).depth
+    } else {
+        image.array
+    };
+    if range.levels.start >= range.levels.end
+        || range.levels.end > image.mips
+        || range.layers.start >= range.layers.end
+        || range.layers.end > max_layers
+    {
+        return Err(ValidationError::OutOfBounds);
+    }
+
+    if range.aspects.is_empty() || !image.format.aspects().contains(range.aspects) {
+        return Err(ValidationError::InvalidAspects(range.aspects));
+    }
+
+    let layers = range.layers.end - range.layers.start;
+    let cube = image
+        .flags
+        .contains(image::ImageCreateFlags::IMAGE_CREATE_CUBE_COMPATIBLE);
+    let fits_kind = match (image.kind, info.kind) {
+        (image::Kind::D1, image::ViewKind::D1) => layers == 1,
+        (image::Kind::D1, image::ViewKind::D1Array) => true,
+        (image::Kind::D2, image::ViewKind::D2) => layers == 1,
+        (image::Kind::D2, image::ViewKind::D2Array) => true,
+        (image::Kind::D2, image::ViewKind::Cube) => cube && layers == 6,
+        (image::Kind::D2, image::ViewKind::CubeArray) => cube && layers % 6 == 0,
+        (image::Kind::D3, image::ViewKind::D3) => true,
+        _ if flat => {
+            image
+                .flags
+                .contains(image::ImageCreateFlags::IMAGE_CREATE_2D_ARRAY_COMPATIBLE)
+                && range.levels.end - range.levels.start == 1
+                && (info.kind == image::ViewKind::D2Array || layers == 1)
+        }
+        _ => false,
+    };
+    if !fits_kind {
+        return Err(ValidationError::IncompatibleViewKind(info.kind));
+    }
+
+    if info.format != image.format
+        && (!image
+            .flags
+            .contains(image::ImageCreateFlags::IMAGE_CREATE_MUTABLE_FORMAT)
+            || info.format.block_size() != image.format.block_size()
+            || info.format.block_extent() != image.format.block_extent())
+    {
+        return Err(ValidationError::IncompatibleFormat(info.format));
+    }
+
+    let features = match image.tiling {
+        image::ImageTiling::Optimal => properties.optimal_tiling_features,
+        image::ImageTiling::Linear => properties.linear_tiling_features,
+    };
+    if info.format == image::Format::UNDEFINED || features.is_empty() {
+        return Err(ValidationError::UnsupportedFormat(info.format));
+    }
+
+    // View format must support all usage of the image.
+    let usage = image.usage
+        - image::UsageFlags::TRANSFER_SRC
+        - image::UsageFlags::TRANSFER_DST
+        - image::UsageFlags::TRANSIENT_ATTACHMENT;
+    let unsupported = usage - image_usage(features);
+    if !unsupported.is_empty() {
+        return Err(ValidationError::UnsupportedUsage(unsupported));
+    }
+
+    Ok(())
+}
+
+/// Image usage allowed by format features.
+fn image_usage(features: FormatFeatureFlags) -> image::UsageFlags {
+    let mut usage = image::UsageFlags::empty();
+    if features.contains(FormatFeatureFlags::SAMPLED_IMAGE) {
+        usage |= image::UsageFlags::SAMPLED;
+    }
+    if features.contains(FormatFeatureFlags::STORAGE_IMAGE) {
+        usage |= image::UsageFlags::STORAGE;
+    }
+    if features.contains(FormatFeatureFlags::COLOR_ATTACHMENT) {
+        usage |= image::UsageFlags::COLOR_ATTACHMENT | image::UsageFlags::INPUT_ATTACHMENT;
+    }
+    if features.contains(FormatFeatureFlags::DEPTH_STENCIL_ATTACHMENT) {
+        usage |= image::UsageFlags::DEPTH_STENCIL_ATTACHMENT | image::UsageFlags::INPUT_ATTACHMENT;
+    }
+    usage
+}